@@ -133,7 +133,11 @@ impl AuthFlow for Flow {
                     ("client_id", self.credentials.id.clone()),
                 ])?)
                 .send()
-                .await.map_err(|e| Error::refresh(e, self.oauth.redirect.clone(), self.oauth.state.clone()))?;
+                .await.map_err(|e| match e.is_connect() || e.is_timeout() {
+                    // The token may still be valid once spotify can be reached again
+                    true => Error::from(e),
+                    false => Error::refresh(e, self.oauth.redirect.clone(), self.oauth.state.clone()),
+                })?;
 
             let body = String::from_utf8(
                 response.bytes().await.map_err(|e| Error::refresh(e, self.oauth.redirect.clone(), self.oauth.state.clone()))?
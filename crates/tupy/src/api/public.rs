@@ -958,6 +958,27 @@ pub trait PublicApi: AuthFlow {
         }
     }
 
+    /// Get the current snapshot ID of a playlist without fetching its details or items.
+    ///
+    /// # Arguments
+    /// - `id`: The [Spotify ID](https://developer.spotify.com/documentation/web-api/#spotify-uris-and-ids) of the playlist.
+    fn playlist_snapshot<I: IntoSpotifyId>(
+        &self,
+        id: I,
+    ) -> impl Future<Output = Result<String, Error>> {
+        async move {
+            let token = self.token();
+
+            let SpotifyResponse { body, .. } = request::get!("playlists/{}", id.into_spotify_id())
+                .param("fields", "snapshot_id")
+                .send(token)
+                .await?;
+
+            let result: HashMap<String, String> = pares!(&body)?;
+            Ok(result.get("snapshot_id").cloned().unwrap_or_default())
+        }
+    }
+
     /// Get full details of the items of a playlist owned by a Spotify user.
     ///
     /// # Arguments
@@ -68,6 +68,14 @@ use super::Uri;
 
 pub static SUPPORTED_ITEMS: &str = "track,episode";
 
+/// Largest playlist cover image spotify accepts, measured after base64 encoding
+pub static MAX_COVER_IMAGE_SIZE: usize = 256 * 1024;
+
+/// Size of an image once it is base64 encoded for upload
+pub fn encoded_image_size(bytes: usize) -> usize {
+    bytes.div_ceil(3) * 4
+}
+
 pub trait OptionalSpotifyId {
     fn optional_spotify_id(self) -> Option<String>;
 }
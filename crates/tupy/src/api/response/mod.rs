@@ -271,6 +271,44 @@ where
     pub fn has_prev(&self) -> bool {
         self.prev.is_some()
     }
+
+    /// Start from the page containing the item offset instead of the first page.
+    ///
+    /// Only has an effect before the first page is fetched.
+    pub fn start_at(&mut self, offset: usize) {
+        if self.current.is_some() || self.page_size == 0 {
+            return;
+        }
+
+        let page = offset / self.page_size;
+        if let Some(next) = self.next.as_mut() {
+            let mut url = next
+                .split('&')
+                .filter(|param| !param.starts_with("offset="))
+                .collect::<Vec<_>>()
+                .join("&");
+            url.push_str(&format!("&offset={}", page * self.page_size));
+            *next = url;
+            self.offset = page as isize - 1;
+        }
+    }
+
+    /// Go back to before the first page so the next call to `next` fetches the first page again.
+    pub fn restart(&mut self) {
+        let Some(url) = self.current.take().or_else(|| self.next.take()) else {
+            return;
+        };
+
+        let mut url = url
+            .split('&')
+            .filter(|param| !param.starts_with("offset="))
+            .collect::<Vec<_>>()
+            .join("&");
+        url.push_str("&offset=0");
+        self.next = Some(url);
+        self.prev = None;
+        self.offset = -1;
+    }
 }
 
 impl<R, P: Deserialize<'static>, F, const N: usize> Pagination for Paginated<R, P, F, N>
@@ -188,8 +188,8 @@ pub struct PlayHistory {
     /// The date and time the track was played.
     #[serde(deserialize_with = "deserialize_datetime")]
     pub played_at: DateTime<Local>,
-    /// The context the track was played from.
-    pub context: Context,
+    /// The context the track was played from. Can be null.
+    pub context: Option<Context>,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
@@ -92,7 +92,7 @@ pub struct Playlist {
     pub public: Option<bool>,
     /// The version identifier for the current playlist. Can be supplied in other requests to target a specific playlist version
     #[serde(rename = "snapshot_id")]
-    snapshot: String,
+    pub snapshot: String,
     ///The Spotify URI for the playlist.
     pub uri: String,
     /// The total number of items in the playlist.
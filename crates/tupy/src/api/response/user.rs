@@ -97,15 +97,15 @@ where
 
     fn page(&self) -> usize {
         if self.offset == 0 {
-            0
+            1
         } else {
-            (self.offset as f64 / self.limit as f64).ceil() as usize
+            (self.offset as f64 / self.limit as f64) as usize + 1
         }
     }
 
     fn max_page(&self) -> usize {
         if self.total == 0 {
-            0
+            1
         } else {
             (self.total as f64 / self.limit as f64).ceil() as usize
         }
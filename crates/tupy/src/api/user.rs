@@ -909,6 +909,9 @@ pub trait UserApi: AuthFlow {
     /// # Arguments
     /// - `id`: The [Spotify ID](https://developer.spotify.com/documentation/web-api/#spotify-uris-and-ids) of the playlist.
     /// - `action`: The action to perform on the playlist.
+    /// - `snapshot`: The playlist's snapshot ID against which to make the changes.
+    ///
+    /// Returns the snapshot ID of the playlist after the change.
     ///
     /// # Scopes
     /// - `playlist-modify-public`: Manage your public playlists.
@@ -917,7 +920,8 @@ pub trait UserApi: AuthFlow {
         &self,
         id: I,
         action: PlaylistAction,
-    ) -> impl Future<Output = Result<(), Error>> {
+        snapshot: Option<String>,
+    ) -> impl Future<Output = Result<String, Error>> {
         async move {
             validate_scope(
                 self.scopes(),
@@ -927,12 +931,22 @@ pub trait UserApi: AuthFlow {
                 ],
             )?;
 
-            request::put!("playlists/{}/tracks", id.into_spotify_id())
-                .body(serde_json::to_string(&action)?)
-                .send(self.token())
-                .await?;
+            let mut body = serde_json::to_value(&action)?;
+            if let Some(snapshot) = snapshot {
+                body["snapshot_id"] = snapshot.into();
+            }
 
-            Ok(())
+            let SpotifyResponse { body, .. } =
+                request::put!("playlists/{}/tracks", id.into_spotify_id())
+                    .body(serde_json::to_string(&body)?)
+                    .send(self.token())
+                    .await?;
+
+            let result: HashMap<String, String> = pares!(&body)?;
+            result
+                .get("snapshot_id")
+                .cloned()
+                .ok_or_else(|| Error::custom("Response is missing the snapshot id"))
         }
     }
 
@@ -984,34 +998,47 @@ pub trait UserApi: AuthFlow {
                     .await?;
 
             let result: HashMap<String, String> = pares!(&body)?;
-            Ok(result.get("snapshot_id").unwrap().to_owned())
+            result
+                .get("snapshot_id")
+                .cloned()
+                .ok_or_else(|| Error::custom("Response is missing the snapshot id"))
         }
     }
 
-    ///
+    /// Remove one or more items from a user's playlist. Every occurrence of an item is removed.
     ///
     /// # Arguments
     /// - `id`: The [Spotify ID](https://developer.spotify.com/documentation/web-api/#spotify-uris-and-ids) of the playlist.
-    /// - `uris`: A list of the [Spotify IDs](https://developer.spotify.com/documentation/web-api/#spotify-uris-and-ids) of the items to add.
-    /// - `at`: The position to insert the items, a zero-based index. For example, to insert the items in the first position: `at=0`. If omitted, the items will be appended to the playlist.
+    /// - `uris`: A list of the [Spotify URIs](https://developer.spotify.com/documentation/web-api/#spotify-uris-and-ids) of the items to remove.
+    /// - `snapshot`: The playlist's snapshot ID against which to make the changes.
+    ///
+    /// Returns the snapshot ID of the playlist after the change.
     ///
     /// # Scopes
     /// - `playlist-modify-public`: Manage your public playlists.
     /// - `playlist-modify-private`: Manage your private playlists.
-    fn remove_items<I, U>(&self, id: I, uris: U) -> impl Future<Output = Result<String, Error>>
+    fn remove_items<I, U>(
+        &self,
+        id: I,
+        uris: U,
+        snapshot: Option<String>,
+    ) -> impl Future<Output = Result<String, Error>>
     where
         I: IntoSpotifyId,
         U: IntoIterator<Item = Uri>,
     {
-        let mut body: HashMap<&str, Vec<UriWrapper>> = HashMap::new();
-        body.insert(
-            "tracks",
-            uris.into_iter()
-                .map(|u| UriWrapper(u))
-                .collect::<Vec<UriWrapper>>(),
-        );
+        let tracks = uris
+            .into_iter()
+            .map(|u| serde_json::to_value(UriWrapper(u)))
+            .collect::<Result<Vec<serde_json::Value>, _>>();
 
         async move {
+            let mut body: HashMap<&str, serde_json::Value> = HashMap::new();
+            body.insert("tracks", tracks?.into());
+            if let Some(snapshot) = snapshot {
+                body.insert("snapshot_id", snapshot.into());
+            }
+
             validate_scope(
                 self.scopes(),
                 [
@@ -1027,7 +1054,10 @@ pub trait UserApi: AuthFlow {
                     .await?;
 
             let result: HashMap<String, String> = pares!(&body)?;
-            Ok(result.get("snapshot_id").unwrap().to_owned())
+            result
+                .get("snapshot_id")
+                .cloned()
+                .ok_or_else(|| Error::custom("Response is missing the snapshot id"))
         }
     }
 
@@ -1124,7 +1154,7 @@ pub trait UserApi: AuthFlow {
     ) -> impl Future<Output = Result<(), Error>> {
         let image = base64::engine::general_purpose::STANDARD.encode(image);
         async move {
-            if image.bytes().len() > (256 * 1024) {
+            if image.len() > request::MAX_COVER_IMAGE_SIZE {
                 return Err(Error::InvalidArgument(
                     "image",
                     "Image size is too large. Max size is 256KB after base64 encoding".to_string(),
@@ -1141,7 +1171,8 @@ pub trait UserApi: AuthFlow {
             )?;
 
             request::put!("playlists/{}/images", id.into_spotify_id())
-                .body(serde_json::to_string(&image)?)
+                .header(reqwest::header::CONTENT_TYPE, "image/jpeg")
+                .body(image)
                 .send(self.token())
                 .await?;
 
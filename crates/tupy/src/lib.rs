@@ -4,7 +4,7 @@ use reqwest::StatusCode;
 
 pub mod api;
 
-pub use chrono::{DateTime, Local, NaiveDateTime, Duration};
+pub use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, Duration};
 
 pub type Shared<T> = Arc<T>;
 pub type Locked<T> = Mutex<T>;
@@ -53,6 +53,8 @@ impl From<StatusCode> for SpotifyErrorType {
 #[derive(Debug, Clone, PartialEq)]
 pub enum Error {
     Other(String),
+    /// Spotify couldn't be reached, for example when the network is down
+    Connection(String),
     TokenRefresh {
         message: String,
         redirect: String,
@@ -86,6 +88,7 @@ impl Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", match self {
             Error::Other(msg) => msg.clone(),
+            Error::Connection(msg) => format!("failed to connect to spotify: {msg}"),
             Error::TokenRefresh { message, .. } => format!("failed to refresh spotify auth access token: {message}"),
             Error::InvalidArgument(name, msg) => format!("invalid argument '{}': {}", name, msg),
             Error::ScopesNotGranted(scopes) => format!(
@@ -100,7 +103,11 @@ impl Display for Error {
 
 impl From<reqwest::Error> for Error {
     fn from(err: reqwest::Error) -> Self {
-        Self::custom(err)
+        if err.is_connect() || err.is_timeout() {
+            Self::Connection(err.to_string())
+        } else {
+            Self::custom(err)
+        }
     }
 }
 
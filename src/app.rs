@@ -1,36 +1,52 @@
 use std::{
-    collections::HashMap,
+    future::Future,
     io::stderr,
 };
 
 use color_eyre::{eyre::eyre, Result};
-use crossterm::event::{KeyEvent, MouseEvent};
+use crossterm::event::{KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 use futures::{FutureExt, StreamExt};
-use ratatui::{backend::CrosstermBackend, widgets::TableState, Terminal};
+use ratatui::{backend::CrosstermBackend, Terminal};
 use tokio::sync::mpsc;
 
 use tupy::{
     api::{
         flow::{AuthFlow, Credentials, Pkce},
         request::Play,
-        response::{Item, PlaybackAction, PlaybackItem, Repeat},
-        scopes, OAuth, Spotify, Uri, UserApi,
+        response::{PlaybackAction, Playlist, Repeat},
+        scopes, OAuth, PublicApi, Spotify, Uri, UserApi,
     },
-    Local,
+    Duration, SpotifyErrorType,
 };
 
 use crate::{
     errors::{install_hooks, StdError, LogError, LogErrorDefault},
+    command::{self, Command},
     key,
+    keymap::Keymap,
+    render::{self, RenderSchedule},
+    tasks::{self, spawn},
     spotify_util::listen_for_authentication_code,
     state::{
-        actions::{GoTo, IntoActions},
-        modal::{ActionState, AddToPlaylistState, ArtistsState, DevicesState},
-        playback::Playback,
-        window::{landing::Landing, queue::Queue, Pages},
-        Countdown, Modal, State, Viewport, Window,
+        actions::{GoTo, PlaylistForm, Radio},
+        modal::{ActionState, AddToPlaylistState, ArtistsState, DevicesState, FindState, PlaylistFormState, RadioState},
+        window::{landing::Landing, library::LibraryState, queue::Queue, Pages},
+        component::{Context, Handled},
+        navigation::Entry,
+        wrappers::GetUri,
+        connection::{self, Connection},
+        enqueue,
+        local_queue,
+        library_index,
+        local_library,
+        mutation,
+        undo,
+        playback::PlaybackState,
+        session::{Session, SessionEntry},
+        Click, Loading, Modal, Motion, State, Window,
     },
     tui,
+    ui::playback::{progress_area, ProgressBar},
 };
 
 static FPS: usize = 24;
@@ -52,6 +68,8 @@ pub enum Event {
 
     // Playback
     Toggle,
+    /// Transfer playback to the device with the id
+    TransferPlayback(String),
     Next,
     Previous,
     Play(Play),
@@ -59,6 +77,16 @@ pub enum Event {
     ToggleShuffle,
     VolumeUp,
     VolumeDown,
+    /// Set the volume to a percentage (0-100)
+    SetVolume(u8),
+    /// Seek to a position in the current item
+    Seek(Duration),
+    /// Seek forward by the given step
+    SeekForward(Duration),
+    /// Seek backward by the given step
+    SeekBackward(Duration),
+    /// Seek to a percentage (0-100) of the current item
+    SeekPercent(u8),
 
     // Navigation
     Up,
@@ -69,32 +97,109 @@ pub enum Event {
     Tab,
     Backtab,
     Refresh,
+    /// Go back to the previous window or landing
+    Back,
+    /// Go forward to the window or landing that was gone back from
+    Forward,
+    /// Jump the selection by more than one row
+    Motion(Motion),
 
     // Open menu
-    OpenAddToPlaylist(Uri),
+    OpenAddToPlaylist(Vec<Uri>),
     OpenSelectDevice,
     OpenGoTo,
     OpenAction,
     OpenHelp,
     OpenSearch,
+    /// Fuzzy find anything saved in the local library
+    OpenFind,
+    /// Prompt for a command to run by name
+    OpenCommand,
+    /// Run a command typed in the command prompt
+    Command(String),
+    /// Open the form to create, edit or delete a playlist
+    OpenPlaylistForm(PlaylistForm),
+    /// The playlist a form changes was loaded, so the form can be opened
+    PlaylistFormLoaded(PlaylistForm, Box<Playlist>),
+    /// Open the form to tune a radio before it is started
+    OpenRadio(Radio),
+    /// A playlist's details or cover were changed, so show it again
+    PlaylistUpdated(Uri),
+    /// A playlist was deleted, so leave its landing
+    PlaylistDeleted(Uri),
+    /// Undo the last library or playlist change
+    Undo,
+    /// Stop adding a context to the queue
+    StopQueueing,
+    /// Switch between spotify's queue and the queue kept by rataify
+    ToggleLocalQueue,
+    /// Remove every item from the local queue
+    ClearLocalQueue,
 
     // Misc input events
     Key(KeyEvent),
     Mouse(MouseEvent),
 }
 
+impl Event {
+    /// Short description of what the event does, shown while a key sequence is being typed
+    pub fn label(&self) -> String {
+        match self {
+            Self::Toggle => "Play/Pause".into(),
+            Self::Next => "Next".into(),
+            Self::Previous => "Previous".into(),
+            Self::ToggleRepeat => "Toggle repeat".into(),
+            Self::ToggleShuffle => "Toggle shuffle".into(),
+            Self::VolumeUp => "Volume up".into(),
+            Self::VolumeDown => "Volume down".into(),
+            Self::SetVolume(volume) => format!("Volume {volume}%"),
+            Self::SeekForward(step) => format!("Seek forward {}s", step.num_seconds()),
+            Self::SeekBackward(step) => format!("Seek backward {}s", step.num_seconds()),
+            Self::SeekPercent(percent) => format!("Seek to {percent}%"),
+            Self::Up => "Up".into(),
+            Self::Down => "Down".into(),
+            Self::Left => "Previous page".into(),
+            Self::Right => "Next page".into(),
+            Self::Select => "Select".into(),
+            Self::Tab => "Next tab".into(),
+            Self::Backtab => "Previous tab".into(),
+            Self::Refresh => "Refresh".into(),
+            Self::Back => "Back".into(),
+            Self::Forward => "Forward".into(),
+            Self::Motion(motion) => motion.label().into(),
+            Self::OpenSelectDevice => "Devices".into(),
+            Self::OpenGoTo => "Go to".into(),
+            Self::OpenAction => "Actions".into(),
+            Self::OpenHelp => "Help".into(),
+            Self::OpenSearch => "Search".into(),
+            Self::OpenFind => "Find".into(),
+            Self::OpenCommand => "Command".into(),
+            Self::Undo => "Undo".into(),
+            Self::StopQueueing => "Stop queueing".into(),
+            Self::ToggleLocalQueue => "Toggle local queue".into(),
+            Self::ClearLocalQueue => "Clear local queue".into(),
+            Self::Close => "Close".into(),
+            Self::Quit => "Quit".into(),
+            other => format!("{other:?}"),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct App {
     pub terminal: tui::Tui,
     pub focused: bool,
     pub quit: bool,
+    pub render: RenderSchedule,
+    /// Restore the last session on start and save it on exit
+    pub session: bool,
 
     pub spotify: Spotify<Pkce>,
     pub state: State,
 }
 
 impl App {
-    pub async fn new() -> Result<Self> {
+    pub async fn new(session: bool) -> Result<Self> {
         let oauth = OAuth::from_env([
             scopes::USER_LIBRARY_READ,
             scopes::USER_LIBRARY_MODIFY,
@@ -107,6 +212,9 @@ impl App {
             scopes::PLAYLIST_READ_PRIVATE,
             scopes::PLAYLIST_MODIFY_PUBLIC,
             scopes::PLAYLIST_MODIFY_PRIVATE,
+            scopes::UGC_IMAGE_UPLOAD,
+            scopes::USER_TOP_READ,
+            scopes::USER_READ_RECENTLY_PLAYED,
         ])
         .expect("Failed to get TUPY_CLIENT_ID and TUPY_REDIRECT_URI environment variables.");
 
@@ -129,79 +237,158 @@ impl App {
             spotify.api.request_access_token(&auth_code).await?;
         }
 
-        if spotify.api.token().is_expired() {
-            spotify.api.refresh().await?;
-        }
-
-        let mut playback = spotify.api.playback_state(None).await?.map(Playback::from);
-
-        if let Some(playback) = playback.as_mut() {
-            playback.saved = match &playback.item {
-                PlaybackItem::Track(t) => spotify
-                    .api
-                    .check_saved_tracks([t.uri.clone()])
-                    .await
-                    .unwrap()[0],
-                PlaybackItem::Episode(e) => spotify
-                    .api
-                    .check_saved_episodes([e.uri.clone()])
-                    .await
-                    .unwrap()[0],
-                _ => false,
-            };
-        }
-
-        let app = Self {
+        // Nothing else is fetched until the ui is shown, so a slow or missing connection
+        // doesn't keep the app from starting
+        Ok(Self {
             terminal: Terminal::new(CrosstermBackend::new(stderr())).unwrap(),
             focused: true,
             quit: false,
+            render: RenderSchedule::default(),
+            session,
 
-            state: State::new("rataify", &spotify.api, Countdown::new(FPS * 3), playback).await?,
+            state: State::new("rataify", &spotify.api)?,
             spotify,
-        };
+        })
+    }
 
-        Ok(app)
+    /// Show the first window and fetch the data the app starts with in the background
+    async fn start(&mut self) {
+        let last = if self.session { Session::load().log_error_ok().flatten() } else { None };
+        match last {
+            Some(last) => self.restore_session(last).await,
+            None => self.state.show(Window::default()),
+        }
+        self.load_user();
+        self.sync_library();
+    }
+
+    /// Sync everything saved in the library to the local library in the background
+    fn sync_library(&self) {
+        let connection = self.state.connection.clone();
+        let api = self.spotify.api.clone();
+        spawn(async move {
+            if api.token().is_expired() {
+                api.refresh().await.log_error();
+            }
+            let result = local_library::sync(&api).await;
+            connection.lock().unwrap().report(result.as_ref().err());
+            result.log_error();
+        });
+    }
+
+    /// Fetch the user and their spotify playlists for the library if they aren't cached
+    fn load_user(&self) {
+        if !self.state.window_state.library.lock().unwrap().needs_user() {
+            return;
+        }
+
+        let library = self.state.window_state.library.clone();
+        let connection = self.state.connection.clone();
+        let api = self.spotify.api.clone();
+        spawn(async move {
+            let result = LibraryState::load_user(library, "rataify", &api).await;
+            connection.lock().unwrap().report_result(&result);
+            result.log_error();
+        });
+    }
+
+    /// Try to reach spotify again after being offline
+    fn retry(&mut self) {
+        *self.state.connection.lock().unwrap() = Connection::Retrying;
+        self.state.playback_poll.lock().unwrap().retry();
+        self.load_user();
+        self.sync_library();
     }
 
     fn render(&mut self) -> Result<()> {
+        // Read the generation before drawing so changes made while drawing aren't missed
+        let generation = render::generation();
         self.terminal.draw(|f| {
-            f.render_widget(self.state.clone(), f.size());
+            f.render_widget(&self.state, f.size());
         })?;
+        self.render.rendered(generation);
         Ok(())
     }
 
     async fn update(
         &mut self,
         action: Event,
-        keymaps: &HashMap<KeyEvent, Event>,
+        keymap: &Keymap,
         tx: mpsc::UnboundedSender<Event>,
     ) -> Result<()> {
+        // Anything other than a tick is from the user and can change what is rendered
+        if !matches!(action, Event::Tick | Event::None) {
+            render::mark_dirty();
+        }
+
         match action {
-            Event::Close => match self.state.viewport {
-                Viewport::Modal(_) => {
-                    self.state.viewport = Viewport::Window;
+            // Close the focused modal, otherwise let the window handle it or go back before quitting
+            Event::Close if !self.state.close() && self.dispatch(&action, &tx)?.is_ignored() => {
+                // Stay open when going back fails instead of quitting
+                let back = self.back().await;
+                if !self.report("go back", back).unwrap_or(true) {
+                    self.quit = true;
                 }
-                _ => self.quit = true,
-            },
+            }
+            Event::Back => {
+                let back = self.back().await;
+                self.report("go back", back);
+            }
+            Event::Forward => {
+                let forward = self.forward().await;
+                self.report("go forward", forward);
+            }
             Event::Quit => self.quit = true,
             Event::Focus if !self.focused => {
                 self.focused = true;
+                self.state.playback_poll.lock().unwrap().set_focused(true);
             }
             Event::Unfocus if self.focused => {
                 self.focused = false;
-                // Restart the playback poll so that it starts over when focus is regained
-                self.state.playback_poll.reset();
+                // Back off polling for playback until focus is regained
+                self.state.playback_poll.lock().unwrap().set_focused(false);
             }
             Event::Tick => {
-                // Only render and poll for updates if the app is focused
-                self.render()?;
+                if mutation::clear_expired() {
+                    render::mark_dirty();
+                }
+                if let Some(reported) = connection::take_reported() {
+                    *self.state.connection.lock().unwrap() = reported;
+                }
+
+                // Run the binding of a key sequence that wasn't finished in time
+                if keymap.is_pending() {
+                    for event in keymap.timeout() {
+                        tx.send(event)?;
+                    }
+                    if !keymap.is_pending() {
+                        render::mark_dirty();
+                    }
+                }
+
+                // Only redraw when the state changed or the progress bar needs to move
+                let playing = self
+                    .state
+                    .playback
+                    .lock()
+                    .unwrap()
+                    .playback
+                    .as_ref()
+                    .map(|pb| pb.is_playing)
+                    .unwrap_or(false);
+                if self.render.should_render(playing) {
+                    self.render()?;
+                }
 
                 // Poll to check if playback state should be fetched
-                if self.state.playback_poll.poll() {
+                let poll = self.state.playback_poll.lock().unwrap().poll();
+                if poll {
                     let playback = self.state.playback.clone();
+                    let poll = self.state.playback_poll.clone();
+                    let connection = self.state.connection.clone();
                     let api = self.spotify.api.clone();
 
-                    tokio::spawn(async move {
+                    spawn(async move {
                         // TODO: Push errors to error queue for displaying
                         // Also push it to a error log file that is cleared on startup
                         // TODO: Probably add a spot in the color_eyre panic hook to log errors
@@ -210,521 +397,405 @@ impl App {
                             api.refresh().await.log_error();
                         }
 
-                        let result = api.playback_state(None).await.log_error_or_default();
+                        let result = api.playback_state(None).await;
+                        connection.lock().unwrap().report(result.as_ref().err());
+                        let result = match result {
+                            Err(tupy::Error::Request { error_type: SpotifyErrorType::RateLimit, .. }) => {
+                                poll.lock().unwrap().rate_limited();
+                                return;
+                            }
+                            // Keep showing the last playback until spotify can be reached again
+                            Err(tupy::Error::Connection(_)) => {
+                                poll.lock().unwrap().schedule(&playback.lock().unwrap());
+                                return;
+                            }
+                            result => result.log_error_or_default(),
+                        };
                         let diff = playback
                             .lock()
                             .unwrap()
                             .set_playback(result.map(|pb| pb.into()));
+                        poll.lock().unwrap().schedule(&playback.lock().unwrap());
+
+                        let uri = playback.lock().unwrap().playback.as_ref().and_then(|pb| pb.uri());
+                        if let (true, Some(uri)) = (diff, uri) {
+                            library_index::check(&api, [uri]).await.log_error();
+                        }
 
-                        if diff && playback.lock().unwrap().is_some() {
-                            let item = playback
-                                .lock()
-                                .unwrap()
-                                .playback
-                                .as_ref()
-                                .unwrap()
-                                .item
-                                .clone();
-                            let saved = match &item {
-                                PlaybackItem::Track(t) => api
-                                    .check_saved_tracks([t.uri.clone()])
-                                    .await
-                                    .log_error_or(vec![false])[0],
-                                PlaybackItem::Episode(e) => api
-                                    .check_saved_episodes([e.uri.clone()])
-                                    .await
-                                    .log_error_or(vec![false])[0],
-                                _ => false,
-                            };
-                            playback.lock().unwrap().set_saved(saved);
+                        let current = playback.lock().unwrap().clone();
+                        if local_queue::sync(&api, &current).await {
+                            poll.lock().unwrap().poll_soon();
                         }
 
                         tx.send(Event::UpdateQueue).log_error();
                     });
-                    self.state.playback_poll.reset();
                 }
             }
+            Event::Next if local_queue::skip(&self.spotify.api) => {
+                self.state.playback_poll.lock().unwrap().poll_soon();
+                tx.send(Event::UpdateQueue)?;
+            }
             Event::Next => {
-                if let Some(playback) = self.state.playback.lock().unwrap().playback.as_ref() {
-                    if let Some(device) = playback.device.as_ref() {
-                        let api = self.spotify.api.clone();
-                        let device = device.id.clone();
-                        tokio::spawn(async move {
-                            if api.token().is_expired() {
-                                api.refresh().await.log_error();
-                            }
-                            api.next(device).await.log_error();
-                        });
-                    }
-                }
+                local_queue::follow();
+                let device = match self.state.playback.lock().unwrap().playback.as_ref() {
+                    Some(pb) => pb.device.as_ref().map(|device| device.id.clone()),
+                    None => return Ok(()),
+                };
+                let Some(device) = device else {
+                    return Ok(());
+                };
+
+                // The first item in the queue is what plays next
+                let queue = self.state.window_state.queue.lock().unwrap().queue.clone();
+                let skipped = match &mut *queue.lock().unwrap() {
+                    Loading::Some(q) => q.pop_next(),
+                    _ => None,
+                };
+
+                let api = self.spotify.api.clone();
+                self.mutate_playback(
+                    "skip to the next item",
+                    async move {
+                        if api.token().is_expired() {
+                            api.refresh().await.log_error();
+                        }
+                        api.next(device).await
+                    },
+                    move |_| {
+                        if let (Some((item, shown)), Loading::Some(q)) = (skipped, &mut *queue.lock().unwrap()) {
+                            q.push_next(item, shown);
+                        }
+                    },
+                );
             }
             Event::Previous => {
                 if let Some(playback) = self.state.playback.lock().unwrap().playback.as_ref() {
                     if let Some(device) = playback.device.as_ref() {
+                        local_queue::follow();
                         let api = self.spotify.api.clone();
                         let device = device.id.clone();
-                        tokio::spawn(async move {
+                        let poll = self.state.playback_poll.clone();
+                        spawn(async move {
                             if api.token().is_expired() {
                                 api.refresh().await.log_error();
                             }
                             api.prev(device).await.log_error();
+                            poll.lock().unwrap().poll_soon();
                         });
                     }
                 }
             }
             Event::Toggle => {
-                let device = match self.state.playback.lock().unwrap().playback.as_ref() {
-                    Some(pb) => pb.device.is_some(),
-                    None => false,
+                let playing = match self.state.playback.lock().unwrap().playback.as_ref() {
+                    Some(pb) if pb.device.is_some() => Some(pb.is_playing),
+                    _ => None,
                 };
-                if device {
-                    let api = self.spotify.api.clone();
-                    let pb = self.state.playback.clone();
-                    tokio::spawn(async move {
+                let Some(playing) = playing else {
+                    tx.send(Event::OpenSelectDevice)?;
+                    return Ok(());
+                };
+
+                self.state.playback.lock().unwrap().set_playing(!playing);
+                let api = self.spotify.api.clone();
+                self.mutate_playback(
+                    if playing { "pause playback" } else { "resume playback" },
+                    async move {
                         if api.token().is_expired() {
                             api.refresh().await.log_error();
                         }
-
-                        let playing = pb.lock().unwrap().playback.as_ref().unwrap().is_playing;
                         if playing {
-                            api.pause(None).await.log_error();
-                            if pb.lock().unwrap().playback.is_some() {
-                                pb.lock().unwrap().playback.as_mut().unwrap().is_playing = false;
-                                pb.lock().unwrap().last_playback_poll = Local::now();
-                            }
+                            api.pause(None).await
                         } else {
-                            api.play(Play::Resume, None).await.log_error();
-                            if pb.lock().unwrap().playback.is_some() {
-                                pb.lock().unwrap().playback.as_mut().unwrap().is_playing = true;
-                                pb.lock().unwrap().last_playback_poll = Local::now();
-                            }
+                            api.play(Play::Resume, None).await
                         }
-                    });
-                    return Ok(());
-                }
-
-                tx.send(Event::OpenSelectDevice)?;
+                    },
+                    move |pb| pb.set_playing(playing),
+                );
+            }
+            Event::Down
+            | Event::Up
+            | Event::Right
+            | Event::Left
+            | Event::Tab
+            | Event::Backtab
+            | Event::Motion(_)
+            | Event::Select => {
+                self.dispatch(&action, &tx)?;
             }
-            Event::Down => match &mut self.state.viewport {
-                Viewport::Modal(modal) => match modal {
-                    Modal::Devices => self.state.modal_state.devices.lock().unwrap().next(),
-                    Modal::Artists => self.state.modal_state.artists.lock().unwrap().down(),
-                    Modal::AddToPlaylist => {
-                        if let Some(atp) = self
-                            .state
-                            .modal_state
-                            .add_to_playlist
-                            .lock()
-                            .unwrap()
-                            .as_mut()
-                        {
-                            atp.down();
-                        }
-                    }
-                    _ => {}
-                },
-                Viewport::Window => match &mut self.state.window {
-                    Window::Queue => self.state.window_state.queue.lock().unwrap().next(),
-                    Window::Library => self.state.window_state.library.lock().unwrap().down().await,
-                    Window::Landing => self.state.window_state.landing.lock().unwrap().down(),
-                },
-            },
-            Event::Up => match &mut self.state.viewport {
-                Viewport::Modal(modal) => match modal {
-                    Modal::Devices => self.state.modal_state.devices.lock().unwrap().prev(),
-                    Modal::Artists => self.state.modal_state.artists.lock().unwrap().up(),
-                    Modal::AddToPlaylist => {
-                        if let Some(atp) = self
-                            .state
-                            .modal_state
-                            .add_to_playlist
-                            .lock()
-                            .unwrap()
-                            .as_mut()
-                        {
-                            atp.up();
-                        }
-                    }
-                    _ => {}
-                },
-                Viewport::Window => match &mut self.state.window {
-                    Window::Queue => self.state.window_state.queue.lock().unwrap().prev(),
-                    Window::Library => self.state.window_state.library.lock().unwrap().up().await,
-                    Window::Landing => self.state.window_state.landing.lock().unwrap().up(),
-                },
-            },
-            Event::Right => match &mut self.state.viewport {
-                Viewport::Modal(modal) => match modal {
-                    Modal::AddToPlaylist => {
-                        if let Some(atp) = self
-                            .state
-                            .modal_state
-                            .add_to_playlist
-                            .lock()
-                            .unwrap()
-                            .as_mut()
-                        {
-                            atp.right().await?;
-                        }
-                    }
-                    _ => {}
-                },
-                Viewport::Window => match &mut self.state.window {
-                    Window::Library => {
-                        self.state
-                            .window_state
-                            .library
-                            .lock()
-                            .unwrap()
-                            .right()
-                            .await?
-                    }
-                    Window::Landing => {
-                        self.state
-                            .window_state
-                            .landing
-                            .lock()
-                            .unwrap()
-                            .right()
-                            .await?
-                    }
-                    _ => {}
-                },
-            },
-            Event::Left => match &mut self.state.viewport {
-                Viewport::Modal(modal) => match modal {
-                    Modal::AddToPlaylist => {
-                        if let Some(atp) = self
-                            .state
-                            .modal_state
-                            .add_to_playlist
-                            .lock()
-                            .unwrap()
-                            .as_mut()
-                        {
-                            atp.left().await?;
-                        }
-                    }
-                    _ => {}
-                },
-                Viewport::Window => match &mut self.state.window {
-                    Window::Library => {
-                        self.state
-                            .window_state
-                            .library
-                            .lock()
-                            .unwrap()
-                            .left()
-                            .await?
-                    }
-                    Window::Landing => {
-                        self.state
-                            .window_state
-                            .landing
-                            .lock()
-                            .unwrap()
-                            .left()
-                            .await?
-                    }
-                    _ => {}
-                },
-            },
-            Event::Tab => match &mut self.state.viewport {
-                Viewport::Window => match &mut self.state.window {
-                    Window::Landing => self.state.window_state.landing.lock().unwrap().tab()?,
-                    Window::Library => {
-                        self.state
-                            .window_state
-                            .library
-                            .lock()
-                            .unwrap()
-                            .tab()
-                            .await?
-                    }
-                    _ => {}
-                },
-                _ => {}
-            },
-            Event::Backtab => match &mut self.state.viewport {
-                Viewport::Window => match &mut self.state.window {
-                    Window::Landing => self.state.window_state.landing.lock().unwrap().backtab()?,
-                    Window::Library => {
-                        self.state
-                            .window_state
-                            .library
-                            .lock()
-                            .unwrap()
-                            .backtab()
-                            .await?
-                    }
-                    _ => {}
-                },
-                _ => {}
-            },
             Event::Play(play) => {
+                local_queue::follow();
                 let api = self.spotify.api.clone();
-                tokio::spawn(async move {
+                let poll = self.state.playback_poll.clone();
+                spawn(async move {
                     if api.token().is_expired() {
                         api.refresh().await.log_error();
                     }
                     api.play(play, None).await.log_error();
+                    poll.lock().unwrap().poll_soon();
                 });
             }
             Event::UpdateQueue => {
                 let api = self.spotify.api.clone();
-                let queue = self.state.window_state.queue.clone();
-                tokio::spawn(async move {
+                let (queue, view) = {
+                    let state = self.state.window_state.queue.lock().unwrap();
+                    (state.queue.clone(), state.view.clone())
+                };
+                spawn(async move {
+                    if let Some(items) = local_queue::items() {
+                        library_index::check(&api, items.iter().map(|i| i.get_uri())).await.log_error();
+                        let q = Queue::from_items(items, &view.lock().unwrap());
+                        *queue.lock().unwrap() = Some(q).into();
+                        return;
+                    }
                     match api.queue().await.log_error_ok() {
                         Some(q) => {
-                            let st = api
-                                .check_saved_tracks(q.queue.iter().filter_map(|i| match i {
-                                    Item::Track(t) => Some(t.uri.clone()),
-                                    Item::Episode(_) => None,
-                                }))
-                                .await
-                                .log_error_or(vec![]);
-
-                            let se = api
-                                .check_saved_episodes(q.queue.iter().filter_map(|i| match i {
-                                    Item::Episode(e) => Some(e.uri.clone()),
-                                    Item::Track(_) => None,
-                                }))
-                                .await
-                                .log_error_or(vec![]);
-                            queue.lock().unwrap().queue = Some(Queue::from((q, st, se))).into();
+                            library_index::check(&api, q.queue.iter().map(|i| i.get_uri())).await.log_error();
+                            let q = Queue::new(q, &view.lock().unwrap());
+                            *queue.lock().unwrap() = Some(q).into();
                         }
                         None => {
-                            queue.lock().unwrap().queue = None.into();
+                            *queue.lock().unwrap() = None.into();
                         }
                     }
                 });
             }
-            Event::Refresh => match &mut self.state.viewport {
-                Viewport::Modal(Modal::Devices) => {
-                    if let Ok(devices) = self.spotify.api.devices().await {
-                        self.state.modal_state.devices.lock().unwrap().devices = devices;
-                        self.state
-                            .modal_state
-                            .devices
-                            .lock()
-                            .unwrap()
-                            .state
-                            .select(Some(0))
-                    } else {
-                        return Err(eyre!("Failed to get devices"));
-                    }
+            Event::Refresh => {
+                if self.state.connection.lock().unwrap().is_offline() {
+                    self.retry();
                 }
-                Viewport::Modal(Modal::Artists) => {
-                    self.state
-                        .window_state
-                        .landing
-                        .lock()
-                        .unwrap()
-                        .refresh()
-                        .await?;
-                }
-                #[allow(clippy::single_match)]
-                Viewport::Window => match self.state.window {
-                    Window::Queue => {
-                        tx.send(Event::UpdateQueue).unwrap();
-                    }
-                    Window::Library => {
-                        self.state
-                            .window_state
-                            .library
-                            .lock()
-                            .unwrap()
-                            .refresh()
-                            .await?;
-                    }
-                    Window::Landing => {
-                        self.state
-                            .window_state
-                            .landing
-                            .lock()
-                            .unwrap()
-                            .refresh()
-                            .await?;
-                    }
-                },
-                _ => {}
-            },
-            Event::Select => match &mut self.state.viewport {
-                Viewport::Modal(modal) => match modal {
-                    Modal::Devices => {
-                        let device = self.state.modal_state.devices.lock().unwrap().select();
-                        let api = self.spotify.api.clone();
-                        tokio::spawn(async move {
-                            if api.token().is_expired() {
-                                api.refresh().await.log_error();
-                            }
-                            api.transfer_playback(device.id, true).await.log_error();
-                        });
-                        self.state.viewport = Viewport::Window;
-                    },
-                    Modal::Artists => {
-                        let artist = self.state.modal_state.artists.lock().unwrap().select();
-                        self.state.viewport = Viewport::Window;
-                        tx.send(Event::GoTo(GoTo::Artist(artist))).log_error();
-                    },
-                    Modal::AddToPlaylist => if let Some(state) = self.state.modal_state.add_to_playlist.lock().unwrap().as_ref() {
-                        let item = state.item.clone();
-                        if let Some(uri) = state.select() {
-                            self.spotify.api.add_items(uri, [item], None).await;
-                        }
-                        self.state.viewport = Viewport::Window;
-                    },
-                    _ => {}
-                }
-                #[allow(clippy::single_match)]
-                Viewport::Window => match self.state.window {
-                    Window::Queue => {
-                        if let Some(actions) =
-                            self.state.window_state.queue.lock().unwrap().select()
-                        {
-                            *self.state.modal_state.actions.lock().unwrap() =
-                                ActionState::new(actions);
-                            self.state.viewport = Viewport::Modal(Modal::Action);
-                        }
-                    }
-                    Window::Library => {
-                        if let Some(actions) =
-                            self.state.window_state.library.lock().unwrap().select()
-                        {
-                            *self.state.modal_state.actions.lock().unwrap() =
-                                ActionState::new(actions);
-                            self.state.viewport = Viewport::Modal(Modal::Action);
-                        }
-                    }
-                    Window::Landing => {
-                        if let Some(actions) =
-                            self.state.window_state.landing.lock().unwrap().select()
-                        {
-                            *self.state.modal_state.actions.lock().unwrap() =
-                                ActionState::new(actions);
-                            self.state.viewport = Viewport::Modal(Modal::Action);
-                        }
+
+                let ctx = Context {
+                    api: &self.spotify.api,
+                    tx: &tx,
+                    modals: &self.state.modal_state,
+                };
+                for component in self.state.focus_stack().iter().rev() {
+                    if component.lock().unwrap().refresh(&ctx) {
+                        break;
                     }
                 }
-            },
+            }
+            Event::TransferPlayback(device) => {
+                let api = self.spotify.api.clone();
+                let poll = self.state.playback_poll.clone();
+                spawn(async move {
+                    if api.token().is_expired() {
+                        api.refresh().await.log_error();
+                    }
+                    api.transfer_playback(device, true).await.log_error();
+                    poll.lock().unwrap().poll_soon();
+                });
+            }
             Event::OpenAction => {
                 let actions = self.state.playback.lock().unwrap().into_actions(true);
                 if !actions.is_empty() {
                     *self.state.modal_state.actions.lock().unwrap() = ActionState::new(actions);
-                    self.state.viewport = Viewport::Modal(Modal::Action);
+                    self.state.open(Modal::Action);
                 }
             }
-            Event::OpenAddToPlaylist(uri) => {
-                let pages = Pages::new(self.spotify.api.playlists(None)?);
+            Event::OpenAddToPlaylist(uris) => {
+                let Some(playlists) = self.report("load your playlists", self.spotify.api.playlists(None).map_err(Into::into)) else {
+                    return Ok(());
+                };
+                let pages = Pages::new(playlists);
                 let p = pages.clone();
-                tokio::spawn(async move {
+                spawn(async move {
                     p.next().await.log_error();
                 });
                 *self.state.modal_state.add_to_playlist.lock().unwrap() =
-                    Some(AddToPlaylistState::new(uri.clone(), pages));
-                self.state.viewport = Viewport::Modal(Modal::AddToPlaylist);
+                    Some(AddToPlaylistState::new(uris.clone(), pages));
+                self.state.open(Modal::AddToPlaylist);
             }
             Event::OpenSelectDevice => {
-                if let Ok(devices) = self.spotify.api.devices().await {
-                    *self.state.modal_state.devices.lock().unwrap() = DevicesState {
-                        state: TableState::default(),
-                        devices,
-                    };
-                    self.state.viewport = Viewport::Modal(Modal::Devices);
-                } else {
-                    return Err(eyre!("Failed to get devices"));
+                let devices = self.spotify.api.devices().await.map_err(Into::into);
+                if let Some(devices) = self.report("load the devices", devices) {
+                    *self.state.modal_state.devices.lock().unwrap() = DevicesState::new(devices);
+                    self.state.open(Modal::Devices);
                 }
             }
             Event::OpenGoTo => {
-                self.state.viewport = Viewport::Modal(Modal::GoTo);
+                self.state.open(Modal::GoTo);
             }
-            Event::GoTo(goto) => {
-                match goto {
-                    GoTo::Queue => {
-                        self.state.viewport = Viewport::Window;
-                        self.state.window = Window::Queue;
-                    }
-                    GoTo::Library => {
-                        self.state.viewport = Viewport::Window;
-                        self.state.window = Window::Library;
-                    }
-                    GoTo::Playlist(playlist) => {
-                        *self.state.window_state.landing.lock().unwrap() =
-                            Landing::playlist(&self.spotify.api, playlist.clone()).await?;
-                        self.state.viewport = Viewport::Window;
-                        self.state.window = Window::Landing;
-                    }
-                    GoTo::Album(album) => {
-                        *self.state.window_state.landing.lock().unwrap() =
-                            Landing::album(&self.spotify.api, album.clone()).await?;
-                        self.state.viewport = Viewport::Window;
-                        self.state.window = Window::Landing;
-                    }
-                    GoTo::Show(show) => {
-                        *self.state.window_state.landing.lock().unwrap() =
-                            Landing::show(&self.spotify.api, show.clone()).await?;
-                        self.state.viewport = Viewport::Window;
-                        self.state.window = Window::Landing;
+            Event::OpenFind => {
+                *self.state.modal_state.find.lock().unwrap() = FindState::default();
+                self.state.open(Modal::Find);
+            }
+            Event::OpenCommand => {
+                self.state.modal_state.command.lock().unwrap().reset();
+                self.state.open(Modal::Command);
+
+                // Device names are completed from the devices that are available right now
+                let api = self.spotify.api.clone();
+                let devices = self.state.modal_state.command.lock().unwrap().devices.clone();
+                spawn(async move {
+                    if api.token().is_expired() {
+                        api.refresh().await.log_error();
                     }
-                    GoTo::Audiobook(audiobook) => {
-                        *self.state.window_state.landing.lock().unwrap() =
-                            Landing::audiobook(&self.spotify.api, audiobook.clone()).await?;
-                        self.state.viewport = Viewport::Window;
-                        self.state.window = Window::Landing;
+                    if let Some(found) = api.devices().await.log_error_ok() {
+                        *devices.lock().unwrap() = found.into_iter().map(|device| device.name).collect();
                     }
-                    GoTo::Artist(artist) => {
-                        *self.state.window_state.landing.lock().unwrap() =
-                            Landing::artist(&self.spotify.api, artist.clone()).await?;
-                        self.state.viewport = Viewport::Window;
-                        self.state.window = Window::Landing;
+                });
+            }
+            Event::Command(line) => match line.parse::<Command>() {
+                Ok(Command::Event(event)) => tx.send(event)?,
+                Ok(Command::Action(action)) => action.run(&self.spotify.api, &tx),
+                Ok(Command::Device(name)) => {
+                    let api = self.spotify.api.clone();
+                    let tx = tx.clone();
+                    spawn(async move {
+                        if api.token().is_expired() {
+                            api.refresh().await.log_error();
+                        }
+                        let Some(devices) = api.devices().await.log_error_ok() else {
+                            return;
+                        };
+                        match command::find_device(&devices, &name) {
+                            Some(device) => tx.send(Event::TransferPlayback(device.id.clone())).log_error(),
+                            None => mutation::notify(format!("No device matches {name}")),
+                        }
+                    });
+                }
+                Err(error) => mutation::notify(error.to_string()),
+            },
+            Event::OpenPlaylistForm(form) => match &form {
+                // Playlists are created for the user, who isn't known until it is loaded
+                PlaylistForm::Create => {
+                    let user_id = self.state.window_state.library.lock().unwrap().user_id.clone();
+                    if user_id.is_empty() {
+                        mutation::notify("Can't create a playlist until your profile is loaded".into());
+                        return Ok(());
                     }
-                    GoTo::Artists(artists) => {
-                        *self.state.modal_state.artists.lock().unwrap() =
-                            ArtistsState::new(artists);
-                        self.state.viewport = Viewport::Modal(Modal::Artists);
+                    *self.state.modal_state.playlist_form.lock().unwrap() =
+                        PlaylistFormState::new(form, None, user_id);
+                    self.state.open(Modal::PlaylistForm);
+                }
+                PlaylistForm::Edit(uri) | PlaylistForm::Cover(uri) | PlaylistForm::Delete(uri, _) => {
+                    let api = self.spotify.api.clone();
+                    let id = uri.id().to_string();
+                    let tx = tx.clone();
+                    spawn(async move {
+                        if api.token().is_expired() {
+                            api.refresh().await.log_error();
+                        }
+                        match api.playlist(id, None).await {
+                            Ok(playlist) => tx.send(Event::PlaylistFormLoaded(form, Box::new(playlist))).log_error(),
+                            Err(error) => {
+                                connection::report_later(&error);
+                                mutation::notify(format!("Couldn't load the playlist: {error}"));
+                                Err::<(), _>(error).log_error();
+                            }
+                        }
+                    });
+                }
+            },
+            Event::PlaylistFormLoaded(form, playlist) => {
+                let user_id = self.state.window_state.library.lock().unwrap().user_id.clone();
+                *self.state.modal_state.playlist_form.lock().unwrap() =
+                    PlaylistFormState::new(form, Some(&playlist), user_id);
+                self.state.open(Modal::PlaylistForm);
+            }
+            Event::OpenRadio(radio) => {
+                let user_id = self.state.window_state.library.lock().unwrap().user_id.clone();
+                let state = RadioState::new(radio, user_id);
+                if state.radio.seeds.is_empty() {
+                    let api = self.spotify.api.clone();
+                    let genres = state.genres.clone();
+                    spawn(async move {
+                        if api.token().is_expired() {
+                            api.refresh().await.log_error();
+                        }
+                        *genres.lock().unwrap() = api.available_genre_seeds().await.log_error_ok().into();
+                    });
+                }
+                *self.state.modal_state.radio.lock().unwrap() = state;
+                self.state.open(Modal::Radio);
+            }
+            Event::PlaylistUpdated(uri) => {
+                // Going to the landing that is shown opens it fresh with the new details
+                let current = self.state.navigation.lock().unwrap().current.clone();
+                if current == GoTo::Playlist(uri) {
+                    let shown = self.navigate(current).await;
+                    self.report("show the playlist", shown);
+                } else {
+                    tx.send(Event::Refresh)?;
+                }
+            }
+            Event::Undo => undo::undo(&self.spotify.api, &tx),
+            Event::StopQueueing => enqueue::cancel(),
+            Event::ToggleLocalQueue => {
+                // The local queue picks up where spotify's queue is
+                let items = match &*self.state.window_state.queue.lock().unwrap().queue.lock().unwrap() {
+                    Loading::Some(q) => q.all.iter().map(|i| i.as_ref().clone()).collect(),
+                    _ => Vec::new(),
+                };
+                local_queue::toggle(items);
+                tx.send(Event::UpdateQueue)?;
+            }
+            Event::ClearLocalQueue => {
+                local_queue::clear();
+                tx.send(Event::UpdateQueue)?;
+            }
+            Event::PlaylistDeleted(uri) => {
+                let current = self.state.navigation.lock().unwrap().current.clone();
+                if current == GoTo::Playlist(uri) {
+                    let back = self.back().await;
+                    if !self.report("go back", back).unwrap_or(true) {
+                        let shown = self.navigate(GoTo::Library).await;
+                        self.report("show the library", shown);
                     }
-                    // TODO: Map in changing ui based on goto when the other states are implemented
-                    _ => todo!(),
                 }
             }
-            Event::Key(key) => match &mut self.state.viewport {
-                Viewport::Window => {
-                    if let Some(action) = keymaps.get(&key) {
-                        tx.send(action.clone()).unwrap();
+            Event::GoTo(GoTo::Artists(artists)) => {
+                *self.state.modal_state.artists.lock().unwrap() = ArtistsState::new(artists);
+                self.state.open(Modal::Artists);
+            }
+            Event::GoTo(goto) => {
+                let shown = self.navigate(goto).await;
+                self.report("open the page", shown);
+            }
+            // Keys typed after the start of a sequence finish it instead of going to the focused
+            // component
+            Event::Key(key) if keymap.is_pending() => {
+                if key == key!(Esc) {
+                    keymap.cancel();
+                } else {
+                    for event in keymap.key(key) {
+                        tx.send(event)?;
                     }
                 }
-                Viewport::Modal(modal) => {
-                    if key == key!('q') || key == key!(Esc) {
-                        tx.send(Event::Close).unwrap();
+            }
+            Event::Key(key) => {
+                if self.state.modal().is_some() {
+                    let typing = self.state.focused().lock().unwrap().text_input();
+                    if (key == key!('q') && !typing) || key == key!(Esc) {
+                        tx.send(Event::Close)?;
+                        return Ok(());
                     } else if key == key!('c' + CONTROL) || key == key!('C' + SHIFT + CONTROL) {
-                        tx.send(Event::Quit).unwrap();
-                    } else {
-                        match modal {
-                            Modal::GoTo => {
-                                let go_to = &mut self.state.modal_state.go_to.lock().unwrap();
-                                if go_to.contains(&key) {
-                                    tx.send(Event::GoTo(go_to.get(&key).unwrap().clone()))
-                                        .unwrap();
-                                }
-                            }
-                            Modal::Action => {
-                                if self.state.modal_state.actions.lock().unwrap().resolve(
-                                    key,
-                                    &self.spotify.api,
-                                    tx.clone(),
-                                ) {
-                                    self.state.viewport = Viewport::Window;
-                                }
-                            }
-                            Modal::Devices | Modal::Artists | Modal::AddToPlaylist => {
-                                if let Some(action) = keymaps.get(&key) {
-                                    tx.send(action.clone()).unwrap();
-                                }
-                            }
-                        }
+                        tx.send(Event::Quit)?;
+                        return Ok(());
                     }
                 }
-            },
+
+                let handled = {
+                    let ctx = Context {
+                        api: &self.spotify.api,
+                        tx: &tx,
+                        modals: &self.state.modal_state,
+                    };
+                    let focused = self.state.focused();
+                    let handled = focused.lock().unwrap().key(key, &ctx);
+                    handled
+                };
+
+                // Keys the focused component doesn't handle fall back to the keymaps
+                if handled.is_ignored() {
+                    for event in keymap.key(key) {
+                        tx.send(event)?;
+                    }
+                } else {
+                    self.apply(handled);
+                }
+            }
             Event::ToggleRepeat => {
                 if self.state.playback.lock().unwrap().is_none() {
                     return Ok(());
@@ -753,17 +824,24 @@ impl App {
                     return Ok(());
                 }
 
-                let pb = self.state.playback.clone();
+                if let Some(pb) = self.state.playback.lock().unwrap().playback.as_mut() {
+                    pb.set_repeat(repeat);
+                }
                 let api = self.spotify.api.clone();
-                tokio::spawn(async move {
-                    if api.token().is_expired() {
-                        api.refresh().await.log_error();
-                    }
-                    api.repeat(repeat, None).await.log_error();
-                    if pb.lock().unwrap().is_some() {
-                        pb.lock().unwrap().playback.as_mut().unwrap().repeat = repeat;
-                    }
-                });
+                self.mutate_playback(
+                    "change repeat",
+                    async move {
+                        if api.token().is_expired() {
+                            api.refresh().await.log_error();
+                        }
+                        api.repeat(repeat, None).await
+                    },
+                    move |pb| {
+                        if let Some(pb) = pb.playback.as_mut() {
+                            pb.set_repeat(old);
+                        }
+                    },
+                );
             }
             Event::ToggleShuffle => {
                 if self.state.playback.lock().unwrap().is_none()
@@ -789,101 +867,355 @@ impl App {
                     .as_ref()
                     .unwrap()
                     .shuffle;
-                let pb = self.state.playback.clone();
+                if let Some(pb) = self.state.playback.lock().unwrap().playback.as_mut() {
+                    pb.set_shuffle(shuffle);
+                }
                 let api = self.spotify.api.clone();
-                tokio::spawn(async move {
-                    if api.token().is_expired() {
-                        api.refresh().await.log_error();
-                    }
-                    api.shuffle(shuffle, None).await.log_error();
-                    if pb.lock().unwrap().is_some() {
-                        pb.lock().unwrap().playback.as_mut().unwrap().shuffle = shuffle;
-                    }
-                });
+                self.mutate_playback(
+                    if shuffle { "turn on shuffle" } else { "turn off shuffle" },
+                    async move {
+                        if api.token().is_expired() {
+                            api.refresh().await.log_error();
+                        }
+                        api.shuffle(shuffle, None).await
+                    },
+                    move |pb| {
+                        if let Some(pb) = pb.playback.as_mut() {
+                            pb.set_shuffle(!shuffle);
+                        }
+                    },
+                );
             }
-            Event::VolumeUp => {
-                if self.state.playback.lock().unwrap().is_none()
-                    || self
-                        .state
-                        .playback
-                        .lock()
-                        .unwrap()
-                        .playback
-                        .as_ref()
-                        .unwrap()
-                        .device
-                        .is_none()
-                {
-                    return Ok(());
-                }
-
-                let (vol, unavailable) = {
+            Event::VolumeUp => self.change_volume(|volume| (volume + 10).min(100)),
+            Event::VolumeDown => self.change_volume(|volume| volume.saturating_sub(10)),
+            Event::SetVolume(volume) => self.change_volume(|_| volume.min(100)),
+            Event::Seek(position) => {
+                let (device, duration) = {
                     let playback = self.state.playback.lock().unwrap();
-                    let device = playback.playback.as_ref().unwrap().device.as_ref().unwrap();
-                    (
-                        (device.volume_percent + 10).min(100),
-                        device.is_restricted || !device.supports_volume,
-                    )
+                    match playback.playback.as_ref() {
+                        Some(pb) if pb.device.is_some() && !pb.disallow(PlaybackAction::Seeking) => {
+                            (pb.device.as_ref().unwrap().id.clone(), playback.duration())
+                        }
+                        _ => return Ok(()),
+                    }
                 };
 
-                if unavailable {
+                let Some(duration) = duration else {
                     return Ok(());
-                }
+                };
+                let position = position.max(Duration::zero()).min(duration);
+
+                self.state.playback.lock().unwrap().set_progress(position);
 
                 let api = self.spotify.api.clone();
-                tokio::spawn(async move {
+                let poll = self.state.playback_poll.clone();
+                spawn(async move {
                     if api.token().is_expired() {
                         api.refresh().await.log_error();
                     }
-                    api.volume(vol, None).await.log_error();
+                    api.seek(position, device).await.log_error();
+                    poll.lock().unwrap().poll_soon();
                 });
             }
-            Event::VolumeDown => {
-                if self.state.playback.lock().unwrap().is_none()
-                    || self
-                        .state
-                        .playback
-                        .lock()
-                        .unwrap()
-                        .playback
-                        .as_ref()
-                        .unwrap()
-                        .device
-                        .is_none()
-                {
-                    return Ok(());
+            Event::SeekForward(step) => {
+                if let Some(progress) = self.state.playback.lock().unwrap().progress() {
+                    tx.send(Event::Seek(progress + step))?;
+                }
+            }
+            Event::SeekBackward(step) => {
+                if let Some(progress) = self.state.playback.lock().unwrap().progress() {
+                    tx.send(Event::Seek(progress - step))?;
+                }
+            }
+            Event::SeekPercent(percent) => {
+                if let Some(duration) = self.state.playback.lock().unwrap().duration() {
+                    let position = duration.num_milliseconds() * percent.min(100) as i64 / 100;
+                    tx.send(Event::Seek(Duration::milliseconds(position)))?;
                 }
+            }
+            Event::Mouse(mouse) => match mouse.kind {
+                // Shift + scroll changes pages while a plain scroll moves the selection
+                MouseEventKind::ScrollDown if mouse.modifiers.contains(KeyModifiers::SHIFT) => tx.send(Event::Right)?,
+                MouseEventKind::ScrollUp if mouse.modifiers.contains(KeyModifiers::SHIFT) => tx.send(Event::Left)?,
+                MouseEventKind::ScrollDown => tx.send(Event::Down)?,
+                MouseEventKind::ScrollUp => tx.send(Event::Up)?,
+                MouseEventKind::ScrollRight => tx.send(Event::Right)?,
+                MouseEventKind::ScrollLeft => tx.send(Event::Left)?,
+                MouseEventKind::Down(MouseButton::Left) => {
+                    let area = progress_area(*self.state.playback_area.lock().unwrap());
+                    if mouse.row == area.y {
+                        let position = {
+                            let playback = self.state.playback.lock().unwrap();
+                            match (playback.progress(), playback.duration()) {
+                                (Some(progress), Some(duration)) => {
+                                    ProgressBar::position(area, progress, duration, mouse.column)
+                                }
+                                _ => None,
+                            }
+                        };
 
-                let (vol, unavailable) = {
-                    let playback = self.state.playback.lock().unwrap();
-                    let device = playback.playback.as_ref().unwrap().device.as_ref().unwrap();
-                    (
-                        device.volume_percent.saturating_sub(10),
-                        device.is_restricted || !device.supports_volume,
-                    )
-                };
+                        if let Some(position) = position {
+                            tx.send(Event::Seek(position))?;
+                        }
+                        return Ok(());
+                    }
 
-                if unavailable {
-                    return Ok(());
-                }
+                    let modal = self.state.modal().is_some();
+                    let click = self.state.focused().lock().unwrap().mouse(mouse.column, mouse.row);
+                    // A single click picks an item in a modal while clicking the already
+                    // selected item in a window activates it
+                    let click = if modal && !click.is_miss() { Click::Activated } else { click };
 
-                let api = self.spotify.api.clone();
-                tokio::spawn(async move {
-                    if api.token().is_expired() {
-                        api.refresh().await.log_error();
+                    if click.is_activated() {
+                        tx.send(Event::Select)?;
                     }
-                    api.volume(vol, None).await.log_error();
-                });
-            }
+                }
+                _ => {}
+            },
             _ => {}
         }
         Ok(())
     }
 
+    /// Send the request for a change that was already made to the playback, undoing the change if
+    /// the request fails. The playback is polled soon after either way so it matches spotify.
+    fn mutate_playback<F, R>(&self, what: &'static str, request: F, rollback: R)
+    where
+        F: Future<Output = Result<(), tupy::Error>> + Send + 'static,
+        R: FnOnce(&mut PlaybackState) + Send + 'static,
+    {
+        let playback = self.state.playback.clone();
+        let poll = self.state.playback_poll.clone();
+        mutation::commit(
+            what,
+            async move {
+                let result = request.await;
+                poll.lock().unwrap().poll_soon();
+                result
+            },
+            move || rollback(&mut playback.lock().unwrap()),
+        );
+    }
+
+    /// Route a navigation event to the focused component and update the focus stack from the result
+    fn dispatch(&mut self, event: &Event, tx: &mpsc::UnboundedSender<Event>) -> Result<Handled> {
+        let handled = {
+            let ctx = Context {
+                api: &self.spotify.api,
+                tx,
+                modals: &self.state.modal_state,
+            };
+            let focused = self.state.focused();
+            let handled = focused.lock().unwrap().event(event, &ctx)?;
+            handled
+        };
+        self.apply(handled.clone());
+        Ok(handled)
+    }
+
+    /// Keep the app running when a request made for an event fails. The error is shown in a
+    /// notice and marks the app offline if spotify couldn't be reached.
+    fn report<T>(&self, what: &str, result: Result<T>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(error) => {
+                if let Some(error) = error.downcast_ref::<tupy::Error>() {
+                    self.state.connection.lock().unwrap().report(Some(error));
+                }
+                mutation::notify(format!("Couldn't {what}: {error}"));
+                None
+            }
+        }
+    }
+
+    /// Show the window or landing, remembering the page being left so it can be gone back to.
+    /// The page is fetched before anything is left, so the page shown stays if it fails.
+    async fn navigate(&mut self, goto: GoTo) -> Result<()> {
+        let landing = self.load_page(&goto).await?;
+        let current = self.state.navigation.lock().unwrap().current.clone();
+        // Going to the page that is shown again opens it fresh without adding to the history
+        if current != goto {
+            let left = self.state.leave();
+            self.state.navigation.lock().unwrap().visit(left, goto.clone());
+        }
+        self.show_page(&goto, landing, 0);
+        Ok(())
+    }
+
+    /// Go back to the previous page. Returns false if there is nothing to go back to.
+    async fn back(&mut self) -> Result<bool> {
+        let entry = self.state.navigation.lock().unwrap().back.pop();
+        let Some(mut entry) = entry else {
+            return Ok(false);
+        };
+        let landing = match self.load_entry(&mut entry).await {
+            Ok(landing) => landing,
+            Err(error) => {
+                self.state.navigation.lock().unwrap().push_back(entry);
+                return Err(error);
+            }
+        };
+        let current = self.state.leave();
+        self.state.navigation.lock().unwrap().forward.push(current);
+        self.restore(entry, landing);
+        Ok(true)
+    }
+
+    /// Go forward to the page that was gone back from. Returns false if there is nothing to go
+    /// forward to.
+    async fn forward(&mut self) -> Result<bool> {
+        let entry = self.state.navigation.lock().unwrap().forward.pop();
+        let Some(mut entry) = entry else {
+            return Ok(false);
+        };
+        let landing = match self.load_entry(&mut entry).await {
+            Ok(landing) => landing,
+            Err(error) => {
+                self.state.navigation.lock().unwrap().forward.push(entry);
+                return Err(error);
+            }
+        };
+        let current = self.state.leave();
+        self.state.navigation.lock().unwrap().push_back(current);
+        self.restore(entry, landing);
+        Ok(true)
+    }
+
+    /// Landing of a page from the navigation history, fetching it again if it wasn't kept
+    async fn load_entry(&self, entry: &mut Entry) -> Result<Option<Landing>> {
+        match entry.landing.take() {
+            Some(landing) => Ok(Some(landing)),
+            None => self.load_page(&entry.goto).await,
+        }
+    }
+
+    /// Show a page from the navigation history with the landing loaded for it
+    fn restore(&mut self, entry: Entry, landing: Option<Landing>) {
+        self.state.navigation.lock().unwrap().current = entry.goto.clone();
+        self.show_page(&entry.goto, landing, 0);
+    }
+
+    /// Show the window or landing for the goto, starting a landing from the page with the offset
+    async fn show_goto(&mut self, goto: &GoTo, offset: usize) -> Result<()> {
+        let landing = self.load_page(goto).await?;
+        self.show_page(goto, landing, offset);
+        Ok(())
+    }
+
+    /// Fetch the landing for the goto. Windows are always loaded, so they don't have one.
+    async fn load_page(&self, goto: &GoTo) -> Result<Option<Landing>> {
+        let api = &self.spotify.api;
+        Ok(Some(match goto {
+            GoTo::Queue | GoTo::Library | GoTo::Top | GoTo::History | GoTo::Browse => return Ok(None),
+            GoTo::Playlist(playlist) => {
+                let user_id = self.state.window_state.library.lock().unwrap().user_id.clone();
+                Landing::playlist(api, playlist.clone(), &user_id).await?
+            },
+            GoTo::Album(album) => Landing::album(api, album.clone()).await?,
+            GoTo::Show(show) => Landing::show(api, show.clone()).await?,
+            GoTo::Audiobook(audiobook) => Landing::audiobook(api, audiobook.clone()).await?,
+            GoTo::Artist(artist) => Landing::artist(api, artist.clone()).await?,
+            // TODO: Map in changing ui based on goto when the other states are implemented
+            GoTo::LikedSongs | GoTo::MyEpisodes | GoTo::Artists(_) => return Err(eyre!("{goto} can't be shown yet")),
+        }))
+    }
+
+    /// Show the page for the goto with the landing that was loaded for it, replacing the landing
+    /// shown and cancelling fetches still running for it
+    fn show_page(&mut self, goto: &GoTo, landing: Option<Landing>, offset: usize) {
+        if let Some(landing) = landing {
+            // The landing scope is only entered once the landing is built, so a landing that
+            // fails to open leaves the fetches of the page that is shown running. Pages are
+            // fetched once the landing is shown.
+            landing.start_at(offset);
+            self.state.restore_landing(landing);
+            return;
+        }
+        let window = match goto {
+            GoTo::Queue => Window::Queue,
+            GoTo::Library => Window::Library,
+            GoTo::Top => Window::Top,
+            GoTo::History => Window::History,
+            GoTo::Browse => Window::Browse,
+            _ => return,
+        };
+        self.state.show(window);
+    }
+
+    /// Restore the page, navigation history and library tabs from the last session
+    async fn restore_session(&mut self, session: Session) {
+        {
+            let mut library = self.state.window_state.library.lock().unwrap();
+            library.selected_tab = session.library_tab;
+            library.selected_spotify_playlist = session.spotify_playlist;
+            for (tab, offset) in session.library_offsets {
+                library.start_at(tab, offset);
+            }
+        }
+
+        {
+            let mut navigation = self.state.navigation.lock().unwrap();
+            navigation.back = session.back.into_iter().filter_map(SessionEntry::into_entry).collect();
+            navigation.forward = session.forward.into_iter().filter_map(SessionEntry::into_entry).collect();
+        }
+
+        let goto = session.page.map(|page| page.goto()).transpose().log_error_ok().flatten();
+        if let Some(goto) = goto {
+            self.state.navigation.lock().unwrap().current = goto.clone();
+            // The landing may no longer exist, so fall back to the library
+            if self.show_goto(&goto, session.landing_offset).await.log_error_ok().is_some() {
+                return;
+            }
+            self.state.navigation.lock().unwrap().current = GoTo::Library;
+        }
+        self.state.show(Window::Library);
+    }
+
+    /// Set the volume of the active device from its current volume
+    fn change_volume(&mut self, change: impl FnOnce(u8) -> u8) {
+        let (vol, old) = {
+            let playback = self.state.playback.lock().unwrap();
+            let Some(device) = playback.playback.as_ref().and_then(|pb| pb.device.as_ref()) else {
+                return;
+            };
+            if device.is_restricted || !device.supports_volume {
+                return;
+            }
+            (change(device.volume_percent), device.volume_percent)
+        };
+
+        if vol == old {
+            return;
+        }
+
+        self.state.playback.lock().unwrap().set_volume(vol);
+        let api = self.spotify.api.clone();
+        self.mutate_playback(
+            "change the volume",
+            async move {
+                if api.token().is_expired() {
+                    api.refresh().await.log_error();
+                }
+                api.volume(vol, None).await
+            },
+            move |pb| pb.set_volume(old),
+        );
+    }
+
+    fn apply(&mut self, handled: Handled) {
+        match handled {
+            Handled::Close => {
+                self.state.close();
+            }
+            Handled::Open(modal) => self.state.open(modal),
+            Handled::Ignored | Handled::Consumed => {}
+        }
+    }
+
     fn handle_events(&self, tx: mpsc::UnboundedSender<Event>) {
         let tick_rate = std::time::Duration::from_millis((1.0 / FPS as f32 * 1000.0) as u64);
 
-        tokio::spawn(async move {
+        spawn(async move {
             let mut reader = crossterm::event::EventStream::new();
             let mut interval = tokio::time::interval(tick_rate);
             loop {
@@ -906,6 +1238,9 @@ impl App {
                           crossterm::event::Event::Mouse(mouse) => {
                             tx.send(Event::Mouse(mouse)).log_error();
                           }
+                          crossterm::event::Event::Resize(_, _) => {
+                            render::mark_dirty();
+                          }
                           _ => {},
                         }
                       }
@@ -924,21 +1259,39 @@ impl App {
     }
 
     // Main Application Loop
-    pub async fn run(&mut self, keymaps: HashMap<KeyEvent, Event>) -> Result<()> {
+    pub async fn run(&mut self, keymap: Keymap) -> Result<()> {
         install_hooks()?;
 
         tui::init()?;
+        // Draw before anything is fetched so loading placeholders show up right away. Errors
+        // from here on still fall through to restoring the terminal.
+        let mut result = self.render();
+        if result.is_ok() {
+            self.start().await;
+        }
+
         let (action_tx, mut action_rx) = mpsc::unbounded_channel();
         self.handle_events(action_tx.clone());
 
-        while !self.quit {
+        while !self.quit && result.is_ok() {
             // application update
             if let Some(action) = action_rx.recv().await {
-                self.update(action, &keymaps, action_tx.clone()).await?;
+                let record = self.session && !matches!(action, Event::Tick | Event::None);
+                result = self.update(action, &keymap, action_tx.clone()).await;
+                // Keep the session up to date in case the app panics
+                if record {
+                    Session::record(&self.state);
+                }
             }
         }
 
+        if self.session {
+            Session::new(&self.state).save().log_error();
+        }
+
+        // Let in flight requests stop before the terminal is restored
+        tasks::shutdown().await;
         tui::restore()?;
-        Ok(())
+        result
     }
 }
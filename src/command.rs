@@ -0,0 +1,347 @@
+use std::{path::PathBuf, str::FromStr};
+
+use color_eyre::Result;
+use tupy::{
+    api::{request::Play, response::Device, Resource, Uri},
+    Duration,
+};
+
+use crate::{
+    app::Event,
+    state::{
+        actions::{Action, GoTo, Radio},
+        local_library::{fuzzy_score, LocalItem, LocalKind, LOCAL_LIBRARY},
+    },
+};
+
+/// Most commands kept in the history
+const MAX_HISTORY: usize = 200;
+/// Most completions offered at once
+const MAX_COMPLETIONS: usize = 20;
+
+lazy_static::lazy_static! {
+    pub static ref HISTORY_FILE_PATH: PathBuf = dirs::data_local_dir().unwrap().join("rataify").join("history");
+}
+
+/// Name of a command along with the arguments it takes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spec {
+    pub name: &'static str,
+    pub args: &'static str,
+    pub description: &'static str,
+}
+
+impl Spec {
+    const fn new(name: &'static str, args: &'static str, description: &'static str) -> Self {
+        Self { name, args, description }
+    }
+}
+
+/// Why a line couldn't be run, shown in the command prompt as is
+type ParseResult<T> = std::result::Result<T, String>;
+
+/// Every command that can be run from the command prompt
+pub static COMMANDS: &[Spec] = &[
+    Spec::new("play", "[uri|name]", "Play an item or resume playback"),
+    Spec::new("toggle", "", "Play or pause"),
+    Spec::new("next", "", "Skip to the next item"),
+    Spec::new("previous", "", "Go back to the previous item"),
+    Spec::new("repeat", "", "Toggle repeat"),
+    Spec::new("shuffle", "", "Toggle shuffle"),
+    Spec::new("volume", "<0-100>", "Set the volume"),
+    Spec::new("seek", "<m:ss|seconds|percent%|+seconds|-seconds>", "Seek in the current item"),
+    Spec::new("queue", "<uri|name>", "Add an item to the queue"),
+    Spec::new("save", "<uri|name>", "Save an item to the library"),
+    Spec::new("remove", "<uri|name>", "Remove an item from the library"),
+    Spec::new("add-to-playlist", "<uri|name>", "Add an item to a playlist"),
+    Spec::new("goto", "[page|kind name|uri]", "Go to a page, or to something saved"),
+    Spec::new("device", "[name]", "Transfer playback to a device"),
+    Spec::new("find", "", "Fuzzy find something saved"),
+    Spec::new("refresh", "", "Fetch the current page again"),
+    Spec::new("radio", "[genre]", "Start a radio from a genre"),
+    Spec::new("undo", "", "Undo the last library or playlist change"),
+    Spec::new("localqueue", "", "Switch between the Spotify queue and the local queue"),
+    Spec::new("clearqueue", "", "Remove every item from the local queue"),
+    Spec::new("back", "", "Go back"),
+    Spec::new("forward", "", "Go forward"),
+    Spec::new("quit", "", "Quit"),
+];
+
+/// Pages that can be gone to by name
+static PAGES: &[(&str, GoTo)] = &[
+    ("library", GoTo::Library),
+    ("queue", GoTo::Queue),
+    ("top", GoTo::Top),
+    ("history", GoTo::History),
+    ("browse", GoTo::Browse),
+];
+
+/// Kinds of saved items that can be gone to by name
+static KINDS: &[(&str, LocalKind)] = &[
+    ("album", LocalKind::Album),
+    ("artist", LocalKind::Artist),
+    ("playlist", LocalKind::Playlist),
+    ("show", LocalKind::Show),
+    ("audiobook", LocalKind::Audiobook),
+];
+
+/// What a line typed in the command prompt runs
+///
+/// Commands are a name followed by its arguments, such as `volume 35` or `goto artist Radiohead`.
+/// Items can be given as a spotify uri or by the name they are saved under in the library.
+#[derive(Debug, Clone)]
+pub enum Command {
+    Event(Event),
+    Action(Action),
+    /// Transfer playback to the device with the name, which is looked up when the command is run
+    Device(String),
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(line: &str) -> ParseResult<Self> {
+        let line = line.trim().trim_start_matches(':').trim_start();
+        let (name, args) = match line.split_once(char::is_whitespace) {
+            Some((name, args)) => (name, args.trim()),
+            None => (line, ""),
+        };
+
+        Ok(match name {
+            "play" if args.is_empty() => Self::Event(Event::Play(Play::Resume)),
+            "play" => play(resolve(None, args)?)?,
+            "toggle" => Self::Event(Event::Toggle),
+            "next" => Self::Event(Event::Next),
+            "previous" | "prev" => Self::Event(Event::Previous),
+            "repeat" => Self::Event(Event::ToggleRepeat),
+            "shuffle" => Self::Event(Event::ToggleShuffle),
+            "volume" => match args.trim_end_matches('%').parse::<u8>() {
+                Ok(volume) if volume <= 100 => Self::Event(Event::SetVolume(volume)),
+                _ => return Err("Volume must be a number from 0 to 100".to_string()),
+            },
+            "seek" => Self::Event(seek(args)?),
+            "queue" => Self::Action(Action::AddToQueue(resolve(None, required(args)?)?)),
+            "save" => Self::Action(Action::Save(resolve(None, required(args)?)?)),
+            "remove" => Self::Action(Action::remove(resolve(None, required(args)?)?)),
+            "add-to-playlist" => Self::Action(Action::AddToPlaylist(resolve(None, required(args)?)?)),
+            "goto" if args.is_empty() => Self::Event(Event::OpenGoTo),
+            "goto" => Self::Event(Event::GoTo(goto(args)?)),
+            "device" if args.is_empty() => Self::Event(Event::OpenSelectDevice),
+            "device" => Self::Device(args.to_string()),
+            "find" => Self::Event(Event::OpenFind),
+            "refresh" => Self::Event(Event::Refresh),
+            "radio" => Self::Event(Event::OpenRadio(Radio::genre((!args.is_empty()).then(|| args.to_lowercase())))),
+            "undo" => Self::Event(Event::Undo),
+            "localqueue" => Self::Event(Event::ToggleLocalQueue),
+            "clearqueue" => Self::Event(Event::ClearLocalQueue),
+            "back" => Self::Event(Event::Back),
+            "forward" => Self::Event(Event::Forward),
+            "quit" => Self::Event(Event::Quit),
+            "" => return Err("No command was given".to_string()),
+            other => return Err(format!("Unknown command: {other}")),
+        })
+    }
+}
+
+fn required(args: &str) -> ParseResult<&str> {
+    if args.is_empty() {
+        return Err("Missing a uri or the name of something saved".to_string());
+    }
+    Ok(args)
+}
+
+/// Parse the argument as a spotify uri if it is one
+fn parse_uri(arg: &str) -> Option<Uri> {
+    // Parsing expects at least a resource and an id
+    if !arg.starts_with("spotify:") || arg.split(':').count() < 3 {
+        return None;
+    }
+    arg.parse().ok()
+}
+
+/// Items saved in the library that best match the name, with exact matches first
+fn lookup(kind: Option<LocalKind>, name: &str, limit: usize) -> Vec<LocalItem> {
+    let mut items = LOCAL_LIBRARY.lock().unwrap().search_kind(kind, name, limit);
+    items.sort_by_key(|item| !item.name.eq_ignore_ascii_case(name));
+    items
+}
+
+/// Uri given directly or of the saved item that best matches the name
+fn resolve(kind: Option<LocalKind>, arg: &str) -> ParseResult<Uri> {
+    if let Some(uri) = parse_uri(arg) {
+        return Ok(uri);
+    }
+    let item = lookup(kind, arg, MAX_COMPLETIONS)
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("Nothing saved matches {arg}"))?;
+    item.uri.parse::<Uri>().map_err(|error| error.to_string())
+}
+
+fn play(uri: Uri) -> ParseResult<Command> {
+    let id = uri.id().to_string();
+    Ok(match uri.resource() {
+        Resource::Track | Resource::Episode => Command::Action(Action::Play(uri)),
+        Resource::Album => Command::Event(Event::Play(Play::album(id, None, 0))),
+        Resource::Playlist => Command::Event(Event::Play(Play::playlist(id, None, 0))),
+        Resource::Show => Command::Event(Event::Play(Play::show(id, None, 0))),
+        Resource::Artist => Command::Event(Event::Play(Play::artist(id))),
+        Resource::User(_) => return Err(format!("Can't play {uri}")),
+    })
+}
+
+fn seek(arg: &str) -> ParseResult<Event> {
+    let invalid = || "Seek to a position like 1:30, 90, 50% or +10".to_string();
+    if let Some(percent) = arg.strip_suffix('%') {
+        return match percent.parse::<u8>() {
+            Ok(percent) if percent <= 100 => Ok(Event::SeekPercent(percent)),
+            _ => Err(invalid()),
+        };
+    }
+    if let Some(seconds) = arg.strip_prefix('+') {
+        return Ok(Event::SeekForward(Duration::seconds(seconds.parse().map_err(|_| invalid())?)));
+    }
+    if let Some(seconds) = arg.strip_prefix('-') {
+        return Ok(Event::SeekBackward(Duration::seconds(seconds.parse().map_err(|_| invalid())?)));
+    }
+    let seconds = match arg.split_once(':') {
+        Some((minutes, seconds)) => {
+            minutes.parse::<i64>().map_err(|_| invalid())? * 60 + seconds.parse::<i64>().map_err(|_| invalid())?
+        }
+        None => arg.parse::<i64>().map_err(|_| invalid())?,
+    };
+    Ok(Event::Seek(Duration::seconds(seconds)))
+}
+
+fn goto(args: &str) -> ParseResult<GoTo> {
+    if let Some(uri) = parse_uri(args) {
+        return GoTo::try_from(uri).map_err(|error| error.to_string());
+    }
+    let (target, name) = match args.split_once(char::is_whitespace) {
+        Some((target, name)) => (target, name.trim()),
+        None => (args, ""),
+    };
+    if let Some((_, page)) = PAGES.iter().find(|(page, _)| *page == target) {
+        return Ok(page.clone());
+    }
+    let Some((_, kind)) = KINDS.iter().find(|(k, _)| *k == target) else {
+        return Err(format!("Can't go to {target}"));
+    };
+    let uri = resolve(Some(*kind), required(name)?)?;
+    Ok(match kind {
+        LocalKind::Audiobook => GoTo::Audiobook(uri),
+        _ => GoTo::try_from(uri).map_err(|error| error.to_string())?,
+    })
+}
+
+/// Device with the name, or the one that best matches it
+pub fn find_device<'a>(devices: &'a [Device], name: &str) -> Option<&'a Device> {
+    devices
+        .iter()
+        .find(|device| device.name.eq_ignore_ascii_case(name))
+        .or_else(|| {
+            devices
+                .iter()
+                .filter_map(|device| fuzzy_score(name, &device.name).map(|score| (score, device)))
+                .max_by_key(|(score, _)| *score)
+                .map(|(_, device)| device)
+        })
+}
+
+/// Line the completion would change the command prompt to, along with what it completes to
+#[derive(Debug, Clone, PartialEq)]
+pub struct Completion {
+    pub line: String,
+    pub label: String,
+    pub detail: String,
+}
+
+/// Complete the last word of the line from the commands, pages, devices and saved items
+pub fn complete(line: &str, devices: &[String]) -> Vec<Completion> {
+    let line = line.trim_start().trim_start_matches(':');
+    let Some((name, args)) = line.split_once(char::is_whitespace) else {
+        return ranked(line, COMMANDS.iter(), |spec| spec.name, |spec| Completion {
+            line: if spec.args.is_empty() { spec.name.to_string() } else { format!("{} ", spec.name) },
+            label: format!("{} {}", spec.name, spec.args).trim_end().to_string(),
+            detail: spec.description.to_string(),
+        });
+    };
+    let args = args.trim_start();
+
+    match name {
+        "play" | "queue" | "save" | "remove" | "add-to-playlist" => items(None, args, |item| format!("{name} {}", item.uri)),
+        "device" => ranked(args, devices.iter(), |device| device.as_str(), |device| Completion {
+            line: format!("device {device}"),
+            label: device.clone(),
+            detail: "Device".to_string(),
+        }),
+        "goto" => match args.split_once(char::is_whitespace) {
+            Some((kind, name)) => match KINDS.iter().find(|(k, _)| *k == kind) {
+                Some((kind_name, kind)) => items(Some(*kind), name.trim_start(), |item| format!("goto {kind_name} {}", item.name)),
+                None => Vec::new(),
+            },
+            None => ranked(
+                args,
+                PAGES.iter().map(|(page, goto)| (*page, goto.to_string())).chain(KINDS.iter().map(|(kind, k)| (*kind, k.label().to_string()))),
+                |(target, _)| *target,
+                |(target, label)| Completion {
+                    line: if KINDS.iter().any(|(kind, _)| *kind == target) { format!("goto {target} ") } else { format!("goto {target}") },
+                    label: target.to_string(),
+                    detail: label,
+                },
+            ),
+        },
+        _ => Vec::new(),
+    }
+}
+
+/// Completions for what matches the word, best match first
+fn ranked<T, I, N, C>(word: &str, candidates: I, name: N, completion: C) -> Vec<Completion>
+where
+    I: Iterator<Item = T>,
+    N: Fn(&T) -> &str,
+    C: Fn(T) -> Completion,
+{
+    let mut matches = candidates
+        .filter_map(|candidate| fuzzy_score(word, name(&candidate)).map(|score| (score, candidate)))
+        .collect::<Vec<_>>();
+    matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    matches.into_iter().take(MAX_COMPLETIONS).map(|(_, candidate)| completion(candidate)).collect()
+}
+
+/// Completions for saved items that match the name
+fn items<L: Fn(&LocalItem) -> String>(kind: Option<LocalKind>, name: &str, line: L) -> Vec<Completion> {
+    if name.trim().is_empty() {
+        return Vec::new();
+    }
+    lookup(kind, name, MAX_COMPLETIONS)
+        .into_iter()
+        .map(|item| Completion {
+            line: line(&item),
+            detail: match item.detail.is_empty() {
+                true => item.kind.label().to_string(),
+                false => format!("{} · {}", item.kind.label(), item.detail),
+            },
+            label: item.name,
+        })
+        .collect()
+}
+
+/// Commands run before, oldest first
+pub fn load_history() -> Result<Vec<String>> {
+    if !HISTORY_FILE_PATH.exists() {
+        return Ok(Vec::new());
+    }
+    let history = std::fs::read_to_string(HISTORY_FILE_PATH.as_path())?;
+    Ok(history.lines().filter(|line| !line.trim().is_empty()).map(String::from).collect())
+}
+
+/// Save the most recent commands that were run
+pub fn save_history(history: &[String]) -> Result<()> {
+    if let Some(parent) = HISTORY_FILE_PATH.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let recent = &history[history.len().saturating_sub(MAX_HISTORY)..];
+    std::fs::write(HISTORY_FILE_PATH.as_path(), recent.join("\n"))?;
+    Ok(())
+}
@@ -2,28 +2,24 @@ use std::{fmt::Display, io::Write, panic, path::PathBuf};
 
 use color_eyre::{config::HookBuilder, eyre};
 
-use crate::tui;
+use crate::{state::session::Session, tui};
 
-/// This replaces the standard color_eyre panic and error hooks with hooks that
-/// restore the terminal before printing the panic or error.
+/// This replaces the standard color_eyre panic and error hooks. The panic hook restores the
+/// terminal before printing the panic. The error hook runs whenever a report is created, even one
+/// that is handled, so the terminal is restored where `App::run` returns instead.
 pub fn install_hooks() -> color_eyre::Result<()> {
     let (panic_hook, eyre_hook) = HookBuilder::default().into_hooks();
 
     // convert from a color_eyre PanicHook to a standard panic hook
     let panic_hook = panic_hook.into_panic_hook();
     panic::set_hook(Box::new(move |panic_info| {
+        Session::save_recorded();
         tui::restore().unwrap();
         panic_hook(panic_info);
     }));
 
     // convert from a color_eyre EyreHook to a eyre ErrorHook
-    let eyre_hook = eyre_hook.into_eyre_hook();
-    eyre::set_hook(Box::new(
-        move |error: &(dyn std::error::Error + 'static)| {
-            tui::restore().unwrap();
-            eyre_hook(error)
-        },
-    ))?;
+    eyre::set_hook(eyre_hook.into_eyre_hook())?;
 
     Ok(())
 }
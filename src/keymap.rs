@@ -0,0 +1,175 @@
+use std::{collections::HashMap, time::{Duration, Instant}};
+
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::{app::Event, state::Motion, Locked};
+
+/// How long to wait for the next key of a sequence before running the binding of the keys typed
+/// so far, the same as vim's default `timeoutlen`
+static SEQUENCE_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// Most times a counted binding is repeated
+const MAX_COUNT: usize = 999;
+
+lazy_static::lazy_static! {
+    // Keys typed so far that are the start of a longer binding
+    static ref PENDING: Locked<Option<Pending>> = Locked::default();
+}
+
+/// Count and keys typed so far, along with the keys that can come next
+#[derive(Debug, Clone)]
+pub struct Pending {
+    /// Count typed before the keys, such as the `5` in `5j`
+    pub count: Option<usize>,
+    pub keys: Vec<KeyEvent>,
+    /// Keys that can come next and what they run, or `None` if they start a longer sequence
+    pub next: Vec<(KeyEvent, Option<String>)>,
+    typed: Instant,
+}
+
+/// Keys typed so far if they are waiting on more keys
+pub fn pending() -> Option<Pending> {
+    PENDING.lock().unwrap().clone()
+}
+
+/// Key sequences mapped to the events they send
+///
+/// A binding can be more than one key, such as `zz`. Keys that start a longer binding wait for
+/// the next key, running their own binding if nothing else is typed within the timeout. Digits
+/// typed before a binding are a count that repeats bindings like moving up and down.
+#[derive(Debug, Clone, Default)]
+pub struct Keymap {
+    bindings: HashMap<Vec<KeyEvent>, Event>,
+}
+
+impl<const N: usize> From<[(Vec<KeyEvent>, Event); N]> for Keymap {
+    fn from(bindings: [(Vec<KeyEvent>, Event); N]) -> Self {
+        Self {
+            bindings: HashMap::from(bindings),
+        }
+    }
+}
+
+impl Keymap {
+    /// True if keys are waiting on the rest of a sequence
+    pub fn is_pending(&self) -> bool {
+        PENDING.lock().unwrap().is_some()
+    }
+
+    /// Stop waiting on the rest of a sequence. Returns true if one was pending.
+    pub fn cancel(&self) -> bool {
+        PENDING.lock().unwrap().take().is_some()
+    }
+
+    /// Add the key to the keys typed so far, returning the events to send once a binding is
+    /// complete
+    pub fn key(&self, key: KeyEvent) -> Vec<Event> {
+        let mut guard = PENDING.lock().unwrap();
+        let mut pending = guard.take().unwrap_or_else(|| Pending {
+            count: None,
+            keys: Vec::new(),
+            next: Vec::new(),
+            typed: Instant::now(),
+        });
+        pending.typed = Instant::now();
+
+        // Digits before any other key are a count. A leading zero is left to its own binding.
+        if pending.keys.is_empty() && key.modifiers.is_empty() {
+            if let KeyCode::Char(c @ '0'..='9') = key.code {
+                if c != '0' || pending.count.is_some() {
+                    let digit = c.to_digit(10).unwrap() as usize;
+                    pending.count = Some((pending.count.unwrap_or(0) * 10 + digit).min(MAX_COUNT));
+                    pending.next = self.next(&pending.keys, pending.count);
+                    *guard = Some(pending);
+                    return Vec::new();
+                }
+            }
+        }
+
+        pending.keys.push(key);
+        // Only wait when a longer binding can still match, which after a count is only the
+        // bindings it repeats
+        pending.next = self.next(&pending.keys, pending.count);
+        if !pending.next.is_empty() {
+            *guard = Some(pending);
+            return Vec::new();
+        }
+
+        match self.bindings.get(&pending.keys) {
+            Some(event) => repeat(event, pending.count),
+            None => Vec::new(),
+        }
+    }
+
+    /// Run the binding of the keys typed so far if the next key of the sequence wasn't typed in
+    /// time
+    pub fn timeout(&self) -> Vec<Event> {
+        let mut guard = PENDING.lock().unwrap();
+        if guard.as_ref().is_none_or(|pending| pending.typed.elapsed() < SEQUENCE_TIMEOUT) {
+            return Vec::new();
+        }
+
+        let pending = guard.take().unwrap();
+        match self.bindings.get(&pending.keys) {
+            Some(event) => repeat(event, pending.count),
+            None => Vec::new(),
+        }
+    }
+
+    /// Keys that can follow the keys typed so far, only counting bindings that use the count if
+    /// one was typed
+    fn next(&self, keys: &[KeyEvent], count: Option<usize>) -> Vec<(KeyEvent, Option<String>)> {
+        let mut next = HashMap::new();
+        for (sequence, event) in &self.bindings {
+            if sequence.len() <= keys.len() || !sequence.starts_with(keys) || (count.is_some() && !counted_event(event)) {
+                continue;
+            }
+            let key = sequence[keys.len()];
+            if sequence.len() == keys.len() + 1 {
+                let label = match (event, count) {
+                    (Event::SeekPercent(_), Some(count)) => Event::SeekPercent(percent(count)).label(),
+                    _ => event.label(),
+                };
+                next.entry(key).or_insert(Some(label));
+            } else {
+                next.insert(key, None);
+            }
+        }
+        next.into_iter().collect()
+    }
+}
+
+/// True if typing a count before the event repeats it, or for seeking, is the percent to seek
+/// to. Only movement is repeated, since every repeat of a playback event would be its own request
+/// to spotify.
+fn counted_event(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::SeekPercent(_)
+            | Event::Up
+            | Event::Down
+            | Event::Left
+            | Event::Right
+            | Event::Tab
+            | Event::Backtab
+            | Event::Motion(Motion::HalfPageDown | Motion::HalfPageUp | Motion::PageDown | Motion::PageUp | Motion::Bottom)
+    )
+}
+
+/// Events to send for a binding typed after the count
+fn repeat(event: &Event, count: Option<usize>) -> Vec<Event> {
+    match (event, count) {
+        (Event::SeekPercent(_), Some(count)) => vec![Event::SeekPercent(percent(count))],
+        // Like vim, a count before jumping to the bottom jumps to that row instead
+        (Event::Motion(Motion::Bottom), Some(count)) => std::iter::once(Event::Motion(Motion::Top))
+            .chain(std::iter::repeat_n(Event::Down, count.saturating_sub(1)))
+            .collect(),
+        (event, Some(count)) if counted_event(event) => std::iter::repeat_n(event.clone(), count).collect(),
+        (event, _) => vec![event.clone()],
+    }
+}
+
+/// Percent to seek to for the count typed before seeking
+fn percent(count: usize) -> u8 {
+    count.min(100) as u8
+}
@@ -6,6 +6,10 @@ pub mod app;
 pub mod ui;
 pub mod spotify_util;
 pub mod state;
+pub mod render;
+pub mod tasks;
+pub mod keymap;
+pub mod command;
 
 pub type Shared<T> = Arc<T>;
 pub type Locked<T> = Mutex<T>;
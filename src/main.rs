@@ -1,49 +1,77 @@
-use std::collections::HashMap;
 use color_eyre::eyre::Result;
-use rataify::{app::{Event, App}, key};
+use rataify::{app::{Event, App}, key, keymap::Keymap, state::Motion};
+use tupy::Duration;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    App::new()
+    // Start fresh without restoring or saving the last session
+    let session = !std::env::args().any(|arg| arg == "--no-session");
+
+    App::new(session)
         .await?
-        .run(HashMap::from([
+        .run(Keymap::from([
             // Menus
-            (key!('d'), Event::OpenSelectDevice),
-            (key!('g'), Event::OpenGoTo),
-            (key!(','), Event::OpenAction),
+            // `gg` jumps to the top from the go to menu, so `g` doesn't wait for a second key
+            (vec![key!('d')], Event::OpenSelectDevice),
+            (vec![key!('g')], Event::OpenGoTo),
+            (vec![key!(',')], Event::OpenAction),
+            (vec![key!('f' + CONTROL)], Event::OpenFind),
+            (vec![key!(':' + SHIFT)], Event::OpenCommand),
             // TODO: Implement
-            (key!('?'), Event::OpenHelp),
+            (vec![key!('?')], Event::OpenHelp),
             // TODO: Implement
-            (key!('/'), Event::OpenSearch),
+            (vec![key!('/')], Event::OpenSearch),
 
             // Playback State
-            (key!(' '), Event::Toggle),
-            (key!('>' + SHIFT), Event::Next),
-            (key!('<' + SHIFT), Event::Previous),
-            (key!('r'), Event::ToggleRepeat),
-            (key!('s'), Event::ToggleShuffle),
-            (key!('+' + SHIFT), Event::VolumeUp),
-            (key!('-'), Event::VolumeDown),
+            (vec![key!(' ')], Event::Toggle),
+            (vec![key!('>' + SHIFT)], Event::Next),
+            (vec![key!('<' + SHIFT)], Event::Previous),
+            (vec![key!('Q' + SHIFT)], Event::ToggleLocalQueue),
+            (vec![key!('r')], Event::ToggleRepeat),
+            (vec![key!('s')], Event::ToggleShuffle),
+            (vec![key!('+' + SHIFT)], Event::VolumeUp),
+            (vec![key!('-')], Event::VolumeDown),
+            (vec![key!(']')], Event::SeekForward(Duration::seconds(10))),
+            (vec![key!('[')], Event::SeekBackward(Duration::seconds(10))),
+            (vec![key!('}' + SHIFT)], Event::SeekForward(Duration::seconds(30))),
+            (vec![key!('{' + SHIFT)], Event::SeekBackward(Duration::seconds(30))),
+            // 1-9 start a count such as `5j`, so a count before `%` seeks to that percent like
+            // vim's `N%`
+            (vec![key!('0')], Event::SeekPercent(0)),
+            (vec![key!('%' + SHIFT)], Event::SeekPercent(50)),
 
             // Navigation
-            (key!(Enter), Event::Select),
-            (key!(Right), Event::Right),
-            (key!('l'), Event::Right),
-            (key!(Left), Event::Left),
-            (key!('h'), Event::Left),
-            (key!(Up), Event::Up),
-            (key!('k'), Event::Up),
-            (key!(Down), Event::Down),
-            (key!('j'), Event::Down),
-            (key!(Tab), Event::Tab),
-            (key!(BackTab + SHIFT), Event::Backtab),
-            (key!('r' + CONTROL), Event::Refresh),
-            (key!('R' + SHIFT + CONTROL), Event::Refresh),
+            (vec![key!(Enter)], Event::Select),
+            (vec![key!(Right)], Event::Right),
+            (vec![key!('l')], Event::Right),
+            (vec![key!(Left)], Event::Left),
+            (vec![key!('h')], Event::Left),
+            (vec![key!(Up)], Event::Up),
+            (vec![key!('k')], Event::Up),
+            (vec![key!(Down)], Event::Down),
+            (vec![key!('j')], Event::Down),
+            (vec![key!(Tab)], Event::Tab),
+            (vec![key!(BackTab + SHIFT)], Event::Backtab),
+            (vec![key!('r' + CONTROL)], Event::Refresh),
+            (vec![key!('R' + SHIFT + CONTROL)], Event::Refresh),
+            (vec![key!('G' + SHIFT)], Event::Motion(Motion::Bottom)),
+            (vec![key!(Home)], Event::Motion(Motion::Top)),
+            (vec![key!(End)], Event::Motion(Motion::Bottom)),
+            (vec![key!('d' + CONTROL)], Event::Motion(Motion::HalfPageDown)),
+            (vec![key!('u' + CONTROL)], Event::Motion(Motion::HalfPageUp)),
+            (vec![key!(PageDown)], Event::Motion(Motion::PageDown)),
+            (vec![key!(PageUp)], Event::Motion(Motion::PageUp)),
+            (vec![key!('z'), key!('z')], Event::Motion(Motion::Center)),
+            (vec![key!('u')], Event::Undo),
+            (vec![key!(Esc)], Event::StopQueueing),
+            (vec![key!(Backspace)], Event::Back),
+            (vec![key!(Left + ALT)], Event::Back),
+            (vec![key!(Right + ALT)], Event::Forward),
 
             // Quit / Close
-            (key!('q'), Event::Close),
-            (key!('c' + CONTROL), Event::Quit),
-            (key!('C' + SHIFT + CONTROL), Event::Quit),
+            (vec![key!('q')], Event::Close),
+            (vec![key!('c' + CONTROL)], Event::Quit),
+            (vec![key!('C' + SHIFT + CONTROL)], Event::Quit),
         ]))
         .await
 }
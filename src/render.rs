@@ -0,0 +1,49 @@
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+/// Time between redraws of the progress bar while something is playing
+static PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Generation of the state, bumped every time something that is rendered changes
+static GENERATION: AtomicUsize = AtomicUsize::new(1);
+
+/// Mark the state as changed so the next tick redraws the terminal
+pub fn mark_dirty() {
+    GENERATION.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn generation() -> usize {
+    GENERATION.load(Ordering::Relaxed)
+}
+
+/// Decides when a tick should redraw the terminal
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderSchedule {
+    drawn: usize,
+    last_draw: Instant,
+}
+
+impl Default for RenderSchedule {
+    fn default() -> Self {
+        Self {
+            drawn: 0,
+            last_draw: Instant::now(),
+        }
+    }
+}
+
+impl RenderSchedule {
+    /// True if the state changed since the last draw, or if the progress bar is moving and is
+    /// due for a redraw
+    pub fn should_render(&self, progressing: bool) -> bool {
+        self.drawn != generation() || (progressing && self.last_draw.elapsed() >= PROGRESS_INTERVAL)
+    }
+
+    /// Record that the current generation was drawn
+    pub fn rendered(&mut self, generation: usize) {
+        self.drawn = generation;
+        self.last_draw = Instant::now();
+    }
+}
@@ -1,18 +1,20 @@
-use std::{fmt::{Debug, Display}, rc::Rc};
+use std::{collections::HashMap, fmt::{Debug, Display}};
 
 use color_eyre::{eyre::eyre, Report, Result};
 use crossterm::event::KeyEvent;
+use tokio::sync::mpsc;
 use tupy::api::{
-    request::Play,
+    flow::{AuthFlow, Pkce},
+    request::{Play, SeedId},
     response::{
-        Context, Episode, Item, PlaybackItem, PlaylistItemInfo, SimplifiedAlbum, SimplifiedChapter, SimplifiedEpisode, SimplifiedTrack, Track
+        Artist, Context, Episode, Item, PlayHistory, PlaybackItem, PlaylistItemInfo, SimplifiedAlbum, SimplifiedChapter, SimplifiedEpisode, SimplifiedTrack, Track
     },
-    Resource, Uri, UserResource,
+    Resource, Uri, UserApi, UserResource,
 };
 
-use crate::{key, Shared};
+use crate::{app::Event, errors::LogError, key, tasks::spawn, Locked, Shared};
 
-use super::{playback::PlaybackState, wrappers::GetUri};
+use super::{enqueue::{self, QueueMode, QUEUE_NEXT}, library_index, local_queue, mutation, playback::PlaybackState, undo::{self, Inverse}, wrappers::GetUri};
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub enum GoTo {
@@ -20,6 +22,9 @@ pub enum GoTo {
     Queue,
     LikedSongs,
     MyEpisodes,
+    Top,
+    History,
+    Browse,
 
     Album(Uri),
     Artist(Uri),
@@ -69,21 +74,71 @@ impl Display for GoTo {
             Self::Audiobook(_) => write!(f, "Audiobook"),
             Self::LikedSongs => write!(f, "Liked Songs"),
             Self::MyEpisodes => write!(f, "My Episodes"),
+            Self::Top => write!(f, "Top Items"),
+            Self::History => write!(f, "Recently Played"),
+            Self::Browse => write!(f, "Browse"),
             Self::Artists(_) => write!(f, "Artists"),
         }
     }
 }
 
+/// Form for creating or changing one of the user's playlists
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlaylistForm {
+    Create,
+    /// Edit the name, description and visibility
+    Edit(Uri),
+    /// Upload a jpeg from disk as the cover
+    Cover(Uri),
+    /// Ask before running the action that removes the playlist
+    Delete(Uri, Box<Action>),
+}
+
+/// Most seeds spotify takes for recommendations
+pub const MAX_SEEDS: usize = 5;
+
+/// Seeds to start a radio from, with the name it is shown as. A radio without seeds has a genre
+/// picked for it.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Radio {
+    pub name: String,
+    pub seeds: Vec<SeedId>,
+}
+
+impl Radio {
+    pub fn new<S: Display>(name: S, seeds: Vec<SeedId>) -> Self {
+        Self { name: name.to_string(), seeds }
+    }
+
+    /// Radio for a genre, or one to pick a genre for when none is given
+    pub fn genre(genre: Option<String>) -> Self {
+        match genre {
+            Some(genre) => Self::new(&genre, vec![SeedId::genre(&genre)]),
+            None => Self::default(),
+        }
+    }
+}
+
 pub mod action_label {
     pub static ADD_TO_PLAYLIST: &str = "Add to Playlist";
     pub static ADD_TO_QUEUE: &str = "Add to Queue";
+    pub static PLAY_NEXT: &str = "Play Next";
+    pub static QUEUE_ALL: &str = "Queue All";
+    pub static QUEUE_NEXT: &str = "Queue Next 10";
+    pub static QUEUE_SHUFFLED: &str = "Queue Shuffled";
 
     pub static PLAY: &str = "Play";
+    pub static PLAY_SELECTION: &str = "Play Selection";
+    pub static START_RADIO: &str = "Start Radio";
     pub static REMOVE: &str = "Remove";
     pub static SAVE: &str = "Save";
 
     pub static GO_TO_PLAYLIST: &str = "Go to Playlist";
     pub static PLAY_PLAYLIST: &str = "Play Playlist";
+    pub static NEW_PLAYLIST: &str = "New Playlist";
+    pub static EDIT_PLAYLIST: &str = "Edit Details";
+    pub static UPLOAD_COVER: &str = "Upload Cover";
+    pub static DELETE_PLAYLIST: &str = "Delete Playlist";
 
     pub static GO_TO_ALBUM: &str = "Go to Album";
     pub static PLAY_ALBUM: &str = "Play Album";
@@ -92,6 +147,7 @@ pub mod action_label {
     pub static PLAY_SHOW: &str = "Play Show";
 
     pub static GO_TO_ARTIST: &str = "Go to Artist";
+    pub static PLAY_ARTIST: &str = "Play Artist";
     pub static SELECT_ARTIST: &str = "Select an Artist";
 
     pub static GO_TO_AUDIOBOOK: &str = "Go to Audiobook";
@@ -106,12 +162,30 @@ pub enum Action {
     /// Saves the item to the library depending on the uri
     /// If the uri is for a context it is added to the library and if it is a track or episode it
     /// is added to the users liked/saved items
-    Save(Uri, Shared<dyn Fn(bool) -> Result<()> + Sync + Send>),
-    Remove(Uri, Shared<dyn Fn(bool) -> Result<()> + Sync + Send>),
+    Save(Uri),
+    /// Removes the item from the library. The callback is called with whether the item is still
+    /// saved, for lists of saved items that the item should be dropped from.
+    Remove(Uri, Option<Shared<dyn Fn(bool) -> Result<()> + Sync + Send>>),
     /// Opens the add to playlist modal with the uri of what is being added
     AddToPlaylist(Uri),
     /// Adds item to queue
     AddToQueue(Uri),
+    /// Puts the item first in the local queue
+    PlayNext(Uri),
+    /// Saves every item, batching the requests by the type of item
+    SaveAll(Vec<Uri>),
+    /// Removes every item from the library. The callback is the same as for `Remove`.
+    RemoveAll(Vec<Uri>, Option<Shared<dyn Fn(bool) -> Result<()> + Sync + Send>>),
+    /// Opens the add to playlist modal with every item being added
+    AddAllToPlaylist(Vec<Uri>),
+    /// Adds every item to the queue in order
+    AddAllToQueue(Vec<Uri>),
+    /// Adds the items of an album, playlist, show or artist to the queue
+    QueueContext(Uri, QueueMode),
+    /// Opens the form to create, edit or delete a playlist
+    ManagePlaylist(PlaylistForm),
+    /// Opens the form to tune a radio of recommendations before it is played
+    StartRadio(Radio),
 
     GoTo(GoTo),
 }
@@ -123,9 +197,17 @@ impl PartialEq for Action {
             Self::PlayContext(p1) => if let Action::PlayContext(p2) = other { p1.eq(p2) } else { false },
             Self::AddToPlaylist(u1) => if let Action::AddToPlaylist(u2) = other { u1.eq(u2) } else { false },
             Self::AddToQueue(u1) => if let Action::AddToQueue(u2) = other { u1.eq(u2) } else { false },
+            Self::PlayNext(u1) => if let Action::PlayNext(u2) = other { u1.eq(u2) } else { false },
             Self::GoTo(g1) => if let Action::GoTo(g2) = other { g1.eq(g2) } else { false },
-            Self::Save(u1, _) =>   if let Action::Save(u2, _) = other { u1.eq(u2) } else { false },
+            Self::Save(u1) =>   if let Action::Save(u2) = other { u1.eq(u2) } else { false },
             Self::Remove(u1, _) => if let Action::Remove(u2, _) = other { u1.eq(u2) } else { false },
+            Self::SaveAll(u1) => if let Action::SaveAll(u2) = other { u1.eq(u2) } else { false },
+            Self::RemoveAll(u1, _) => if let Action::RemoveAll(u2, _) = other { u1.eq(u2) } else { false },
+            Self::AddAllToPlaylist(u1) => if let Action::AddAllToPlaylist(u2) = other { u1.eq(u2) } else { false },
+            Self::AddAllToQueue(u1) => if let Action::AddAllToQueue(u2) = other { u1.eq(u2) } else { false },
+            Self::QueueContext(u1, m1) => if let Action::QueueContext(u2, m2) = other { u1.eq(u2) && m1.eq(m2) } else { false },
+            Self::ManagePlaylist(f1) => if let Action::ManagePlaylist(f2) = other { f1.eq(f2) } else { false },
+            Self::StartRadio(r1) => if let Action::StartRadio(r2) = other { r1.eq(r2) } else { false },
         }
     }
 }
@@ -137,27 +219,235 @@ impl Debug for Action {
             Self::PlayContext(p) => write!(f, "PlayContext({:?})", p),
             Self::AddToPlaylist(u) => write!(f, "AddToPlaylist({})", u),
             Self::AddToQueue(u) => write!(f, "AddToQueue({})", u),
+            Self::PlayNext(u) => write!(f, "PlayNext({})", u),
             Self::GoTo(g) => write!(f, "GoTo({:?})", g),
-            Self::Save(u, _) => write!(f, "Save({})", u),
+            Self::Save(u) => write!(f, "Save({})", u),
             Self::Remove(u, _) => write!(f, "Remove({})", u),
+            Self::SaveAll(u) => write!(f, "SaveAll({:?})", u),
+            Self::RemoveAll(u, _) => write!(f, "RemoveAll({:?})", u),
+            Self::AddAllToPlaylist(u) => write!(f, "AddAllToPlaylist({:?})", u),
+            Self::AddAllToQueue(u) => write!(f, "AddAllToQueue({:?})", u),
+            Self::QueueContext(u, mode) => write!(f, "QueueContext({}, {:?})", u, mode),
+            Self::ManagePlaylist(form) => write!(f, "ManagePlaylist({:?})", form),
+            Self::StartRadio(radio) => write!(f, "StartRadio({:?})", radio),
         }
     }
 }
 
 impl Action {
-    pub fn save<F>(uri: Uri, callback: F) -> Self
-    where
-        F: Fn(bool) -> Result<()> + Sync + Send + 'static
-    {
-        Self::Save(uri, Shared::new(callback))
-    } 
+    pub fn remove(uri: Uri) -> Self {
+        Self::Remove(uri, None)
+    }
 
-    pub fn remove<F>(uri: Uri, callback: F) -> Self
+    pub fn remove_from<F>(uri: Uri, callback: F) -> Self
     where
         F: Fn(bool) -> Result<()> + Sync + Send + 'static
     {
-        Self::Remove(uri, Shared::new(callback))
-    } 
+        Self::Remove(uri, Some(Shared::new(callback)))
+    }
+
+    /// Run the action, sending any events it leads to
+    pub fn run(&self, api: &Pkce, tx: &mpsc::UnboundedSender<Event>) {
+        match self {
+            // The local queue can play the item directly instead of skipping to it
+            Action::Play(uri) if local_queue::play_now(api, uri) => {
+                tx.send(Event::UpdateQueue).log_error();
+            }
+            Action::Play(play) => {
+                let api = api.clone();
+                let uri = play.clone();
+                spawn(async move {
+                    if api.token().is_expired() {
+                        api.refresh().await.log_error();
+                    }
+                    api.add_to_queue(uri, None).await.log_error();
+                    api.next(None).await.log_error();
+                });
+            }
+            Action::PlayContext(play) => tx.send(Event::Play(play.clone())).log_error(),
+            Action::Save(uri) => save(api, vec![uri.clone()], true, None),
+            Action::Remove(uri, callback) => save(api, vec![uri.clone()], false, callback.clone()),
+            Action::SaveAll(uris) => save(api, uris.clone(), true, None),
+            Action::RemoveAll(uris, callback) => save(api, uris.clone(), false, callback.clone()),
+            Action::AddToPlaylist(uri) => {
+                tx.send(Event::OpenAddToPlaylist(vec![uri.clone()])).log_error();
+            },
+            Action::AddAllToPlaylist(uris) => {
+                tx.send(Event::OpenAddToPlaylist(uris.clone())).log_error();
+            },
+            Action::AddToQueue(uri) if local_queue::is_active() => local_queue::add(api, tx, vec![uri.clone()], false),
+            Action::AddAllToQueue(uris) if local_queue::is_active() => local_queue::add(api, tx, uris.clone(), false),
+            Action::PlayNext(uri) => local_queue::add(api, tx, vec![uri.clone()], true),
+            Action::AddToQueue(uri) => {
+                let api = api.clone();
+                let uri = uri.clone();
+                spawn(async move {
+                    if api.token().is_expired() {
+                        api.refresh().await.log_error();
+                    }
+                    api.add_to_queue(uri, None).await.log_error();
+                });
+            },
+            Action::AddAllToQueue(uris) => {
+                let api = api.clone();
+                let uris = uris.clone();
+                spawn(async move {
+                    if api.token().is_expired() {
+                        api.refresh().await.log_error();
+                    }
+                    // Spotify only queues one item per request, so they are sent in order
+                    for uri in uris {
+                        if api.add_to_queue(uri, None).await.log_error_ok().is_none() {
+                            break;
+                        }
+                    }
+                });
+            },
+            Action::QueueContext(uri, mode) => enqueue::queue_context(api, tx, uri.clone(), *mode),
+            Action::ManagePlaylist(form) => {
+                tx.send(Event::OpenPlaylistForm(form.clone())).log_error();
+            },
+            Action::StartRadio(radio) => {
+                tx.send(Event::OpenRadio(radio.clone())).log_error();
+            },
+            Action::GoTo(goto) => {
+                tx.send(Event::GoTo(goto.clone())).log_error();
+            },
+        }
+    }
+}
+
+/// Save or remove the items, showing the change right away. The callback is called with whether
+/// the items are still saved, and again if the change has to be rolled back. Items that are
+/// removed can be saved again with undo.
+pub fn save(api: &Pkce, uris: Vec<Uri>, saved: bool, callback: Option<Shared<dyn Fn(bool) -> Result<()> + Sync + Send>>) {
+    for uri in uris.iter() {
+        library_index::set_saved(uri.clone(), saved);
+    }
+    if let Some(callback) = callback.as_ref() {
+        callback(saved).log_error();
+    }
+
+    let api = api.clone();
+    let inverse = callback.clone();
+    // Items whose request hasn't gone through, which are the only ones rolled back on failure
+    let pending = Shared::new(Locked::new(uris.clone()));
+    let unsent = pending.clone();
+    mutation::commit(
+        if saved { "save to your library" } else { "remove from your library" },
+        async move {
+            if api.token().is_expired() {
+                api.refresh().await.log_error();
+            }
+            let result = save_requests(&api, uris.clone(), saved, &pending).await;
+            if !saved {
+                let removed = {
+                    let pending = pending.lock().unwrap();
+                    uris.into_iter().filter(|uri| !pending.contains(uri)).collect::<Vec<_>>()
+                };
+                let what = match removed.len() {
+                    0 => None,
+                    1 => Some("Removed from your library".to_string()),
+                    n => Some(format!("Removed {n} items from your library")),
+                };
+                if let Some(what) = what {
+                    undo::push(what, Inverse::Save(removed, inverse));
+                }
+            }
+            result
+        },
+        move || {
+            for uri in unsent.lock().unwrap().drain(..) {
+                library_index::set_saved(uri, !saved);
+            }
+            if let Some(callback) = callback {
+                callback(!saved).log_error();
+            }
+        },
+    );
+}
+
+/// Send the requests to save or remove the items, batched by the type of item. Items are taken
+/// out of `pending` as their requests go through.
+async fn save_requests(api: &Pkce, uris: Vec<Uri>, saved: bool, pending: &Locked<Vec<Uri>>) -> Result<(), tupy::Error> {
+    let mut resources: HashMap<Resource, Vec<Uri>> = HashMap::new();
+    for uri in uris {
+        resources.entry(uri.resource()).or_default().push(uri);
+    }
+    let sent = |batch: &[Uri]| pending.lock().unwrap().retain(|uri| !batch.contains(uri));
+
+    for (resource, uris) in resources {
+        // Playlists can only be followed one at a time
+        if resource == Resource::Playlist {
+            for uri in uris {
+                if saved {
+                    api.follow_playlist(uri.clone(), true).await?;
+                } else {
+                    api.unfollow_playlist(uri.clone()).await?;
+                }
+                sent(&[uri]);
+            }
+            continue;
+        }
+
+        for batch in uris.chunks(library_index::batch_size(resource)) {
+            let ids = batch.iter().cloned();
+            match (resource, saved) {
+                (Resource::Track, true) => api.save_tracks(ids).await?,
+                (Resource::Track, false) => api.remove_saved_tracks(ids).await?,
+                (Resource::Episode, true) => api.save_episodes(ids).await?,
+                (Resource::Episode, false) => api.remove_saved_episodes(ids).await?,
+                (Resource::Artist, true) => api.follow_artists(ids).await?,
+                (Resource::Artist, false) => api.unfollow_artists(ids).await?,
+                (Resource::Album, true) => api.save_albums(ids).await?,
+                (Resource::Album, false) => api.remove_saved_albums(ids).await?,
+                (Resource::Show, true) => api.save_shows(ids).await?,
+                (Resource::Show, false) => api.remove_saved_shows(ids).await?,
+                _ => {}
+            }
+            sent(batch);
+        }
+    }
+    Ok(())
+}
+
+/// Action to play the item next, offered while the local queue is used
+pub fn play_next_action(uri: &Uri) -> Option<(KeyEvent, Action, &'static str)> {
+    local_queue::is_active().then(|| (key!('n'), Action::PlayNext(uri.clone()), action_label::PLAY_NEXT))
+}
+
+/// Actions for adding the items of an album, playlist, show or artist to the queue
+pub fn queue_actions(context: &Uri) -> [(KeyEvent, Action, &'static str); 3] {
+    [
+        (key!('b'), Action::QueueContext(context.clone(), QueueMode::All), action_label::QUEUE_ALL),
+        (key!('N' + SHIFT), Action::QueueContext(context.clone(), QueueMode::Next(QUEUE_NEXT)), action_label::QUEUE_NEXT),
+        (key!('S' + SHIFT), Action::QueueContext(context.clone(), QueueMode::Shuffled), action_label::QUEUE_SHUFFLED),
+    ]
+}
+
+/// Actions for the items marked in a table. `removed` is the callback for removing them, for
+/// lists of saved items that the rows should be dropped from.
+pub fn batch_actions(uris: Vec<Uri>, removed: Option<Shared<dyn Fn(bool) -> Result<()> + Sync + Send>>) -> Vec<(KeyEvent, Action, &'static str)> {
+    let playable = uris
+        .iter()
+        .filter(|uri| matches!(uri.resource(), Resource::Track | Resource::Episode))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let mut actions = Vec::new();
+    if !playable.is_empty() {
+        actions.extend([
+            (key!(Enter), Action::PlayContext(Play::queue(playable.clone())), action_label::PLAY_SELECTION),
+            (key!('p'), Action::AddAllToPlaylist(playable.clone()), action_label::ADD_TO_PLAYLIST),
+            (key!('b'), Action::AddAllToQueue(playable), action_label::ADD_TO_QUEUE),
+        ]);
+    }
+    // The marks can mix saved and unsaved items, so both are offered
+    actions.extend([
+        (key!('f'), Action::SaveAll(uris.clone()), action_label::SAVE),
+        (key!('r'), Action::RemoveAll(uris, removed), action_label::REMOVE),
+    ]);
+    actions
 }
 
 impl Display for Action {
@@ -177,9 +467,26 @@ impl Display for Action {
                 }
             ),
             Self::Remove(_, _) => write!(f, "Remove Favorite"),
-            Self::Save(_, _) => write!(f, "Favorite"),
+            Self::Save(_) => write!(f, "Favorite"),
             Self::AddToPlaylist(_) => write!(f, "Add to Playlist"),
             Self::AddToQueue(_) => write!(f, "Add to Queue"),
+            Self::PlayNext(_) => write!(f, "Play Next"),
+            Self::SaveAll(u) => write!(f, "Favorite {} Items", u.len()),
+            Self::RemoveAll(u, _) => write!(f, "Remove {} Favorites", u.len()),
+            Self::AddAllToPlaylist(u) => write!(f, "Add {} Items to Playlist", u.len()),
+            Self::AddAllToQueue(u) => write!(f, "Add {} Items to Queue", u.len()),
+            Self::QueueContext(_, mode) => write!(f, "{mode}"),
+            Self::ManagePlaylist(form) => write!(
+                f,
+                "{}",
+                match form {
+                    PlaylistForm::Create => action_label::NEW_PLAYLIST,
+                    PlaylistForm::Edit(_) => action_label::EDIT_PLAYLIST,
+                    PlaylistForm::Cover(_) => action_label::UPLOAD_COVER,
+                    PlaylistForm::Delete(..) => action_label::DELETE_PLAYLIST,
+                }
+            ),
+            Self::StartRadio(_) => write!(f, "{}", action_label::START_RADIO),
             Self::GoTo(go_to) => write!(f, "Go to {}", go_to),
         }
     }
@@ -220,6 +527,15 @@ impl IntoActions for SimplifiedAlbum {
                 action_label::SELECT_ARTIST,
             ))
         }
+        // Albums can't seed recommendations, so the radio is built from their artists
+        actions.push((
+            key!('R'),
+            Action::StartRadio(Radio::new(
+                &self.name,
+                self.artists.iter().take(MAX_SEEDS).map(|a| SeedId::artist(&a.id)).collect(),
+            )),
+            action_label::START_RADIO,
+        ));
 
         actions
     }
@@ -257,6 +573,12 @@ impl IntoActions for Track {
                 )
             },
         ];
+        actions.extend(play_next_action(&self.uri));
+        actions.push((
+            key!('R'),
+            Action::StartRadio(Radio::new(&self.name, vec![SeedId::track(&self.id)])),
+            action_label::START_RADIO,
+        ));
 
         if context {
             if self.album.total_tracks > 1 {
@@ -277,9 +599,60 @@ impl IntoActions for Track {
     }
 }
 
+impl IntoActions for Artist {
+    fn into_actions(&self, context: bool) -> Vec<(KeyEvent, Action, &'static str)> {
+        let mut actions = Vec::new();
+        if context {
+            actions.push((
+                key!('c'),
+                Action::PlayContext(Play::artist(self.uri.clone())),
+                action_label::PLAY_ARTIST,
+            ));
+        }
+        actions.push((
+            key!('C'),
+            Action::GoTo(GoTo::Artist(self.uri.clone())),
+            action_label::GO_TO_ARTIST,
+        ));
+        actions.push((
+            key!('R'),
+            Action::StartRadio(Radio::new(&self.name, vec![SeedId::artist(&self.id)])),
+            action_label::START_RADIO,
+        ));
+        actions
+    }
+}
+
+impl IntoActions for PlayHistory {
+    fn into_actions(&self, context: bool) -> Vec<(KeyEvent, Action, &'static str)> {
+        let mut actions = self.track.into_actions(false);
+
+        if context {
+            if self.track.album.total_tracks > 1 {
+                actions.push((
+                    key!('c'),
+                    Action::PlayContext(Play::album(self.track.album.uri.clone(), None, 0)),
+                    action_label::PLAY_ALBUM,
+                ));
+            }
+            // Prefer the context the track was played from, falling back to its album
+            match self.context.as_ref().and_then(|c| GoTo::try_from(c.uri.clone()).ok()) {
+                Some(goto) => actions.push((key!('C'), Action::GoTo(goto), action_label::GO_TO_CONTEXT)),
+                None => actions.push((
+                    key!('C'),
+                    Action::GoTo(GoTo::Album(self.track.album.uri.clone())),
+                    action_label::GO_TO_ALBUM,
+                )),
+            }
+        }
+
+        actions
+    }
+}
+
 impl IntoActions for SimplifiedTrack {
     fn into_actions(&self, _: bool) -> Vec<(KeyEvent, Action, &'static str)> {
-        let actions = vec![
+        let mut actions = vec![
             (
                 key!('p'),
                 Action::AddToPlaylist(self.uri.clone()),
@@ -309,6 +682,12 @@ impl IntoActions for SimplifiedTrack {
                 )
             },
         ];
+        actions.extend(play_next_action(&self.uri));
+        actions.push((
+            key!('R'),
+            Action::StartRadio(Radio::new(&self.name, vec![SeedId::track(&self.id)])),
+            action_label::START_RADIO,
+        ));
 
         actions
     }
@@ -316,7 +695,7 @@ impl IntoActions for SimplifiedTrack {
 
 impl IntoActions for SimplifiedEpisode {
     fn into_actions(&self, _: bool) -> Vec<(KeyEvent, Action, &'static str)> {
-        vec![
+        let mut actions = vec![
             (
                 key!('p'),
                 Action::AddToPlaylist(self.uri.clone()),
@@ -327,7 +706,9 @@ impl IntoActions for SimplifiedEpisode {
                 Action::AddToQueue(self.uri.clone()),
                 action_label::ADD_TO_QUEUE,
             ),
-        ]
+        ];
+        actions.extend(play_next_action(&self.uri));
+        actions
     }
 }
 
@@ -345,6 +726,7 @@ impl IntoActions for Episode {
                 action_label::ADD_TO_QUEUE,
             ),
         ];
+        actions.extend(play_next_action(&self.uri));
 
         if context {
             if let Some(show) = self.show.as_ref() {
@@ -402,20 +784,19 @@ impl GetUri for Item {
     }
 }
 
-impl IntoActions for PlaybackState {
-    fn into_actions(&self, context: bool) -> Vec<(KeyEvent, Action, &'static str)> {
+impl PlaybackState {
+    /// Actions for the item that is playing
+    pub fn into_actions(&self, context: bool) -> Vec<(KeyEvent, Action, &'static str)> {
         if let Some(pb) = self.playback.as_ref() {
             match &pb.item {
                 PlaybackItem::Track(t) => {
                     let mut actions = vec![
-                        // TODO: Wrap the playback fetching on if it is saved. If it has the
-                        // functionality then add the action to save/remove it from saved items
-                        if !pb.saved {
-                            (key!('f'), Action::save(t.uri.clone(), |saved| Ok(())), action_label::SAVE)
+                        if !self.saved() {
+                            (key!('f'), Action::Save(t.uri.clone()), action_label::SAVE)
                         } else {
                             (
                                 key!('r'),
-                                Action::remove(t.uri.clone(), |saved| Ok(())),
+                                Action::remove(t.uri.clone()),
                                 action_label::REMOVE,
                             )
                         },
@@ -453,12 +834,12 @@ impl IntoActions for PlaybackState {
                 }
                 PlaybackItem::Episode(e) => {
                     let mut actions = vec![
-                        if !pb.saved {
-                            (key!('f'), Action::save(e.uri.clone(), |saved| Ok(())), action_label::SAVE)
+                        if !self.saved() {
+                            (key!('f'), Action::Save(e.uri.clone()), action_label::SAVE)
                         } else {
                             (
                                 key!('r'),
-                                Action::remove(e.uri.clone(), |saved| Ok(())),
+                                Action::remove(e.uri.clone()),
                                 action_label::REMOVE,
                             )
                         },
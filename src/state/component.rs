@@ -0,0 +1,123 @@
+use color_eyre::Result;
+use crossterm::event::KeyEvent;
+use ratatui::{buffer::Buffer, layout::Rect};
+use tokio::sync::mpsc;
+use tupy::api::flow::Pkce;
+
+use crate::app::Event;
+
+use super::{actions::Action, modal::{ActionState, ModalState}, Click, Modal};
+
+/// Resources a component can use while handling input
+pub struct Context<'a> {
+    pub api: &'a Pkce,
+    pub tx: &'a mpsc::UnboundedSender<Event>,
+    pub modals: &'a ModalState,
+}
+
+/// What the focus stack should do after a component handles input
+#[derive(Debug, Clone, PartialEq, strum_macros::EnumIs)]
+pub enum Handled {
+    /// The component doesn't handle the input
+    Ignored,
+    Consumed,
+    /// The input was handled and the component should be closed
+    Close,
+    /// The input was handled and the modal should be opened over the component
+    Open(Modal),
+}
+
+/// A window or modal that handles its own input and rendering
+///
+/// Components are kept in a focus stack with the current window at the bottom and opened modals
+/// on top of it. Input is routed to the component at the top of the stack.
+pub trait Component {
+    /// Handle a key before it is mapped to an event
+    fn key(&mut self, _key: KeyEvent, _ctx: &Context) -> Handled {
+        Handled::Ignored
+    }
+
+    /// Handle a navigation event: `Up`, `Down`, `Left`, `Right`, `Tab`, `Backtab`, `Select` or `Close`
+    fn event(&mut self, _event: &Event, _ctx: &Context) -> Result<Handled> {
+        Ok(Handled::Ignored)
+    }
+
+    /// Handle a left click at the position
+    fn mouse(&mut self, _column: u16, _row: u16) -> Click {
+        Click::Miss
+    }
+
+    /// True if the component takes typed text, so keys like `q` are typed instead of closing it
+    fn text_input(&self) -> bool {
+        false
+    }
+
+    /// Called when the component is brought into focus
+    fn focus(&mut self) {}
+
+    /// Fetch the component's data again. Returns false to pass the refresh to the component
+    /// below it in the focus stack.
+    fn refresh(&mut self, _ctx: &Context) -> bool {
+        true
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer);
+}
+
+/// Modals that may not have been opened yet, such as adding to a playlist, are stored as options
+impl<C: Component> Component for Option<C> {
+    fn key(&mut self, key: KeyEvent, ctx: &Context) -> Handled {
+        match self {
+            Some(component) => component.key(key, ctx),
+            None => Handled::Ignored,
+        }
+    }
+
+    fn event(&mut self, event: &Event, ctx: &Context) -> Result<Handled> {
+        match self {
+            Some(component) => component.event(event, ctx),
+            None => Ok(Handled::Ignored),
+        }
+    }
+
+    fn mouse(&mut self, column: u16, row: u16) -> Click {
+        match self {
+            Some(component) => component.mouse(column, row),
+            None => Click::Miss,
+        }
+    }
+
+    fn text_input(&self) -> bool {
+        self.as_ref().is_some_and(|component| component.text_input())
+    }
+
+    fn focus(&mut self) {
+        if let Some(component) = self {
+            component.focus();
+        }
+    }
+
+    fn refresh(&mut self, ctx: &Context) -> bool {
+        match self {
+            Some(component) => component.refresh(ctx),
+            None => true,
+        }
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        if let Some(component) = self {
+            component.render(area, buf);
+        }
+    }
+}
+
+/// Open the action modal for the selected item if there is one
+pub fn open_actions(actions: Option<Vec<(KeyEvent, Action, &'static str)>>, ctx: &Context) -> Handled {
+    match actions {
+        Some(actions) => {
+            *ctx.modals.actions.lock().unwrap() = ActionState::new(actions);
+            Handled::Open(Modal::Action)
+        }
+        None => Handled::Consumed,
+    }
+}
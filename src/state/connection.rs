@@ -0,0 +1,46 @@
+use crate::Locked;
+
+lazy_static::lazy_static! {
+    // Connection reported by a request made away from the app state, applied on the next tick
+    static ref REPORTED: Locked<Option<Connection>> = Locked::default();
+}
+
+/// Whether spotify could be reached by the last request that was checked
+#[derive(Debug, Default, Clone, PartialEq, strum_macros::EnumIs)]
+pub enum Connection {
+    #[default]
+    Online,
+    /// Spotify couldn't be reached, with the reason
+    Offline(String),
+    /// Trying to reach spotify again after being offline
+    Retrying,
+}
+
+impl Connection {
+    /// Update the connection from the error of a request, going back online if spotify was reached
+    pub fn report(&mut self, error: Option<&tupy::Error>) {
+        *self = match error {
+            Some(tupy::Error::Connection(message)) => Self::Offline(message.clone()),
+            // Any other error is a response from spotify
+            _ => Self::Online,
+        };
+    }
+
+    /// Same as [`Connection::report`] for errors that may wrap a request error
+    pub fn report_result<T>(&mut self, result: &color_eyre::Result<T>) {
+        self.report(result.as_ref().err().and_then(|e| e.downcast_ref::<tupy::Error>()));
+    }
+}
+
+/// Report the error of a request made where the app state can't be reached, such as a window
+/// fetching a page in the background
+pub fn report_later(error: &tupy::Error) {
+    let mut connection = Connection::Online;
+    connection.report(Some(error));
+    *REPORTED.lock().unwrap() = Some(connection);
+}
+
+/// Connection reported since the last time this was called
+pub fn take_reported() -> Option<Connection> {
+    REPORTED.lock().unwrap().take()
+}
@@ -0,0 +1,173 @@
+use std::fmt::Display;
+
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use tupy::{
+    api::{
+        flow::{AuthFlow, Pkce},
+        response::{Paged, Paginated},
+        PublicApi, Resource, Uri, UserApi,
+    },
+    Pagination,
+};
+
+use crate::{app::Event, errors::LogError, render, tasks::{self, Scope}, Locked};
+
+use super::{local_queue, mutation, wrappers::GetUri};
+
+/// Most items fetched in one request while paging through a context
+const FETCH_SIZE: usize = 50;
+
+/// Items added by "Queue Next"
+pub const QUEUE_NEXT: usize = 10;
+
+lazy_static::lazy_static! {
+    // Context being added to the queue
+    static ref PROGRESS: Locked<Option<Progress>> = Locked::default();
+}
+
+/// How much of a context is added to the queue
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QueueMode {
+    All,
+    /// Only the first items of the context
+    Next(usize),
+    /// Every item in a random order
+    Shuffled,
+}
+
+impl Display for QueueMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::All => write!(f, "Queue All"),
+            Self::Next(n) => write!(f, "Queue Next {n}"),
+            Self::Shuffled => write!(f, "Queue Shuffled"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Progress {
+    added: usize,
+    /// Items being added, unknown until the context is fetched
+    total: Option<usize>,
+}
+
+/// Add the items of an album, playlist, show or the top tracks of an artist to the queue in the
+/// background. Only one context is added at a time and it can be stopped with `cancel`.
+pub fn queue_context(api: &Pkce, tx: &mpsc::UnboundedSender<Event>, context: Uri, mode: QueueMode) {
+    {
+        let mut progress = PROGRESS.lock().unwrap();
+        if progress.is_some() {
+            mutation::notify("Already adding to the queue, press esc to stop".into());
+            return;
+        }
+        *progress = Some(Progress { added: 0, total: None });
+    }
+    render::mark_dirty();
+
+    let api = api.clone();
+    let tx = tx.clone();
+    tasks::spawn_in(Scope::Enqueue, async move {
+        let result = add_context(&api, &tx, &context, mode).await;
+        let added = PROGRESS.lock().unwrap().take().map(|p| p.added).unwrap_or_default();
+        match result {
+            // The local queue reports the items it added itself
+            Ok(()) if local_queue::is_active() => {}
+            Ok(()) => mutation::notify(format!("Added {added} items to the queue")),
+            Err(error) => {
+                mutation::notify(format!("Couldn't add to the queue after {added} items: {error}"));
+                Err::<(), _>(error).log_error();
+            }
+        }
+    });
+}
+
+/// Stop adding a context to the queue. Items that were already added stay in the queue.
+pub fn cancel() {
+    let Some(progress) = PROGRESS.lock().unwrap().take() else {
+        return;
+    };
+    tasks::cancel(Scope::Enqueue);
+    mutation::notify(format!("Stopped adding to the queue after {} items", progress.added));
+}
+
+/// Progress of the context being added to the queue, if there is one
+pub fn progress() -> Option<String> {
+    PROGRESS.lock().unwrap().as_ref().map(|progress| match progress.total {
+        Some(total) => format!("Adding to the queue {}/{total}, press esc to stop", progress.added),
+        None => "Fetching items to add to the queue, press esc to stop".to_string(),
+    })
+}
+
+async fn add_context(api: &Pkce, tx: &mpsc::UnboundedSender<Event>, context: &Uri, mode: QueueMode) -> Result<(), tupy::Error> {
+    if api.token().is_expired() {
+        api.refresh().await.log_error();
+    }
+
+    let limit = match mode {
+        QueueMode::Next(n) => n,
+        QueueMode::All | QueueMode::Shuffled => usize::MAX,
+    };
+    let mut uris = match context.resource() {
+        Resource::Album => collect(api.album_tracks::<FETCH_SIZE, _, _>(context.id(), None)?, |t| Some(t.uri.clone()), limit).await?,
+        Resource::Show => collect(api.show_episodes::<FETCH_SIZE, _, _>(context.id(), None)?, |e| Some(e.uri.clone()), limit).await?,
+        // Local files can't be queued
+        Resource::Playlist => collect(
+            api.playlist_items::<FETCH_SIZE, _, _>(context.id(), None)?,
+            |i| (!i.is_local).then(|| i.get_uri()),
+            limit,
+        )
+        .await?,
+        Resource::Artist => api
+            .artist_top_tracks(context.id(), None)
+            .await?
+            .into_iter()
+            .map(|t| t.uri)
+            .take(limit)
+            .collect(),
+        _ => Vec::new(),
+    };
+    if mode == QueueMode::Shuffled {
+        uris.shuffle(&mut rand::thread_rng());
+    }
+
+    if local_queue::is_active() {
+        local_queue::add(api, tx, uris, false);
+        return Ok(());
+    }
+
+    if let Some(progress) = PROGRESS.lock().unwrap().as_mut() {
+        progress.total = Some(uris.len());
+    }
+    render::mark_dirty();
+
+    // Spotify only queues one item per request, so they are sent in order
+    for uri in uris {
+        api.add_to_queue(uri, None).await?;
+        if let Some(progress) = PROGRESS.lock().unwrap().as_mut() {
+            progress.added += 1;
+        }
+        render::mark_dirty();
+    }
+    Ok(())
+}
+
+/// Page through the context, keeping the uri of every item that can be queued up to the limit
+async fn collect<R, P, F, const N: usize>(mut pager: Paginated<R, P, Pkce, N>, uri: F, limit: usize) -> Result<Vec<Uri>, tupy::Error>
+where
+    R: Paged,
+    P: Deserialize<'static>,
+    F: Fn(&R::Item) -> Option<Uri>,
+{
+    let mut uris = Vec::new();
+    while uris.len() < limit {
+        let Some(page) = pager.next().await? else {
+            break;
+        };
+        uris.extend(page.items().iter().filter_map(&uri));
+    }
+    uris.truncate(limit);
+    Ok(uris)
+}
@@ -0,0 +1,131 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use tupy::api::{flow::Pkce, PublicApi, Resource, Uri, UserApi};
+
+use crate::Locked;
+
+/// How long a saved status is trusted before it is checked with spotify again
+static EXPIRY: Duration = Duration::from_secs(10 * 60);
+
+lazy_static::lazy_static! {
+    // Whether items are saved or followed, shared by every view
+    pub static ref LIBRARY_INDEX: Locked<LibraryIndex> = Locked::default();
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Status {
+    saved: bool,
+    checked: Instant,
+    /// Version of the index when the status was set
+    version: u64,
+}
+
+/// Saved tracks, episodes, albums and shows, and followed artists and playlists, by uri
+#[derive(Debug, Default)]
+pub struct LibraryIndex {
+    statuses: HashMap<Uri, Status>,
+    /// Counts every status that was set, so a check can tell what changed while it ran
+    version: u64,
+}
+
+impl LibraryIndex {
+    /// Whether the item is saved or followed. Expired statuses are still returned until they
+    /// are checked again so the ♥ doesn't flicker.
+    pub fn get(&self, uri: &Uri) -> Option<bool> {
+        self.statuses.get(uri).map(|status| status.saved)
+    }
+
+    pub fn set(&mut self, uri: Uri, saved: bool) {
+        self.version += 1;
+        self.statuses.insert(uri, Status { saved, checked: Instant::now(), version: self.version });
+    }
+
+    /// Set the status from a check started at the version, unless the item was saved or removed
+    /// since then
+    fn set_checked(&mut self, uri: Uri, saved: bool, started: u64) {
+        if self.statuses.get(&uri).is_some_and(|status| status.version > started) {
+            return;
+        }
+        self.set(uri, saved);
+    }
+
+    /// Uris that were never checked or whose status has expired, without duplicates
+    fn unchecked<I: IntoIterator<Item = Uri>>(&self, uris: I) -> Vec<Uri> {
+        let mut unchecked = Vec::new();
+        for uri in uris {
+            let expired = self
+                .statuses
+                .get(&uri)
+                .map(|status| status.checked.elapsed() >= EXPIRY)
+                .unwrap_or(true);
+            if expired && !unchecked.contains(&uri) {
+                unchecked.push(uri);
+            }
+        }
+        unchecked
+    }
+}
+
+/// Whether the item is saved or followed, false if it hasn't been checked yet
+pub fn is_saved(uri: &Uri) -> bool {
+    LIBRARY_INDEX.lock().unwrap().get(uri).unwrap_or(false)
+}
+
+pub fn set_saved(uri: Uri, saved: bool) {
+    LIBRARY_INDEX.lock().unwrap().set(uri, saved);
+}
+
+/// Most ids spotify accepts in one request to check, save or remove items
+pub fn batch_size(resource: Resource) -> usize {
+    match resource {
+        Resource::Album => 20,
+        _ => 50,
+    }
+}
+
+/// Check if the items are saved or followed, only requesting the ones that were never checked or
+/// have expired. Requests are batched by the type of item. Items saved or removed while the check
+/// runs keep that status.
+pub async fn check<I: IntoIterator<Item = Uri>>(api: &Pkce, uris: I) -> Result<(), tupy::Error> {
+    let (unchecked, started) = {
+        let index = LIBRARY_INDEX.lock().unwrap();
+        (index.unchecked(uris), index.version)
+    };
+
+    let mut resources: HashMap<Resource, Vec<Uri>> = HashMap::new();
+    for uri in unchecked {
+        resources.entry(uri.resource()).or_default().push(uri);
+    }
+
+    for (resource, uris) in resources {
+        // Playlists can only be checked one at a time
+        if resource == Resource::Playlist {
+            for uri in uris {
+                let followed = api.check_follow_playlist(uri.clone()).await?;
+                LIBRARY_INDEX.lock().unwrap().set_checked(uri, followed, started);
+            }
+            continue;
+        }
+
+        for batch in uris.chunks(batch_size(resource)) {
+            let ids = batch.iter().cloned();
+            let saved = match resource {
+                Resource::Track => api.check_saved_tracks(ids).await?,
+                Resource::Episode => api.check_saved_episodes(ids).await?,
+                Resource::Album => api.check_saved_albums(ids).await?,
+                Resource::Show => api.check_saved_shows(ids).await?,
+                Resource::Artist => api.check_follow_artists(ids).await?,
+                _ => continue,
+            };
+
+            let mut index = LIBRARY_INDEX.lock().unwrap();
+            for (uri, saved) in batch.iter().cloned().zip(saved) {
+                index.set_checked(uri, saved, started);
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,273 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+use tupy::{
+    api::{flow::Pkce, UserApi},
+    Local, Pagination,
+};
+
+use crate::{errors::LogError, Locked};
+
+/// Most items spotify returns in one page of the library
+const SYNC_PAGE_SIZE: usize = 50;
+
+lazy_static::lazy_static! {
+    pub static ref LIBRARY_FILE_PATH: PathBuf = dirs::data_local_dir().unwrap().join("rataify").join("library.json");
+    // Everything saved in the library, loaded from the last sync so it can be searched offline
+    pub static ref LOCAL_LIBRARY: Locked<LocalLibrary> = Locked::new(LocalLibrary::load().log_error_ok().flatten().unwrap_or_default());
+}
+
+// Only one sync runs at a time
+static SYNCING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LocalKind {
+    Track,
+    Album,
+    Show,
+    Audiobook,
+    Artist,
+    Playlist,
+}
+
+impl LocalKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Track => "Track",
+            Self::Album => "Album",
+            Self::Show => "Show",
+            Self::Audiobook => "Audiobook",
+            Self::Artist => "Artist",
+            Self::Playlist => "Playlist",
+        }
+    }
+}
+
+/// Item saved in the library
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocalItem {
+    pub kind: LocalKind,
+    pub uri: String,
+    pub name: String,
+    /// Artists, owner or publisher shown next to the name
+    pub detail: String,
+    /// Unix timestamp of when it was saved, for the items spotify reports it for
+    pub added_at: Option<i64>,
+}
+
+/// Local copy of everything saved in the library
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LocalLibrary {
+    /// Items of each kind, most recently saved first
+    pub items: HashMap<LocalKind, Vec<LocalItem>>,
+    /// Unix timestamp of the last sync that finished
+    pub synced_at: Option<i64>,
+}
+
+impl LocalLibrary {
+    /// Load the library from the last sync if there is one
+    pub fn load() -> Result<Option<Self>> {
+        if !LIBRARY_FILE_PATH.exists() {
+            return Ok(None);
+        }
+        let library = std::fs::read_to_string(LIBRARY_FILE_PATH.as_path())?;
+        Ok(Some(serde_json::from_str(&library)?))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = LIBRARY_FILE_PATH.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(LIBRARY_FILE_PATH.as_path(), serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// When the most recent item of the kind was saved
+    fn newest(&self, kind: LocalKind) -> Option<i64> {
+        self.items.get(&kind).and_then(|items| items.first()).and_then(|item| item.added_at)
+    }
+
+    fn replace(&mut self, kind: LocalKind, items: Vec<LocalItem>) {
+        self.items.insert(kind, items);
+    }
+
+    /// Add items saved since the last sync. Returns false if the library no longer has as many
+    /// items as spotify reports, meaning some were removed and the kind has to be fetched again.
+    fn prepend(&mut self, kind: LocalKind, mut items: Vec<LocalItem>, total: usize) -> bool {
+        let known = self.items.entry(kind).or_default();
+        known.retain(|item| !items.iter().any(|i| i.uri == item.uri));
+        items.append(known);
+        let complete = items.len() == total;
+        *known = items;
+        complete
+    }
+
+    /// Items matching the query, best match first
+    pub fn search(&self, query: &str, limit: usize) -> Vec<LocalItem> {
+        self.search_kind(None, query, limit)
+    }
+
+    /// Items of the kind matching the query, or items of any kind if there is none
+    pub fn search_kind(&self, kind: Option<LocalKind>, query: &str, limit: usize) -> Vec<LocalItem> {
+        let mut matches = self
+            .items
+            .iter()
+            .filter(|(k, _)| kind.is_none_or(|kind| **k == kind))
+            .flat_map(|(_, items)| items)
+            .filter_map(|item| {
+                let text = format!("{} {}", item.name, item.detail);
+                fuzzy_score(query, &text).map(|score| (score, text.len(), item))
+            })
+            .collect::<Vec<_>>();
+        // Shorter names win ties since more of them was matched
+        matches.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        matches.into_iter().take(limit).map(|(_, _, item)| item.clone()).collect()
+    }
+}
+
+/// Score how well the query matches the text, or None if the characters of the query don't all
+/// appear in the text in order. Consecutive characters and characters starting a word score
+/// higher, and gaps between them score lower.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let text = text.to_lowercase().chars().collect::<Vec<_>>();
+    let mut score = 0;
+    let mut position = 0;
+    let mut last: Option<usize> = None;
+
+    for c in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = position + text[position..].iter().position(|t| *t == c)?;
+        score += 1;
+        if last.is_some_and(|last| last + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        score -= (found - position).min(5) as i64;
+
+        last = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
+
+/// Fetch pages of the library newest first, stopping at the first item that was saved before
+/// `newest`
+macro_rules! fetch {
+    ($pager: expr, $newest: expr, $map: expr) => {{
+        let mut pager = $pager;
+        let mut items = Vec::new();
+        let mut total = 0;
+        'pages: while let Some(page) = pager.next().await? {
+            total = page.total;
+            for item in page.items {
+                let item: LocalItem = ($map)(item);
+                if let (Some(newest), Some(added_at)) = ($newest, item.added_at) {
+                    if added_at <= newest {
+                        break 'pages;
+                    }
+                }
+                items.push(item);
+            }
+        }
+        (items, total)
+    }};
+}
+
+/// Sync a kind of item, only fetching what was saved since the last sync if spotify reports when
+/// items were saved
+macro_rules! sync_kind {
+    ($kind: expr, $pager: expr, $map: expr) => {{
+        let newest = LOCAL_LIBRARY.lock().unwrap().newest($kind);
+        let (items, total) = fetch!($pager, newest, $map);
+        let complete = match newest {
+            Some(_) => LOCAL_LIBRARY.lock().unwrap().prepend($kind, items, total),
+            None => {
+                LOCAL_LIBRARY.lock().unwrap().replace($kind, items);
+                true
+            }
+        };
+
+        // Items were removed since the last sync, so everything is fetched again
+        if !complete {
+            let (items, _) = fetch!($pager, None::<i64>, $map);
+            LOCAL_LIBRARY.lock().unwrap().replace($kind, items);
+        }
+    }};
+}
+
+fn names<'a, I: IntoIterator<Item = &'a String>>(names: I) -> String {
+    names.into_iter().map(String::as_str).collect::<Vec<_>>().join(", ")
+}
+
+/// Sync everything saved in the library into the local library and save it to the data dir.
+/// Does nothing if a sync is already running. Only request errors are returned, failing to save
+/// the library is logged.
+pub async fn sync(api: &Pkce) -> Result<(), tupy::Error> {
+    if SYNCING.swap(true, Ordering::SeqCst) {
+        return Ok(());
+    }
+    let result = sync_all(api).await;
+    SYNCING.store(false, Ordering::SeqCst);
+    result
+}
+
+async fn sync_all(api: &Pkce) -> Result<(), tupy::Error> {
+    sync_kind!(LocalKind::Track, api.saved_tracks::<SYNC_PAGE_SIZE, _>(None)?, |saved: tupy::api::response::SavedTrack| LocalItem {
+        kind: LocalKind::Track,
+        uri: saved.track.uri.to_string(),
+        name: saved.track.name,
+        detail: names(saved.track.artists.iter().map(|a| &a.name)),
+        added_at: Some(saved.added_at.timestamp()),
+    });
+    sync_kind!(LocalKind::Album, api.saved_albums::<SYNC_PAGE_SIZE, _>(None)?, |saved: tupy::api::response::SavedAlbum| LocalItem {
+        kind: LocalKind::Album,
+        uri: saved.album.uri.to_string(),
+        name: saved.album.name,
+        detail: names(saved.album.artists.iter().map(|a| &a.name)),
+        added_at: Some(saved.added_at.timestamp()),
+    });
+    sync_kind!(LocalKind::Show, api.saved_shows::<SYNC_PAGE_SIZE>()?, |saved: tupy::api::response::SavedShow| LocalItem {
+        kind: LocalKind::Show,
+        uri: saved.show.uri.to_string(),
+        name: saved.show.name,
+        detail: saved.show.publisher.unwrap_or_default(),
+        added_at: Some(saved.added_at.timestamp()),
+    });
+    sync_kind!(LocalKind::Audiobook, api.saved_audiobooks::<SYNC_PAGE_SIZE>()?, |audiobook: tupy::api::response::Audiobook| LocalItem {
+        kind: LocalKind::Audiobook,
+        uri: audiobook.uri.to_string(),
+        name: audiobook.name,
+        detail: names(&audiobook.authors),
+        added_at: None,
+    });
+    sync_kind!(LocalKind::Artist, api.followed_artists::<SYNC_PAGE_SIZE>()?, |artist: tupy::api::response::Artist| LocalItem {
+        kind: LocalKind::Artist,
+        uri: artist.uri.to_string(),
+        name: artist.name,
+        detail: String::new(),
+        added_at: None,
+    });
+    sync_kind!(LocalKind::Playlist, api.playlists::<SYNC_PAGE_SIZE, _>(None)?, |playlist: tupy::api::response::SimplifiedPlaylist| LocalItem {
+        kind: LocalKind::Playlist,
+        uri: playlist.uri.to_string(),
+        name: playlist.name,
+        detail: playlist.owner.name.unwrap_or(playlist.owner.id),
+        added_at: None,
+    });
+
+    let library = {
+        let mut library = LOCAL_LIBRARY.lock().unwrap();
+        library.synced_at = Some(Local::now().timestamp());
+        library.clone()
+    };
+    library.save().log_error();
+    Ok(())
+}
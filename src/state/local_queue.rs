@@ -0,0 +1,316 @@
+use tokio::sync::mpsc;
+use tupy::{
+    api::{
+        flow::{AuthFlow, Pkce},
+        request::Play,
+        response::Item,
+        PublicApi, Resource, Uri, UserApi,
+    },
+    DateTime, Duration, Local,
+};
+
+use crate::{app::Event, errors::LogError, tasks::spawn, Locked};
+
+use super::{mutation, playback::PlaybackState, wrappers::GetUri};
+
+/// Seconds spotify is given to report an item the queue started before something else playing
+/// counts as a change made from another client
+static START_GRACE: i64 = 5;
+
+/// Seconds before its expected end that an item can stop and still count as finished
+static END_TOLERANCE: i64 = 3;
+
+/// Most tracks or episodes fetched in one request
+static FETCH_SIZE: usize = 50;
+
+lazy_static::lazy_static! {
+    // Queue kept by rataify, only set while it drives playback instead of spotify's queue
+    static ref LOCAL_QUEUE: Locked<Option<LocalQueue>> = Locked::default();
+}
+
+/// Upcoming items kept by rataify. Spotify's queue can only be appended to, so the items are
+/// started one at a time as the item before them ends.
+#[derive(Debug)]
+struct LocalQueue {
+    items: Vec<Item>,
+    /// Item the queue continues after. Whatever plays next is followed while this isn't known.
+    playing: Option<Uri>,
+    /// When the item playing should end, unknown while it is paused
+    ends: Option<DateTime<Local>>,
+    /// When the queue last changed what plays
+    changed: DateTime<Local>,
+}
+
+impl LocalQueue {
+    fn new(items: Vec<Item>) -> Self {
+        Self {
+            items,
+            playing: None,
+            ends: None,
+            changed: Local::now(),
+        }
+    }
+
+    /// Take the item at the index out of the queue to be played
+    fn start(&mut self, index: usize) -> Uri {
+        let uri = self.items.remove(index).get_uri();
+        self.playing = Some(uri.clone());
+        self.ends = None;
+        self.changed = Local::now();
+        uri
+    }
+}
+
+/// Whether rataify's queue drives playback instead of spotify's
+pub fn is_active() -> bool {
+    LOCAL_QUEUE.lock().unwrap().is_some()
+}
+
+/// Items in the local queue, if it is being used
+pub fn items() -> Option<Vec<Item>> {
+    LOCAL_QUEUE.lock().unwrap().as_ref().map(|queue| queue.items.clone())
+}
+
+/// Switch between spotify's queue and the local queue. The local queue starts with the items
+/// that were in spotify's queue.
+pub fn toggle(items: Vec<Item>) {
+    let mut queue = LOCAL_QUEUE.lock().unwrap();
+    *queue = match queue.take() {
+        Some(_) => {
+            mutation::notify("Using the Spotify queue".into());
+            None
+        }
+        None => {
+            mutation::notify("Using the local queue".into());
+            Some(LocalQueue::new(items))
+        }
+    };
+}
+
+/// Add items to the end of the local queue, or right after the item playing when `next` is set.
+/// Items already in the queue are moved when played next instead of being added again.
+pub fn add(api: &Pkce, tx: &mpsc::UnboundedSender<Event>, uris: Vec<Uri>, next: bool) {
+    let api = api.clone();
+    let tx = tx.clone();
+    spawn(async move {
+        if api.token().is_expired() {
+            api.refresh().await.log_error();
+        }
+
+        let mut uris = uris;
+        let mut moved = 0;
+        if next {
+            if let Some(queue) = LOCAL_QUEUE.lock().unwrap().as_mut() {
+                let mut taken = Vec::new();
+                uris.retain(|uri| match queue.items.iter().position(|i| i.get_uri() == *uri) {
+                    Some(index) => {
+                        taken.push(queue.items.remove(index));
+                        false
+                    }
+                    None => true,
+                });
+                moved = taken.len();
+                queue.items.splice(0..0, taken);
+            }
+        }
+
+        let items = match resolve(&api, &uris).await {
+            Ok(items) => items,
+            Err(error) => {
+                mutation::notify(format!("Couldn't add to the local queue: {error}"));
+                return;
+            }
+        };
+        let count = items.len();
+        match LOCAL_QUEUE.lock().unwrap().as_mut() {
+            Some(queue) if next => {
+                let at = moved.min(queue.items.len());
+                queue.items.splice(at..at, items);
+            }
+            Some(queue) => queue.items.extend(items),
+            None => return,
+        }
+        mutation::notify(match (next, count + moved) {
+            (true, 1) => "Plays next in the local queue".to_string(),
+            (true, n) => format!("{n} items play next in the local queue"),
+            (false, 1) => "Added to the local queue".to_string(),
+            (false, n) => format!("Added {n} items to the local queue"),
+        });
+        tx.send(Event::UpdateQueue).log_error();
+    });
+}
+
+/// Move the item at `from` so it ends up at `to`
+pub fn move_item(from: usize, to: usize) {
+    if let Some(queue) = LOCAL_QUEUE.lock().unwrap().as_mut() {
+        if from < queue.items.len() && to < queue.items.len() {
+            let item = queue.items.remove(from);
+            queue.items.insert(to, item);
+        }
+    }
+}
+
+/// Remove every occurrence of the items from the local queue
+pub fn remove(uris: &[Uri]) {
+    if let Some(queue) = LOCAL_QUEUE.lock().unwrap().as_mut() {
+        queue.items.retain(|item| !uris.contains(&item.get_uri()));
+    }
+}
+
+/// Remove every item from the local queue, letting the item playing finish
+pub fn clear() {
+    if let Some(queue) = LOCAL_QUEUE.lock().unwrap().as_mut() {
+        queue.items.clear();
+        mutation::notify("Cleared the local queue".into());
+    }
+}
+
+/// Skip to the next item in the local queue. False if spotify should skip instead.
+pub fn skip(api: &Pkce) -> bool {
+    let uri = match LOCAL_QUEUE.lock().unwrap().as_mut() {
+        Some(queue) if !queue.items.is_empty() => queue.start(0),
+        _ => return false,
+    };
+    start(api, uri);
+    true
+}
+
+/// Play the item right away, keeping the rest of the local queue after it. False if the local
+/// queue isn't being used.
+pub fn play_now(api: &Pkce, uri: &Uri) -> bool {
+    let uri = {
+        let mut queue = LOCAL_QUEUE.lock().unwrap();
+        let Some(queue) = queue.as_mut() else {
+            return false;
+        };
+        match queue.items.iter().position(|item| item.get_uri() == *uri) {
+            Some(index) => queue.start(index),
+            None => {
+                queue.playing = Some(uri.clone());
+                queue.ends = None;
+                queue.changed = Local::now();
+                uri.clone()
+            }
+        }
+    };
+    start(api, uri);
+    true
+}
+
+/// Playback was changed from rataify, so continue after whatever plays next instead of handing
+/// control back to spotify
+pub fn follow() {
+    if let Some(queue) = LOCAL_QUEUE.lock().unwrap().as_mut() {
+        queue.playing = None;
+        queue.ends = None;
+        queue.changed = Local::now();
+    }
+}
+
+/// Keep the local queue in step with the playback that was just fetched, starting the next item
+/// once the one playing ends. If something else plays before it ends, playback was changed from
+/// another client and spotify's queue is used again. True if an item was started.
+pub async fn sync(api: &Pkce, playback: &PlaybackState) -> bool {
+    let uri = {
+        let mut local = LOCAL_QUEUE.lock().unwrap();
+        let Some(queue) = local.as_mut() else {
+            return false;
+        };
+
+        let now = Local::now();
+        // Spotify can still report what played before the queue changed it
+        if now - queue.changed < Duration::seconds(START_GRACE) {
+            return false;
+        }
+
+        let current = playback.playback.as_ref().and_then(|pb| pb.uri());
+        let is_playing = playback.playback.as_ref().map(|pb| pb.is_playing).unwrap_or(false);
+        let ends = match (playback.progress(), playback.duration()) {
+            (Some(progress), Some(duration)) if is_playing => Some(now + (duration - progress)),
+            _ => None,
+        };
+        let ended = queue.ends.is_some_and(|ends| now + Duration::seconds(END_TOLERANCE) >= ends);
+
+        match (queue.playing.as_ref(), current) {
+            // Stopping at the end of the item is the same as the item ending
+            (Some(playing), Some(current)) if *playing == current && (is_playing || !ended) => {
+                queue.ends = ends;
+                return false;
+            }
+            (Some(_), _) if ended => {}
+            (Some(_), _) => {
+                *local = None;
+                mutation::notify("Playback was changed somewhere else, using the Spotify queue".into());
+                return false;
+            }
+            (None, Some(current)) => {
+                queue.playing = Some(current);
+                queue.ends = ends;
+                return false;
+            }
+            (None, None) => return false,
+        }
+
+        if queue.items.is_empty() {
+            // Let spotify carry on and follow whatever it plays next
+            queue.playing = None;
+            queue.ends = None;
+            return false;
+        }
+        queue.start(0)
+    };
+
+    play(api, uri).await;
+    true
+}
+
+/// Start playing an item taken from the local queue
+fn start(api: &Pkce, uri: Uri) {
+    let api = api.clone();
+    spawn(async move {
+        if api.token().is_expired() {
+            api.refresh().await.log_error();
+        }
+        play(&api, uri).await;
+    });
+}
+
+async fn play(api: &Pkce, uri: Uri) {
+    if let Err(error) = api.play(Play::queue([uri]), None).await {
+        mutation::notify(format!("Couldn't play from the local queue: {error}"));
+    }
+}
+
+/// Fetch the tracks and episodes so they can be shown, keeping their order. Anything else can't
+/// be queued and is left out.
+async fn resolve(api: &Pkce, uris: &[Uri]) -> Result<Vec<Item>, tupy::Error> {
+    let mut items = Vec::new();
+    for chunk in uris.chunks(FETCH_SIZE) {
+        let ids = |resource: Resource| {
+            chunk
+                .iter()
+                .filter(|uri| uri.resource() == resource)
+                .map(|uri| uri.id().to_string())
+                .collect::<Vec<_>>()
+        };
+        let (track_ids, episode_ids) = (ids(Resource::Track), ids(Resource::Episode));
+
+        let mut tracks = Vec::new();
+        if !track_ids.is_empty() {
+            tracks = api.tracks(track_ids, None).await?;
+        }
+        let mut episodes = Vec::new();
+        if !episode_ids.is_empty() {
+            episodes = api.episodes(episode_ids, None).await?;
+        }
+
+        items.extend(chunk.iter().filter_map(|uri| {
+            tracks
+                .iter()
+                .find(|t| t.uri == *uri)
+                .map(|t| Item::Track(Box::new(t.clone())))
+                .or_else(|| episodes.iter().find(|e| e.uri == *uri).map(|e| Item::Episode(Box::new(e.clone()))))
+        }));
+    }
+    Ok(items)
+}
@@ -1,24 +1,83 @@
 use std::fmt::Debug;
 use color_eyre::Result;
 
-use ratatui::widgets::{ListState, TableState};
+use ratatui::{layout::{Position, Rect}, widgets::{ListState, TableState}};
+use serde::{Deserialize, Serialize};
 use modal::ModalState;
 use tupy::api::flow::Pkce;
 use window::WindowState;
 
-use crate::{Locked, Shared};
+use crate::{tasks, Locked, Shared};
 
-use self::playback::Playback;
+use self::{component::Component, navigation::{Entry, Navigation}, window::landing::Landing};
 
 pub mod modal;
 pub mod window;
 pub mod playback;
 pub mod wrappers;
 pub mod actions;
+pub mod poll;
+pub mod component;
+pub mod navigation;
+pub mod session;
+pub mod connection;
+pub mod mutation;
+pub mod undo;
+pub mod enqueue;
+pub mod local_queue;
+pub mod library_index;
+pub mod local_library;
+pub mod view;
+
+/// Jump of the selection in a list by more than one row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Motion {
+    Top,
+    Bottom,
+    HalfPageDown,
+    HalfPageUp,
+    PageDown,
+    PageUp,
+    /// Scroll so the selected row is in the middle of the list
+    Center,
+}
+
+impl Motion {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Top => "Top",
+            Self::Bottom => "Bottom",
+            Self::HalfPageDown => "Half page down",
+            Self::HalfPageUp => "Half page up",
+            Self::PageDown => "Page down",
+            Self::PageUp => "Page up",
+            Self::Center => "Center selection",
+        }
+    }
+
+    /// Row selected after the motion given how many rows are shown, or `None` if the
+    /// selection doesn't move
+    fn target(&self, selected: Option<usize>, len: usize, rows: usize) -> Option<usize> {
+        let selected = selected.unwrap_or(0);
+        let last = len.checked_sub(1)?;
+        let half = (rows / 2).max(1);
+        match self {
+            Self::Top => Some(0),
+            Self::Bottom => Some(last),
+            Self::HalfPageDown => Some((selected + half).min(last)),
+            Self::HalfPageUp => Some(selected.saturating_sub(half)),
+            Self::PageDown => Some((selected + rows.max(1)).min(last)),
+            Self::PageUp => Some(selected.saturating_sub(rows.max(1))),
+            Self::Center => None,
+        }
+    }
+}
 
 pub trait IterCollection {
     fn next_in_list(&mut self, len: usize);
     fn prev_in_list(&mut self, len: usize);
+    /// Apply the motion to a list of `len` items where `rows` of them are shown at once
+    fn jump_in_list(&mut self, motion: Motion, len: usize, rows: usize);
 }
 
 impl IterCollection for ListState {
@@ -45,6 +104,13 @@ impl IterCollection for ListState {
             _ => {}
         }
     }
+
+    fn jump_in_list(&mut self, motion: Motion, len: usize, rows: usize) {
+        match motion.target(self.selected(), len, rows) {
+            Some(target) => self.select(Some(target)),
+            None => *self.offset_mut() = self.selected().unwrap_or(0).saturating_sub(rows / 2),
+        }
+    }
 }
 
 impl IterCollection for TableState {
@@ -71,6 +137,62 @@ impl IterCollection for TableState {
             _ => {}
         }
     }
+
+    fn jump_in_list(&mut self, motion: Motion, len: usize, rows: usize) {
+        match motion.target(self.selected(), len, rows) {
+            Some(target) => self.select(Some(target)),
+            None => *self.offset_mut() = self.selected().unwrap_or(0).saturating_sub(rows / 2),
+        }
+    }
+}
+
+/// Result of a mouse click on an interactive area
+#[derive(Debug, Clone, Copy, PartialEq, strum_macros::EnumIs)]
+pub enum Click {
+    /// Nothing interactive was under the cursor
+    Miss,
+    /// The item under the cursor is now selected
+    Selected,
+    /// The item under the cursor was already selected and should be activated
+    Activated,
+}
+
+/// Area a table was last rendered to along with its scroll offset, used to map
+/// mouse positions back to rows
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct TableArea {
+    pub area: Rect,
+    pub offset: usize,
+}
+
+impl TableArea {
+    pub fn new(area: Rect, state: &TableState) -> Self {
+        Self {
+            area,
+            offset: state.offset(),
+        }
+    }
+
+    /// Index of the row under the position if there is one
+    pub fn row(&self, column: u16, row: u16, len: usize) -> Option<usize> {
+        if !self.area.contains(Position::new(column, row)) {
+            return None;
+        }
+        let index = self.offset + (row - self.area.y) as usize;
+        (index < len).then_some(index)
+    }
+
+    /// Select the row under the position
+    pub fn click(&self, state: &mut TableState, column: u16, row: u16, len: usize) -> Click {
+        match self.row(column, row, len) {
+            Some(index) if state.selected() == Some(index) => Click::Activated,
+            Some(index) => {
+                state.select(Some(index));
+                Click::Selected
+            }
+            None => Click::Miss,
+        }
+    }
 }
 
 #[derive(Default)]
@@ -156,34 +278,6 @@ impl<T: PartialEq> PartialEq for Loading<T> {
     }
 }
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
-pub struct Countdown {
-    count: usize,
-    _origin: usize,
-}
-impl Countdown {
-    pub fn new(origin: usize) -> Self {
-        Self {
-            count: origin,
-            _origin: origin,
-        }
-    }
-
-    pub fn decrement(&mut self) {
-        self.count = self.count.saturating_sub(1);
-    }
-    pub fn poll(&mut self) -> bool {
-        self.decrement();
-        self.is_ready()
-    }
-    pub fn is_ready(&self) -> bool {
-        self.count == 0
-    }
-    pub fn reset(&mut self) {
-        self.count = self._origin;
-    }
-}
-
 #[derive(Debug, Clone, PartialEq)]
 pub enum Modal {
     Devices,
@@ -191,53 +285,140 @@ pub enum Modal {
     AddToPlaylist,
     Artists,
     GoTo,
+    /// Fuzzy finder over the local library
+    Find,
+    /// Prompt for a command to run by name
+    Command,
+    /// Create, edit or delete one of the user's playlists
+    PlaylistForm,
+    /// Tune a radio of recommendations before starting it
+    Radio,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum Window {
     Queue,
     #[default]
     Library,
     Landing,
-}
-
-#[derive(Default, Debug, Clone, PartialEq)]
-pub enum Viewport {
-    Modal(Modal),
-    #[default]
-    Window,
+    Top,
+    History,
+    Browse,
 }
 
 #[derive(Debug, Clone)]
 pub struct State {
-    // State for what is shown in the viewport
-    pub viewport: Viewport,
+    // Focus stack of the window that is shown and the modals opened over it, focused modal last
     pub window: Window,
+    pub modals: Vec<Modal>,
+    // Pages that can be gone back and forward to
+    pub navigation: Shared<Locked<Navigation>>,
 
     // State for the current modal
     pub modal_state: ModalState,
     // State for the current window
     pub window_state: WindowState,
 
-    // Countdown for when to poll for playback
-    pub playback_poll: Countdown,
+    // Whether spotify could be reached, shown as a banner while offline
+    pub connection: Shared<Locked<connection::Connection>>,
+    // Scheduler for when to poll for playback
+    pub playback_poll: Shared<Locked<poll::PlaybackPoll>>,
     pub playback: Shared<Locked<playback::PlaybackState>>,
+    // Area the playback was last rendered to, used for mouse interactions
+    pub playback_area: Shared<Locked<Rect>>,
 }
 
 impl State {
-    pub async fn new(dir: &str, api: &Pkce, countdown: Countdown, playback: Option<Playback>) -> Result<Self> {
+    /// Create the state without making any requests, so the ui can be shown right away. The
+    /// first poll fetches the playback and windows fetch their data once they are shown.
+    pub fn new(dir: &str, api: &Pkce) -> Result<Self> {
         Ok(Self {
-            viewport: Viewport::default(),
             window: Window::default(),
+            modals: Vec::new(),
+            navigation: Shared::default(),
 
             modal_state: ModalState::default(),
-            window_state: WindowState::new(dir, api).await?,
+            window_state: WindowState::new(dir, api)?,
 
-            playback_poll: countdown,
-            playback: Shared::new(Locked::new(playback::PlaybackState::new(playback))),
+            connection: Shared::default(),
+            playback_poll: Shared::default(),
+            playback: Shared::default(),
+            playback_area: Shared::default(),
         })
     }
 
+    /// Modal with focus if one is open
+    pub fn modal(&self) -> Option<&Modal> {
+        self.modals.last()
+    }
+
+    /// Component with focus that input is routed to
+    pub fn focused(&self) -> Shared<Locked<dyn Component>> {
+        match self.modals.last() {
+            Some(modal) => self.modal_state.component(modal),
+            None => self.window_state.component(self.window),
+        }
+    }
+
+    /// Components in the focus stack from the window up to the focused modal
+    pub fn focus_stack(&self) -> Vec<Shared<Locked<dyn Component>>> {
+        std::iter::once(self.window_state.component(self.window))
+            .chain(self.modals.iter().map(|modal| self.modal_state.component(modal)))
+            .collect()
+    }
+
+    /// Show the window, closing any open modals and cancelling the fetches of the window that
+    /// was shown before
+    pub fn show(&mut self, window: Window) {
+        tasks::enter(window);
+        self.modals.clear();
+        self.window = window;
+        self.focused().lock().unwrap().focus();
+    }
+
+    /// Open the modal over the focused component, moving it to the top if it is already open
+    pub fn open(&mut self, modal: Modal) {
+        self.modals.retain(|m| *m != modal);
+        self.modals.push(modal);
+        self.focused().lock().unwrap().focus();
+    }
+
+    /// Close the focused modal. Returns false if no modal was open.
+    pub fn close(&mut self) -> bool {
+        self.modals.pop().is_some()
+    }
+
+    /// Label of the page that is shown, used in the breadcrumb
+    pub fn label(&self) -> String {
+        match self.window {
+            Window::Landing => self.window_state.landing.lock().unwrap().title(),
+            window => format!("{:?}", window),
+        }
+    }
+
+    /// Leave the page that is shown, taking its landing so it can be restored from memory
+    pub fn leave(&mut self) -> Entry {
+        let label = self.label();
+        let landing = match self.window {
+            Window::Landing => Some(std::mem::take(&mut *self.window_state.landing.lock().unwrap())),
+            _ => None,
+        };
+        Entry {
+            goto: self.navigation.lock().unwrap().current.clone(),
+            label,
+            landing,
+        }
+    }
+
+    /// Show a landing that was just built or kept in the navigation history
+    pub fn restore_landing(&mut self, landing: Landing) {
+        // Fetches still running belong to the landing that was left
+        tasks::cancel(tasks::Scope::Window(Window::Landing));
+        *self.window_state.landing.lock().unwrap() = landing;
+        self.show(Window::Landing);
+    }
+
     pub fn show_queue(&self) -> bool {
         #[allow(irrefutable_let_patterns)]
         if let Window::Queue = self.window {
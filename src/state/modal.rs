@@ -1,21 +1,30 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, path::PathBuf};
 
-use crossterm::event::{KeyCode, KeyEvent};
-use ratatui::widgets::TableState;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{buffer::Buffer, layout::Rect, widgets::{TableState, Widget}};
 use tokio::sync::mpsc;
-use tupy::api::{flow::{AuthFlow, Pkce}, response::{Device, PagedPlaylists}, Resource, Uri, UserApi};
+use tupy::api::{flow::{AuthFlow, Pkce}, request::{encoded_image_size, Play, PlaylistDetails, RecommendationSeed, SeedId, MAX_COVER_IMAGE_SIZE}, response::{Device, PagedPlaylists, Playlist}, PublicApi, Uri, UserApi};
 
-use crate::{app::Event, errors::LogError, key, state::actions::{Action, GoTo}, Locked, Shared};
+use crate::{app::Event, command::{self, Completion}, errors::{LogError, LogErrorDefault}, key, tasks::spawn, state::{actions::{Action, GoTo, PlaylistForm, Radio}, library_index, local_queue, local_library::{LocalItem, LocalKind, LOCAL_LIBRARY}, mutation, undo::{self, Inverse}}, ui::{modal::goto::UiGoto, window::landing::decode_description}, Locked, Shared};
 
-use super::{window::Pages, IterCollection, Loading};
+use super::{component::{Component, Context, Handled}, window::Pages, Click, IterCollection, Loading, Modal, Motion, TableArea};
 
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct DevicesState {
     pub state: TableState,
     pub devices: Vec<Device>,
+    pub area: TableArea,
 }
 
 impl DevicesState {
+    pub fn new(devices: Vec<Device>) -> Self {
+        Self {
+            devices,
+            state: TableState::default(),
+            area: TableArea::default(),
+        }
+    }
+
     pub fn next(&mut self) {
         self.state.next_in_list(self.devices.len());
     }
@@ -24,11 +33,47 @@ impl DevicesState {
         self.state.prev_in_list(self.devices.len());
     }
 
+    pub fn jump(&mut self, motion: Motion) {
+        self.state.jump_in_list(motion, self.devices.len(), self.area.area.height as usize);
+    }
+
+    pub fn click(&mut self, column: u16, row: u16) -> Click {
+        self.area.click(&mut self.state, column, row, self.devices.len())
+    }
+
     pub fn select(&self) -> Device {
         self.devices[self.state.selected().unwrap_or(0)].clone()
     }
 }
 
+impl Component for DevicesState {
+    fn event(&mut self, event: &Event, ctx: &Context) -> color_eyre::Result<Handled> {
+        Ok(match event {
+            Event::Down => { self.next(); Handled::Consumed },
+            Event::Up => { self.prev(); Handled::Consumed },
+            Event::Motion(motion) => { self.jump(*motion); Handled::Consumed },
+            Event::Select => {
+                ctx.tx.send(Event::TransferPlayback(self.select().id)).log_error();
+                Handled::Close
+            },
+            _ => Handled::Ignored,
+        })
+    }
+
+    fn mouse(&mut self, column: u16, row: u16) -> Click {
+        self.click(column, row)
+    }
+
+    fn refresh(&mut self, ctx: &Context) -> bool {
+        ctx.tx.send(Event::OpenSelectDevice).log_error();
+        true
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Widget::render(self, area, buf);
+    }
+}
+
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct GoToState {
     lookup: HashMap<KeyEvent, usize>,
@@ -52,10 +97,32 @@ impl GoToState {
     }
 }
 
+impl Component for GoToState {
+    fn key(&mut self, key: KeyEvent, ctx: &Context) -> Handled {
+        // `gg` jumps to the top of the page like vim
+        if key == key!('g') {
+            ctx.tx.send(Event::Motion(Motion::Top)).log_error();
+            return Handled::Close;
+        }
+        match self.get(&key) {
+            Some(go_to) => {
+                ctx.tx.send(Event::GoTo(go_to.clone())).log_error();
+                Handled::Close
+            }
+            None => Handled::Consumed,
+        }
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Widget::render(UiGoto(&self.mappings), area, buf);
+    }
+}
+
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct ArtistsState {
     pub state: TableState,
     pub artists: Vec<(Uri, String)>,
+    pub area: TableArea,
 }
 
 impl ArtistsState {
@@ -63,6 +130,7 @@ impl ArtistsState {
         Self {
             artists,
             state: TableState::default(),
+            area: TableArea::default(),
         }
     }
 
@@ -74,24 +142,66 @@ impl ArtistsState {
         self.state.prev_in_list(self.artists.len());
     }
 
+    pub fn jump(&mut self, motion: Motion) {
+        self.state.jump_in_list(motion, self.artists.len(), self.area.area.height as usize);
+    }
+
+    pub fn click(&mut self, column: u16, row: u16) -> Click {
+        self.area.click(&mut self.state, column, row, self.artists.len())
+    }
+
     pub fn select(&self) -> Uri {
         self.artists[self.state.selected().unwrap_or(0)].clone().0
     }
 }
 
+impl Component for ArtistsState {
+    fn event(&mut self, event: &Event, ctx: &Context) -> color_eyre::Result<Handled> {
+        Ok(match event {
+            Event::Down => { self.down(); Handled::Consumed },
+            Event::Up => { self.up(); Handled::Consumed },
+            Event::Motion(motion) => { self.jump(*motion); Handled::Consumed },
+            Event::Select => {
+                ctx.tx.send(Event::GoTo(GoTo::Artist(self.select()))).log_error();
+                Handled::Close
+            },
+            _ => Handled::Ignored,
+        })
+    }
+
+    fn mouse(&mut self, column: u16, row: u16) -> Click {
+        self.click(column, row)
+    }
+
+    /// The artists come from the landing page under the modal so it is refreshed instead
+    fn refresh(&mut self, _ctx: &Context) -> bool {
+        false
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Widget::render(self, area, buf);
+    }
+}
+
+/// Most items spotify accepts in one request to add to a playlist
+pub const ADD_ITEMS_BATCH: usize = 100;
+
 #[derive(Debug, Clone)]
 pub struct AddToPlaylistState {
-    pub item: Uri,
+    /// Items being added, in the order they are added to the playlist
+    pub items: Vec<Uri>,
     pub state: TableState,
     pub playlists: Pages<PagedPlaylists, PagedPlaylists>,
+    pub area: TableArea,
 }
 
 impl AddToPlaylistState {
-    pub fn new(item: Uri, playlists: Pages<PagedPlaylists, PagedPlaylists>) -> Self {
+    pub fn new(items: Vec<Uri>, playlists: Pages<PagedPlaylists, PagedPlaylists>) -> Self {
         Self {
-            item,
+            items,
             playlists,
             state: TableState::default(),
+            area: TableArea::default(),
         }
     }
 
@@ -107,18 +217,26 @@ impl AddToPlaylistState {
         };
     }
 
-    pub async fn right(&mut self) -> color_eyre::Result<()> {
-        if self.playlists.has_next().await {
-            self.playlists.next().await?;
-        }
-        Ok(())
+    pub fn jump(&mut self, motion: Motion) {
+        if let Some(Loading::Some(items)) = self.playlists.items.lock().unwrap().as_ref().map(|p| p.as_ref()){
+            self.state.jump_in_list(motion, items.items.len(), self.area.area.height as usize);
+        };
     }
 
-    pub async fn left(&mut self) -> color_eyre::Result<()> {
-        if self.playlists.has_prev().await {
-            self.playlists.prev().await?;
-        }
-        Ok(())
+    pub fn right(&mut self) {
+        self.playlists.next_page();
+    }
+
+    pub fn left(&mut self) {
+        self.playlists.prev_page();
+    }
+
+    pub fn click(&mut self, column: u16, row: u16) -> Click {
+        let len = match self.playlists.items.lock().unwrap().as_ref().map(|p| p.as_ref()) {
+            Some(Loading::Some(items)) => items.items.len(),
+            _ => 0,
+        };
+        self.area.click(&mut self.state, column, row, len)
     }
 
     pub fn select(&self) -> Option<Uri> {
@@ -129,6 +247,53 @@ impl AddToPlaylistState {
     }
 }
 
+impl Component for AddToPlaylistState {
+    fn event(&mut self, event: &Event, ctx: &Context) -> color_eyre::Result<Handled> {
+        Ok(match event {
+            Event::Down => { self.down(); Handled::Consumed },
+            Event::Up => { self.up(); Handled::Consumed },
+            Event::Motion(motion) => { self.jump(*motion); Handled::Consumed },
+            Event::Right => { self.right(); Handled::Consumed },
+            Event::Left => { self.left(); Handled::Consumed },
+            Event::Select => {
+                if let Some(playlist) = self.select() {
+                    let api = ctx.api.clone();
+                    let items = self.items.clone();
+                    mutation::commit(
+                        "add to the playlist",
+                        async move {
+                            if api.token().is_expired() {
+                                api.refresh().await.log_error();
+                            }
+                            for batch in items.chunks(ADD_ITEMS_BATCH) {
+                                api.add_items(playlist.clone(), batch.iter().cloned(), None).await?;
+                            }
+                            Ok(())
+                        },
+                        || {},
+                    );
+                }
+                Handled::Close
+            },
+            _ => Handled::Ignored,
+        })
+    }
+
+    fn mouse(&mut self, column: u16, row: u16) -> Click {
+        self.click(column, row)
+    }
+
+    fn refresh(&mut self, _ctx: &Context) -> bool {
+        self.playlists.refresh_page();
+        self.state.select(None);
+        true
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Widget::render(self, area, buf);
+    }
+}
+
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct ActionState {
     lookup: HashMap<KeyEvent, usize>,
@@ -153,146 +318,776 @@ impl ActionState {
 
     pub fn resolve(&self, key: KeyEvent, api: &Pkce, tx: mpsc::UnboundedSender<Event>) -> bool {
         if let Some(action) = self.get(key) {
-            match action {
-                Action::Play(play) => {
-                    let api = api.clone();
-                    let uri = play.clone();
-                    tokio::spawn(async move {
-                        if api.token().is_expired() {
-                            api.refresh().await.log_error();
-                        }
-                        api.add_to_queue(uri, None).await.log_error();
-                        api.next(None).await.log_error();
-                    });
+            action.run(api, &tx);
+            return true;
+        }
+        false
+    }
+}
+
+impl Component for ActionState {
+    fn key(&mut self, key: KeyEvent, ctx: &Context) -> Handled {
+        if self.resolve(key, ctx.api, ctx.tx.clone()) {
+            Handled::Close
+        } else {
+            Handled::Consumed
+        }
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Widget::render(&*self, area, buf);
+    }
+}
+
+/// Most results shown in the fuzzy finder
+const FIND_LIMIT: usize = 50;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FindState {
+    pub query: String,
+    pub results: Vec<LocalItem>,
+    pub state: TableState,
+    pub area: TableArea,
+}
+
+impl FindState {
+    /// Search the local library again with the current query
+    pub fn search(&mut self) {
+        self.results = if self.query.trim().is_empty() {
+            Vec::new()
+        } else {
+            LOCAL_LIBRARY.lock().unwrap().search(&self.query, FIND_LIMIT)
+        };
+        self.state.select((!self.results.is_empty()).then_some(0));
+    }
+
+    pub fn down(&mut self) {
+        self.state.next_in_list(self.results.len());
+    }
+
+    pub fn up(&mut self) {
+        self.state.prev_in_list(self.results.len());
+    }
+
+    pub fn jump(&mut self, motion: Motion) {
+        self.state.jump_in_list(motion, self.results.len(), self.area.area.height as usize);
+    }
+
+    pub fn click(&mut self, column: u16, row: u16) -> Click {
+        self.area.click(&mut self.state, column, row, self.results.len())
+    }
+
+    pub fn select(&self) -> Option<&LocalItem> {
+        self.results.get(self.state.selected().unwrap_or(0))
+    }
+}
+
+impl Component for FindState {
+    fn key(&mut self, key: KeyEvent, _ctx: &Context) -> Handled {
+        if key.modifiers.difference(KeyModifiers::SHIFT).is_empty() {
+            match key.code {
+                KeyCode::Char(c) => self.query.push(c),
+                KeyCode::Backspace => {
+                    self.query.pop();
                 }
-                Action::PlayContext(play) => tx.send(Event::Play(play.clone())).log_error(),
-                Action::Save(uri, callback) => {
-                    let api = api.clone();
-                    let uri = uri.clone();
-                    let callback = callback.clone();
-                    match uri.resource() {
-                        Resource::Track => {tokio::spawn(async move {
-                            if api.token().is_expired() {
-                                api.refresh().await.log_error();
-                            }
-                            api.save_tracks([uri]).await.log_error();
-                            callback(true).log_error()
-                        });},
-                        Resource::Episode => {tokio::spawn(async move {
-                            if api.token().is_expired() {
-                                api.refresh().await.log_error();
-                            }
-                            api.save_episodes([uri]).await.log_error();
-                            callback(true).log_error()
-                        });},
-                        Resource::Artist => {tokio::spawn(async move {
-                            if api.token().is_expired() {
-                                api.refresh().await.log_error();
-                            }
-                            api.follow_artists([uri]).await.log_error();
-                            callback(true).log_error()
-                        });},
-                        Resource::Album => {tokio::spawn(async move {
-                            if api.token().is_expired() {
-                                api.refresh().await.log_error();
-                            }
-                            api.save_albums([uri]).await.log_error();
-                            callback(true).log_error()
-                        });},
-                        Resource::Playlist => {tokio::spawn(async move {
+                _ => return Handled::Ignored,
+            }
+            self.search();
+            return Handled::Consumed;
+        }
+        Handled::Ignored
+    }
+
+    fn text_input(&self) -> bool {
+        true
+    }
+
+    fn event(&mut self, event: &Event, ctx: &Context) -> color_eyre::Result<Handled> {
+        Ok(match event {
+            Event::Down => { self.down(); Handled::Consumed },
+            Event::Up => { self.up(); Handled::Consumed },
+            Event::Motion(motion) => { self.jump(*motion); Handled::Consumed },
+            Event::Select => {
+                let Some(item) = self.select() else {
+                    return Ok(Handled::Consumed);
+                };
+                let uri = item.uri.parse::<Uri>().map_err(color_eyre::eyre::Error::msg)?;
+                let goto = match item.kind {
+                    LocalKind::Track => {
+                        if local_queue::play_now(ctx.api, &uri) {
+                            ctx.tx.send(Event::UpdateQueue).log_error();
+                            return Ok(Handled::Close);
+                        }
+                        // Play the track on its own instead of skipping to it so spotify's queue
+                        // is left as it was
+                        let api = ctx.api.clone();
+                        spawn(async move {
                             if api.token().is_expired() {
                                 api.refresh().await.log_error();
                             }
-                            api.follow_playlist(uri, true).await.log_error();
-                            callback(true).log_error()
-                        });},
-                        Resource::Show => {
-                            tokio::spawn(async move {
-                                if api.token().is_expired() {
-                                    api.refresh().await.log_error();
-                                }
-                                api.save_shows([uri]).await.log_error();
-                                callback(true).log_error()
-                            });
-                        },
-                        _ => {}
+                            api.play(Play::queue([uri]), None).await.log_error();
+                        });
+                        return Ok(Handled::Close);
                     }
+                    LocalKind::Album => GoTo::Album(uri),
+                    LocalKind::Show => GoTo::Show(uri),
+                    LocalKind::Audiobook => GoTo::Audiobook(uri),
+                    LocalKind::Artist => GoTo::Artist(uri),
+                    LocalKind::Playlist => GoTo::Playlist(uri),
+                };
+                ctx.tx.send(Event::GoTo(goto)).log_error();
+                Handled::Close
+            },
+            _ => Handled::Ignored,
+        })
+    }
+
+    fn mouse(&mut self, column: u16, row: u16) -> Click {
+        self.click(column, row)
+    }
+
+    /// The results come from the local library so there is nothing to fetch
+    fn refresh(&mut self, _ctx: &Context) -> bool {
+        self.search();
+        true
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Widget::render(self, area, buf);
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct CommandState {
+    pub input: String,
+    pub completions: Vec<Completion>,
+    pub state: TableState,
+    pub area: TableArea,
+    /// Commands that were run, oldest first
+    pub history: Vec<String>,
+    /// How far back in the history the input was recalled from
+    recalled: Option<usize>,
+    /// Names of the devices that can be completed, fetched when the prompt is opened
+    pub devices: Shared<Locked<Vec<String>>>,
+}
+
+impl CommandState {
+    pub fn new(history: Vec<String>) -> Self {
+        Self {
+            history,
+            ..Default::default()
+        }
+    }
+
+    /// Clear the prompt so it can be opened again, keeping the history
+    pub fn reset(&mut self) {
+        self.input.clear();
+        self.recalled = None;
+        self.complete();
+    }
+
+    /// Find completions for what was typed
+    pub fn complete(&mut self) {
+        self.completions = command::complete(&self.input, &self.devices.lock().unwrap());
+        self.state.select(None);
+    }
+
+    /// Fill the input with the next completion, wrapping around to the first
+    pub fn next_completion(&mut self) {
+        if self.completions.is_empty() {
+            return;
+        }
+        let next = self.state.selected().map(|i| (i + 1) % self.completions.len()).unwrap_or(0);
+        self.use_completion(next);
+    }
+
+    /// Fill the input with the previous completion, wrapping around to the last
+    pub fn prev_completion(&mut self) {
+        if self.completions.is_empty() {
+            return;
+        }
+        let prev = match self.state.selected() {
+            Some(0) | None => self.completions.len() - 1,
+            Some(i) => i - 1,
+        };
+        self.use_completion(prev);
+    }
+
+    fn use_completion(&mut self, index: usize) {
+        self.state.select(Some(index));
+        self.input = self.completions[index].line.clone();
+    }
+
+    /// Recall the command run before the one shown
+    pub fn older(&mut self) {
+        let recalled = self.recalled.map(|i| i + 1).unwrap_or(0);
+        if recalled < self.history.len() {
+            self.recalled = Some(recalled);
+            self.input = self.history[self.history.len() - 1 - recalled].clone();
+            self.complete();
+        }
+    }
+
+    /// Recall the command run after the one shown, or clear the input past the newest
+    pub fn newer(&mut self) {
+        match self.recalled {
+            Some(0) => {
+                self.recalled = None;
+                self.input.clear();
+            }
+            Some(recalled) => {
+                self.recalled = Some(recalled - 1);
+                self.input = self.history[self.history.len() - recalled].clone();
+            }
+            None => return,
+        }
+        self.complete();
+    }
+
+    pub fn click(&mut self, column: u16, row: u16) -> Click {
+        let click = self.area.click(&mut self.state, column, row, self.completions.len());
+        if !click.is_miss() {
+            if let Some(index) = self.state.selected() {
+                self.use_completion(index);
+            }
+        }
+        click
+    }
+
+    /// Remember the command in the history and send it to be run
+    pub fn run(&mut self, tx: &mpsc::UnboundedSender<Event>) {
+        let line = self.input.trim().to_string();
+        if line.is_empty() {
+            return;
+        }
+        if self.history.last() != Some(&line) {
+            self.history.push(line.clone());
+            command::save_history(&self.history).log_error();
+        }
+        tx.send(Event::Command(line)).log_error();
+    }
+}
+
+impl Component for CommandState {
+    fn key(&mut self, key: KeyEvent, _ctx: &Context) -> Handled {
+        if key.modifiers.difference(KeyModifiers::SHIFT).is_empty() {
+            match key.code {
+                KeyCode::Char(c) => self.input.push(c),
+                KeyCode::Backspace => {
+                    self.input.pop();
                 }
-                Action::Remove(uri, callback) => {
-                    let api = api.clone();
-                    let uri = uri.clone();
-                    let callback = callback.clone();
-                    match uri.resource() {
-                        Resource::Track => {tokio::spawn(async move {
-                            if api.token().is_expired() {
-                                api.refresh().await.log_error();
-                            }
-                            api.remove_saved_tracks([uri]).await.log_error();
-                            callback(false).log_error()
-                        });},
-                        Resource::Episode => {tokio::spawn(async move {
-                            if api.token().is_expired() {
-                                api.refresh().await.log_error();
-                            }
-                            api.remove_saved_episodes([uri]).await.log_error();
-                            callback(false).log_error()
-                        });},
-                        Resource::Artist => {tokio::spawn(async move {
-                            if api.token().is_expired() {
-                                api.refresh().await.log_error();
-                            }
-                            api.unfollow_artists([uri]).await.log_error();
-                            callback(false).log_error()
-                        });},
-                        Resource::Album => {tokio::spawn(async move {
-                            if api.token().is_expired() {
-                                api.refresh().await.log_error();
-                            }
-                            api.remove_saved_albums([uri]).await.log_error();
-                            callback(false).log_error()
-                        });},
-                        Resource::Playlist => {tokio::spawn(async move {
-                            if api.token().is_expired() {
-                                api.refresh().await.log_error();
+                _ => return Handled::Ignored,
+            }
+            self.recalled = None;
+            self.complete();
+            return Handled::Consumed;
+        }
+        Handled::Ignored
+    }
+
+    fn text_input(&self) -> bool {
+        true
+    }
+
+    fn event(&mut self, event: &Event, ctx: &Context) -> color_eyre::Result<Handled> {
+        Ok(match event {
+            Event::Tab => { self.next_completion(); Handled::Consumed },
+            Event::Backtab => { self.prev_completion(); Handled::Consumed },
+            Event::Up => { self.older(); Handled::Consumed },
+            Event::Down => { self.newer(); Handled::Consumed },
+            Event::Select => {
+                self.run(ctx.tx);
+                Handled::Close
+            },
+            _ => Handled::Ignored,
+        })
+    }
+
+    fn mouse(&mut self, column: u16, row: u16) -> Click {
+        self.click(column, row)
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Widget::render(self, area, buf);
+    }
+}
+
+/// A field of the playlist or radio form
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormField {
+    Text(&'static str, String),
+    Toggle(&'static str, bool),
+}
+
+impl FormField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Text(label, _) | Self::Toggle(label, _) => label,
+        }
+    }
+}
+
+// Fields of the details form
+const NAME: usize = 0;
+const DESCRIPTION: usize = 1;
+const PUBLIC: usize = 2;
+const COLLABORATIVE: usize = 3;
+// Field of the cover form
+const PATH: usize = 0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaylistFormState {
+    pub form: PlaylistForm,
+    /// Name of the playlist being changed, empty when creating one
+    pub name: String,
+    /// Playlists are created for this user
+    user_id: String,
+    pub fields: Vec<FormField>,
+    pub selected: usize,
+    /// Why the form couldn't be submitted
+    pub error: Option<String>,
+    /// Details of the playlist before it is edited, to put back with undo
+    previous: Option<PlaylistDetails>,
+}
+
+impl PlaylistFormState {
+    /// Form filled in from the playlist being changed, or with defaults when creating one
+    pub fn new(form: PlaylistForm, playlist: Option<&Playlist>, user_id: String) -> Self {
+        let fields = match &form {
+            PlaylistForm::Create | PlaylistForm::Edit(_) => vec![
+                FormField::Text("Name", playlist.map(|p| p.name.clone()).unwrap_or_default()),
+                FormField::Text(
+                    "Description",
+                    playlist.and_then(|p| p.description.as_deref()).map(decode_description).unwrap_or_default(),
+                ),
+                // Spotify makes new playlists public unless asked not to
+                FormField::Toggle("Public", playlist.map(|p| p.public.unwrap_or_default()).unwrap_or(true)),
+                FormField::Toggle("Collaborative", playlist.is_some_and(|p| p.collaborative)),
+            ],
+            PlaylistForm::Cover(_) => vec![FormField::Text("JPEG Path", String::new())],
+            PlaylistForm::Delete(..) => Vec::new(),
+        };
+
+        let previous = playlist.map(|p| {
+            PlaylistDetails::new()
+                .name(&p.name)
+                .description(p.description.as_deref().map(decode_description).unwrap_or_default())
+                .public(p.public.unwrap_or_default())
+                .collaborative(p.collaborative)
+        });
+
+        Self {
+            form,
+            name: playlist.map(|p| p.name.clone()).unwrap_or_default(),
+            user_id,
+            fields,
+            selected: 0,
+            error: None,
+            previous,
+        }
+    }
+
+    pub fn down(&mut self) {
+        if !self.fields.is_empty() {
+            self.selected = (self.selected + 1) % self.fields.len();
+        }
+    }
+
+    pub fn up(&mut self) {
+        if !self.fields.is_empty() {
+            self.selected = (self.selected + self.fields.len() - 1) % self.fields.len();
+        }
+    }
+
+    fn text(&self, index: usize) -> &str {
+        match &self.fields[index] {
+            FormField::Text(_, value) => value.trim(),
+            FormField::Toggle(..) => "",
+        }
+    }
+
+    fn toggle(&self, index: usize) -> bool {
+        matches!(self.fields[index], FormField::Toggle(_, true))
+    }
+
+    /// Details to send from the name, description and visibility fields
+    fn details(&self) -> Result<PlaylistDetails, String> {
+        if self.text(NAME).is_empty() {
+            return Err("The playlist needs a name".into());
+        }
+        // Spotify only lets private playlists be collaborative
+        if self.toggle(PUBLIC) && self.toggle(COLLABORATIVE) {
+            return Err("Collaborative playlists can't be public".into());
+        }
+        Ok(PlaylistDetails::new()
+            .name(self.text(NAME))
+            .description(self.text(DESCRIPTION))
+            .public(self.toggle(PUBLIC))
+            .collaborative(self.toggle(COLLABORATIVE)))
+    }
+
+    /// Read the cover, making sure it is a jpeg spotify will accept before it is uploaded
+    fn cover(&self) -> Result<Vec<u8>, String> {
+        let path = self.text(PATH);
+        if path.is_empty() {
+            return Err("Enter the path to a JPEG image".into());
+        }
+        let path = match path.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir().unwrap_or_default().join(rest),
+            None => PathBuf::from(path),
+        };
+
+        let image = std::fs::read(&path).map_err(|error| format!("Couldn't read {}: {error}", path.display()))?;
+        if !image.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            return Err(format!("{} isn't a JPEG image", path.display()));
+        }
+        let size = encoded_image_size(image.len());
+        if size > MAX_COVER_IMAGE_SIZE {
+            return Err(format!(
+                "The image is {} KB once encoded, covers can be at most {} KB",
+                size.div_ceil(1024),
+                MAX_COVER_IMAGE_SIZE / 1024,
+            ));
+        }
+        Ok(image)
+    }
+
+    /// Send the form, returning why it couldn't be sent so it can stay open
+    pub fn submit(&self, api: &Pkce, tx: &mpsc::UnboundedSender<Event>) -> Result<(), String> {
+        let api = api.clone();
+        let tx = tx.clone();
+        match &self.form {
+            PlaylistForm::Create => {
+                let details = self.details()?;
+                let user_id = self.user_id.clone();
+                spawn(async move {
+                    if api.token().is_expired() {
+                        api.refresh().await.log_error();
+                    }
+                    match api.create_playlist(user_id, details).await {
+                        Ok(playlist) => {
+                            let uri = Uri::playlist(playlist.id);
+                            library_index::set_saved(uri.clone(), true);
+                            tx.send(Event::GoTo(GoTo::Playlist(uri))).log_error();
+                        }
+                        Err(error) => mutation::notify(format!("Couldn't create the playlist: {error}")),
+                    }
+                });
+            }
+            PlaylistForm::Edit(uri) => {
+                let details = self.details()?;
+                let uri = uri.clone();
+                let previous = self.previous.clone();
+                let name = self.name.clone();
+                spawn(async move {
+                    if api.token().is_expired() {
+                        api.refresh().await.log_error();
+                    }
+                    match api.update_playlist_details(uri.id(), details).await {
+                        Ok(()) => {
+                            if let Some(details) = previous {
+                                undo::push(format!("Edited {name}"), Inverse::Details { playlist: uri.clone(), details });
                             }
-                            api.unfollow_playlist(uri).await.log_error();
-                            callback(false).log_error()
-                        });},
-                        Resource::Show => {
-                            tokio::spawn(async move {
-                                if api.token().is_expired() {
-                                    api.refresh().await.log_error();
-                                }
-                                api.remove_saved_shows([uri]).await.log_error();
-                                callback(false).log_error()
-                            });
-                        },
-                        _ => {}
+                            tx.send(Event::PlaylistUpdated(uri)).log_error();
+                        }
+                        Err(error) => mutation::notify(format!("Couldn't edit the playlist: {error}")),
+                    }
+                });
+            }
+            PlaylistForm::Cover(uri) => {
+                let image = self.cover()?;
+                let uri = uri.clone();
+                spawn(async move {
+                    if api.token().is_expired() {
+                        api.refresh().await.log_error();
+                    }
+                    match api.add_playlist_cover_image(uri.id(), image).await {
+                        Ok(()) => tx.send(Event::PlaylistUpdated(uri)).log_error(),
+                        Err(error) => mutation::notify(format!("Couldn't upload the cover: {error}")),
                     }
+                });
+            }
+            PlaylistForm::Delete(uri, remove) => {
+                remove.run(&api, &tx);
+                tx.send(Event::PlaylistDeleted(uri.clone())).log_error();
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for PlaylistFormState {
+    fn default() -> Self {
+        Self::new(PlaylistForm::Create, None, String::new())
+    }
+}
+
+impl Component for PlaylistFormState {
+    fn key(&mut self, key: KeyEvent, _ctx: &Context) -> Handled {
+        if !key.modifiers.difference(KeyModifiers::SHIFT).is_empty() {
+            return Handled::Ignored;
+        }
+        match (self.fields.get_mut(self.selected), key.code) {
+            (Some(FormField::Text(_, value)), KeyCode::Char(c)) => value.push(c),
+            (Some(FormField::Text(_, value)), KeyCode::Backspace) => {
+                value.pop();
+            }
+            (Some(FormField::Toggle(_, value)), KeyCode::Char(' ')) => *value = !*value,
+            // Other keys would run their bindings while the form is being filled in
+            (Some(FormField::Toggle(..)), KeyCode::Char(_)) => {},
+            _ => return Handled::Ignored,
+        }
+        self.error = None;
+        Handled::Consumed
+    }
+
+    fn text_input(&self) -> bool {
+        !self.fields.is_empty()
+    }
+
+    fn event(&mut self, event: &Event, ctx: &Context) -> color_eyre::Result<Handled> {
+        Ok(match event {
+            Event::Down | Event::Tab => { self.down(); Handled::Consumed },
+            Event::Up | Event::Backtab => { self.up(); Handled::Consumed },
+            Event::Select => match self.submit(ctx.api, ctx.tx) {
+                Ok(()) => Handled::Close,
+                Err(error) => {
+                    self.error = Some(error);
+                    Handled::Consumed
                 }
-                Action::AddToPlaylist(uri) => {
-                    tx.send(Event::OpenAddToPlaylist(uri.clone())).log_error();
-                },
-                Action::AddToQueue(uri) => {
-                    let api = api.clone();
-                    let uri = uri.clone();
-                    tokio::spawn(async move {
-                        if api.token().is_expired() {
-                            api.refresh().await.log_error();
-                        }
-                        api.add_to_queue(uri, None).await.log_error();
-                    });
-                },
-                Action::GoTo(goto) => {
-                    tx.send(Event::GoTo(goto.clone())).log_error();
-                },
-            };
-            return true;
+            },
+            _ => Handled::Ignored,
+        })
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Widget::render(&*self, area, buf);
+    }
+}
+
+/// Most tracks spotify recommends at once is 100, a radio plays half of that
+const RADIO_SIZE: usize = 50;
+
+// Labels of the radio form's fields. The genre is only asked for when a radio has no seeds.
+const GENRE: &str = "Genre";
+const ENERGY: &str = "Energy (0-100)";
+const TEMPO: &str = "Tempo (BPM)";
+const VALENCE: &str = "Valence (0-100)";
+const SAVE_RADIO: &str = "Save as Playlist";
+
+#[derive(Debug, Clone)]
+pub struct RadioState {
+    pub radio: Radio,
+    pub fields: Vec<FormField>,
+    pub selected: usize,
+    /// Why the radio couldn't be started
+    pub error: Option<String>,
+    /// Genres spotify can seed a radio with, fetched while a genre is picked
+    pub genres: Shared<Locked<Loading<Vec<String>>>>,
+    /// Radios are saved as playlists for this user
+    user_id: String,
+}
+
+impl RadioState {
+    /// Form for tuning the radio, leaving every target attribute open
+    pub fn new(radio: Radio, user_id: String) -> Self {
+        let mut fields = Vec::new();
+        if radio.seeds.is_empty() {
+            fields.push(FormField::Text(GENRE, String::new()));
         }
-        false
+        fields.extend([
+            FormField::Text(ENERGY, String::new()),
+            FormField::Text(TEMPO, String::new()),
+            FormField::Text(VALENCE, String::new()),
+            FormField::Toggle(SAVE_RADIO, false),
+        ]);
+
+        Self {
+            radio,
+            fields,
+            selected: 0,
+            error: None,
+            genres: Shared::default(),
+            user_id,
+        }
+    }
+
+    pub fn down(&mut self) {
+        self.selected = (self.selected + 1) % self.fields.len();
+    }
+
+    pub fn up(&mut self) {
+        self.selected = (self.selected + self.fields.len() - 1) % self.fields.len();
+    }
+
+    fn field(&self, label: &str) -> Option<&FormField> {
+        self.fields.iter().find(|field| field.label() == label)
+    }
+
+    fn text(&self, label: &str) -> &str {
+        match self.field(label) {
+            Some(FormField::Text(_, value)) => value.trim(),
+            _ => "",
+        }
+    }
+
+    /// True while the genre is being typed in
+    pub fn picking_genre(&self) -> bool {
+        self.fields.get(self.selected).is_some_and(|field| field.label() == GENRE)
+    }
+
+    /// Genres starting with what was typed, the first being what the genre completes to
+    pub fn matching_genres(&self) -> Vec<String> {
+        let typed = self.text(GENRE).to_lowercase();
+        match self.genres.lock().unwrap().as_ref() {
+            Loading::Some(genres) => genres.iter().filter(|genre| genre.starts_with(&typed)).cloned().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Target for an attribute given as a percent, sent to spotify as a fraction
+    fn percent(&self, label: &str) -> Result<Option<f32>, String> {
+        let text = self.text(label);
+        if text.is_empty() {
+            return Ok(None);
+        }
+        match text.parse::<f32>() {
+            Ok(value) if (0.0..=100.0).contains(&value) => Ok(Some(value / 100.0)),
+            _ => Err(format!("{} must be from 0 to 100", label.split(" (").next().unwrap_or(label))),
+        }
+    }
+
+    /// Seeds and targets to fetch the recommendations with
+    fn seed(&self) -> Result<(String, RecommendationSeed), String> {
+        let (name, seed_ids) = if self.radio.seeds.is_empty() {
+            let genre = self.text(GENRE).to_lowercase();
+            match self.genres.lock().unwrap().as_ref() {
+                Loading::Some(genres) if !genres.contains(&genre) => {
+                    return Err(format!("{genre} isn't a genre Spotify knows, press right to complete it"));
+                }
+                _ if genre.is_empty() => return Err("Pick a genre to start the radio from".into()),
+                _ => (genre.clone(), vec![SeedId::genre(genre)]),
+            }
+        } else {
+            (self.radio.name.clone(), self.radio.seeds.clone())
+        };
+
+        let tempo = match self.text(TEMPO) {
+            "" => None,
+            text => Some(text.parse::<f32>().ok().filter(|bpm| *bpm > 0.0).ok_or("Tempo must be a number of beats per minute")?),
+        };
+        Ok((name, RecommendationSeed {
+            seed_ids,
+            target_energy: self.percent(ENERGY)?,
+            target_tempo: tempo,
+            target_valence: self.percent(VALENCE)?,
+            ..Default::default()
+        }))
+    }
+
+    /// Fetch the recommendations and play them as a queue, or save them as a new playlist.
+    /// Returns why the radio couldn't be started so the form can stay open.
+    pub fn submit(&self, api: &Pkce, tx: &mpsc::UnboundedSender<Event>) -> Result<(), String> {
+        let (name, seed) = self.seed()?;
+        let save = matches!(self.field(SAVE_RADIO), Some(FormField::Toggle(_, true)));
+        let user_id = self.user_id.clone();
+        let api = api.clone();
+        let tx = tx.clone();
+        spawn(async move {
+            if api.token().is_expired() {
+                api.refresh().await.log_error();
+            }
+            let uris = match api.recommendations::<RADIO_SIZE, _>(None, seed).await {
+                Ok(recommendations) => recommendations.tracks.into_iter().map(|t| t.uri).collect::<Vec<_>>(),
+                Err(error) => {
+                    mutation::notify(format!("Couldn't start the radio: {error}"));
+                    return;
+                }
+            };
+            if uris.is_empty() {
+                mutation::notify(format!("Spotify has nothing to recommend for {name}"));
+                return;
+            }
+
+            if !save {
+                tx.send(Event::Play(Play::queue(uris))).log_error();
+                return;
+            }
+            let details = PlaylistDetails::new().name(format!("{name} Radio")).public(false);
+            let result = async {
+                let playlist = api.create_playlist(user_id, details).await?;
+                for batch in uris.chunks(ADD_ITEMS_BATCH) {
+                    api.add_items(playlist.id.clone(), batch.iter().cloned(), None).await?;
+                }
+                Ok::<_, tupy::Error>(Uri::playlist(playlist.id))
+            };
+            match result.await {
+                Ok(uri) => {
+                    library_index::set_saved(uri.clone(), true);
+                    tx.send(Event::GoTo(GoTo::Playlist(uri))).log_error();
+                }
+                Err(error) => mutation::notify(format!("Couldn't save the radio: {error}")),
+            }
+        });
+        Ok(())
     }
 }
 
+impl Default for RadioState {
+    fn default() -> Self {
+        Self::new(Radio::default(), String::new())
+    }
+}
+
+impl Component for RadioState {
+    fn key(&mut self, key: KeyEvent, _ctx: &Context) -> Handled {
+        if !key.modifiers.difference(KeyModifiers::SHIFT).is_empty() {
+            return Handled::Ignored;
+        }
+        if self.picking_genre() && key.code == KeyCode::Right {
+            if let (Some(genre), Some(FormField::Text(_, value))) = (self.matching_genres().first(), self.fields.get_mut(self.selected)) {
+                *value = genre.clone();
+            }
+            self.error = None;
+            return Handled::Consumed;
+        }
+        match (self.fields.get_mut(self.selected), key.code) {
+            (Some(FormField::Text(_, value)), KeyCode::Char(c)) => value.push(c),
+            (Some(FormField::Text(_, value)), KeyCode::Backspace) => {
+                value.pop();
+            }
+            (Some(FormField::Toggle(_, value)), KeyCode::Char(' ')) => *value = !*value,
+            // Other keys would run their bindings while the form is being filled in
+            (Some(FormField::Toggle(..)), KeyCode::Char(_)) => {},
+            _ => return Handled::Ignored,
+        }
+        self.error = None;
+        Handled::Consumed
+    }
+
+    fn text_input(&self) -> bool {
+        true
+    }
+
+    fn event(&mut self, event: &Event, ctx: &Context) -> color_eyre::Result<Handled> {
+        Ok(match event {
+            Event::Down | Event::Tab => { self.down(); Handled::Consumed },
+            Event::Up | Event::Backtab => { self.up(); Handled::Consumed },
+            Event::Select => match self.submit(ctx.api, ctx.tx) {
+                Ok(()) => Handled::Close,
+                Err(error) => {
+                    self.error = Some(error);
+                    Handled::Consumed
+                }
+            },
+            _ => Handled::Ignored,
+        })
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Widget::render(&*self, area, buf);
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct ModalState {
@@ -301,6 +1096,10 @@ pub struct ModalState {
     pub actions: Shared<Locked<ActionState>>,
     pub add_to_playlist: Shared<Locked<Option<AddToPlaylistState>>>,
     pub artists: Shared<Locked<ArtistsState>>,
+    pub find: Shared<Locked<FindState>>,
+    pub command: Shared<Locked<CommandState>>,
+    pub playlist_form: Shared<Locked<PlaylistFormState>>,
+    pub radio: Shared<Locked<RadioState>>,
 }
 
 impl Default for ModalState {
@@ -310,10 +1109,33 @@ impl Default for ModalState {
             go_to: Shared::new(Locked::new(GoToState::new(vec![
                 (key!('_' + SHIFT), GoTo::Queue),
                 (key!('L' + SHIFT), GoTo::Library),
+                (key!('T' + SHIFT), GoTo::Top),
+                (key!('H' + SHIFT), GoTo::History),
+                (key!('B' + SHIFT), GoTo::Browse),
             ]))),
             actions: Shared::default(),
             artists: Shared::default(),
             add_to_playlist: Shared::default(),
+            find: Shared::default(),
+            command: Shared::new(Locked::new(CommandState::new(command::load_history().log_error_or_default()))),
+            playlist_form: Shared::default(),
+            radio: Shared::default(),
+        }
+    }
+}
+
+impl ModalState {
+    pub fn component(&self, modal: &Modal) -> Shared<Locked<dyn Component>> {
+        match modal {
+            Modal::Devices => self.devices.clone(),
+            Modal::Action => self.actions.clone(),
+            Modal::AddToPlaylist => self.add_to_playlist.clone(),
+            Modal::Artists => self.artists.clone(),
+            Modal::GoTo => self.go_to.clone(),
+            Modal::Find => self.find.clone(),
+            Modal::Command => self.command.clone(),
+            Modal::PlaylistForm => self.playlist_form.clone(),
+            Modal::Radio => self.radio.clone(),
         }
     }
 }
@@ -0,0 +1,66 @@
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
+
+use crate::{errors::LogError, tasks::spawn, Locked};
+
+/// How long the notice for a rolled back change stays on screen
+static NOTICE_DURATION: Duration = Duration::from_secs(5);
+
+lazy_static::lazy_static! {
+    // Notice for the last change that was rolled back
+    static ref NOTICE: Locked<Option<Notice>> = Locked::default();
+}
+
+#[derive(Debug, Clone)]
+struct Notice {
+    message: String,
+    shown: Instant,
+}
+
+/// Send the request for a change that was already made to the state
+///
+/// The change is shown right away instead of waiting on spotify. If the request fails the
+/// change is undone with `rollback` and a notice is shown saying what couldn't be done.
+pub fn commit<F, R>(what: &'static str, request: F, rollback: R)
+where
+    F: Future<Output = Result<(), tupy::Error>> + Send + 'static,
+    R: FnOnce() + Send + 'static,
+{
+    spawn(async move {
+        if let Err(error) = request.await {
+            rollback();
+            notify(format!("Couldn't {what}: {error}"));
+            Err::<(), _>(error).log_error();
+        }
+    });
+}
+
+/// Show a notice at the top of the screen for a few seconds
+pub fn notify(message: String) {
+    *NOTICE.lock().unwrap() = Some(Notice {
+        message,
+        shown: Instant::now(),
+    });
+}
+
+/// Message of the notice if it hasn't expired yet
+pub fn notice() -> Option<String> {
+    NOTICE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .filter(|notice| notice.shown.elapsed() < NOTICE_DURATION)
+        .map(|notice| notice.message.clone())
+}
+
+/// Clear the notice once it expires. Returns true if it was cleared and needs to be redrawn.
+pub fn clear_expired() -> bool {
+    let mut notice = NOTICE.lock().unwrap();
+    if notice.as_ref().is_some_and(|notice| notice.shown.elapsed() >= NOTICE_DURATION) {
+        *notice = None;
+        return true;
+    }
+    false
+}
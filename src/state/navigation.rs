@@ -0,0 +1,54 @@
+use super::{actions::GoTo, window::landing::Landing};
+
+/// Most pages kept to go back to. Landings are kept in memory so the oldest are dropped.
+static MAX_HISTORY: usize = 50;
+
+/// Page that was navigated away from
+#[derive(Debug)]
+pub struct Entry {
+    pub goto: GoTo,
+    /// Shown in the breadcrumb
+    pub label: String,
+    /// Landing as it was left, with its selection, page and section, so going back to it doesn't
+    /// fetch it again
+    pub landing: Option<Landing>,
+}
+
+/// Back and forward stacks of the pages that were navigated to
+#[derive(Debug)]
+pub struct Navigation {
+    pub current: GoTo,
+    pub back: Vec<Entry>,
+    pub forward: Vec<Entry>,
+}
+
+impl Default for Navigation {
+    fn default() -> Self {
+        Self {
+            current: GoTo::Library,
+            back: Vec::new(),
+            forward: Vec::new(),
+        }
+    }
+}
+
+impl Navigation {
+    /// Navigate to a new page from the entry that was left. Clears the forward stack.
+    pub fn visit(&mut self, left: Entry, goto: GoTo) {
+        self.push_back(left);
+        self.forward.clear();
+        self.current = goto;
+    }
+
+    pub fn push_back(&mut self, entry: Entry) {
+        self.back.push(entry);
+        if self.back.len() > MAX_HISTORY {
+            self.back.remove(0);
+        }
+    }
+
+    /// Labels of the pages that can be gone back to, oldest first
+    pub fn breadcrumb(&self) -> impl Iterator<Item = &str> {
+        self.back.iter().map(|entry| entry.label.as_str())
+    }
+}
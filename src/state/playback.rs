@@ -2,9 +2,10 @@ use std::collections::HashMap;
 
 use tupy::{api::{response::{self, Context, Device, PlaybackAction, PlaybackActionScope, PlaybackItem, Repeat}, Uri}, DateTime, Duration, Local};
 
+use crate::state::library_index;
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Playback {
-    pub saved: bool,
     pub context: Option<Context>,
     pub device: Option<Device>,
     pub repeat: Repeat,
@@ -16,6 +17,20 @@ pub struct Playback {
 }
 
 impl Playback {
+    /// Uri of the track or episode that is playing
+    pub fn uri(&self) -> Option<Uri> {
+        match &self.item {
+            PlaybackItem::Track(t) => Some(t.uri.clone()),
+            PlaybackItem::Episode(e) => Some(e.uri.clone()),
+            _ => None,
+        }
+    }
+
+    /// Whether the item that is playing is saved, from the library index
+    pub fn saved(&self) -> bool {
+        self.uri().map(|uri| library_index::is_saved(&uri)).unwrap_or(false)
+    }
+
     pub fn set_repeat(&mut self, repeat: Repeat) {
         self.repeat = repeat;
     }
@@ -53,7 +68,6 @@ impl Playback {
 impl From<response::Playback> for Playback {
     fn from(pb: response::Playback) -> Self {
         Self {
-            saved: false,
             device: pb.device,
             repeat: pb.repeat,
             shuffle: pb.shuffle,
@@ -99,6 +113,8 @@ impl Item {
 pub struct PlaybackState {
     pub last_playback_poll: DateTime<Local>,
     pub playback: Option<Playback>,
+    /// False until the playback has been fetched for the first time
+    pub loaded: bool,
 }
 
 impl PlaybackState {
@@ -106,22 +122,64 @@ impl PlaybackState {
         Self {
             last_playback_poll: Local::now(),
             playback,
+            loaded: true,
         }
     }
 
     pub fn set_playback(&mut self, playback: Option<Playback>) -> bool {
         self.last_playback_poll = Local::now();
+        self.loaded = true;
         let diff = self.playback != playback;
-        let saved = self.playback.as_ref().map(|pb| pb.saved).unwrap_or(false);
-
         self.playback = playback;
-        self.set_saved(saved);
         diff
     }
 
-    pub fn set_saved(&mut self, saved: bool) {
+    pub fn saved(&self) -> bool {
+        self.playback.as_ref().map(Playback::saved).unwrap_or(false)
+    }
+
+    /// Progress of the current item including the time elapsed since the last poll
+    pub fn progress(&self) -> Option<Duration> {
+        self.playback.as_ref().map(|pb| {
+            let mut progress = pb.progress.unwrap_or(Duration::zero());
+            if pb.is_playing {
+                progress += Local::now() - self.last_playback_poll;
+            }
+            progress
+        })
+    }
+
+    /// Duration of the current item if it is a track or episode
+    pub fn duration(&self) -> Option<Duration> {
+        self.playback.as_ref().and_then(|pb| match &pb.item {
+            PlaybackItem::Track(t) => Some(t.duration),
+            PlaybackItem::Episode(e) => Some(e.duration),
+            _ => None,
+        })
+    }
+
+    /// Optimistically update the progress so it doesn't snap back before the next poll
+    pub fn set_progress(&mut self, progress: Duration) {
+        if let Some(playback) = self.playback.as_mut() {
+            playback.set_progress(Some(progress));
+            self.last_playback_poll = Local::now();
+        }
+    }
+
+    /// Optimistically play or pause, keeping the progress where it is
+    pub fn set_playing(&mut self, is_playing: bool) {
+        if let Some(progress) = self.progress() {
+            self.set_progress(progress);
+        }
         if let Some(playback) = self.playback.as_mut() {
-            playback.saved = saved;
+            playback.set_playing(is_playing);
+        }
+    }
+
+    /// Optimistically update the volume of the device so it doesn't wait on the next poll
+    pub fn set_volume(&mut self, volume: u8) {
+        if let Some(device) = self.playback.as_mut().and_then(|pb| pb.device.as_mut()) {
+            device.volume_percent = volume;
         }
     }
 
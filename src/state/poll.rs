@@ -0,0 +1,129 @@
+use std::time::{Duration, Instant};
+
+use super::playback::PlaybackState;
+
+/// Delay after a playback command before polling so spotify has applied the change
+static COMMAND_DELAY: Duration = Duration::from_millis(500);
+/// Extra time after the predicted end of an item so the next item has started
+static END_OF_ITEM_DELAY: Duration = Duration::from_millis(750);
+
+/// Longest time between polls while something is playing. Catches changes made from other devices
+static PLAYING_INTERVAL: Duration = Duration::from_secs(10);
+/// Time between polls while playback is paused or there is no playback
+static PAUSED_INTERVAL: Duration = Duration::from_secs(20);
+/// Time between polls while the terminal doesn't have focus
+static UNFOCUSED_INTERVAL: Duration = Duration::from_secs(60);
+
+static MIN_BACKOFF: Duration = Duration::from_secs(5);
+static MAX_BACKOFF: Duration = Duration::from_secs(120);
+
+/// Decides when the playback state should be fetched from spotify
+///
+/// Polls right after user commands and at the predicted end of the current item, otherwise
+/// polling slowly to catch changes made outside of rataify. Polling backs off when playback is
+/// paused, the app is unfocused, or spotify responds with a rate limit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlaybackPoll {
+    next: Instant,
+    focused: bool,
+    in_flight: bool,
+    // A command finished while a poll was in flight, so that poll may not include it
+    soon: bool,
+    backoff: Option<Duration>,
+}
+
+impl Default for PlaybackPoll {
+    fn default() -> Self {
+        Self {
+            next: Instant::now(),
+            focused: true,
+            in_flight: false,
+            soon: false,
+            backoff: None,
+        }
+    }
+}
+
+impl PlaybackPoll {
+    /// True if the playback state should be fetched now. Marks the poll as in flight until
+    /// [`PlaybackPoll::schedule`] or [`PlaybackPoll::rate_limited`] is called.
+    pub fn poll(&mut self) -> bool {
+        if self.in_flight || Instant::now() < self.next {
+            return false;
+        }
+        self.in_flight = true;
+        true
+    }
+
+    /// Poll shortly after a command that changes playback was sent
+    pub fn poll_soon(&mut self) {
+        if self.in_flight {
+            self.soon = true;
+        } else if self.backoff.is_none() {
+            self.next = self.next.min(Instant::now() + COMMAND_DELAY);
+        }
+    }
+
+    /// Schedule the next poll from the playback state that was just fetched
+    pub fn schedule(&mut self, playback: &PlaybackState) {
+        self.in_flight = false;
+        self.backoff = None;
+
+        let mut interval = match playback.playback.as_ref() {
+            Some(pb) if pb.is_playing => PLAYING_INTERVAL,
+            _ => PAUSED_INTERVAL,
+        };
+        if !self.focused {
+            interval = UNFOCUSED_INTERVAL;
+        }
+
+        // Poll right when the current item is expected to end so the next one shows up instantly
+        if let (Some(progress), Some(duration), Some(true)) = (
+            playback.progress(),
+            playback.duration(),
+            playback.playback.as_ref().map(|pb| pb.is_playing),
+        ) {
+            let remaining = (duration - progress).to_std().unwrap_or_default();
+            interval = interval.min(remaining + END_OF_ITEM_DELAY);
+        }
+
+        if self.soon {
+            self.soon = false;
+            interval = interval.min(COMMAND_DELAY);
+        }
+
+        self.next = Instant::now() + interval;
+    }
+
+    /// Spotify rejected the poll for too many requests, back off exponentially
+    pub fn rate_limited(&mut self) {
+        self.in_flight = false;
+        self.soon = false;
+        let backoff = self
+            .backoff
+            .map(|b| (b * 2).min(MAX_BACKOFF))
+            .unwrap_or(MIN_BACKOFF);
+        self.backoff = Some(backoff);
+        self.next = Instant::now() + backoff;
+    }
+
+    /// Poll right away, ignoring any backoff, e.g. when retrying after being offline
+    pub fn retry(&mut self) {
+        self.backoff = None;
+        if self.in_flight {
+            self.soon = true;
+        } else {
+            self.next = Instant::now();
+        }
+    }
+
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        if focused {
+            // Playback may have changed while unfocused
+            self.poll_soon();
+        } else {
+            self.next = self.next.max(Instant::now() + UNFOCUSED_INTERVAL);
+        }
+    }
+}
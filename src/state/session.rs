@@ -0,0 +1,175 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use color_eyre::{eyre::Error, Result};
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use tupy::api::Uri;
+
+use crate::{errors::LogError, Locked};
+
+use super::{
+    actions::GoTo,
+    navigation::Entry,
+    window::library::{FromSpotify, LibraryTab},
+    State, Window,
+};
+
+lazy_static::lazy_static! {
+    pub static ref SESSION_FILE_PATH: PathBuf = dirs::data_local_dir().unwrap().join("rataify").join("session.json");
+    // Session recorded after the last input, saved if the app panics
+    static ref RECORDED: Locked<Option<Session>> = Locked::default();
+}
+
+/// Window or landing that is saved with the session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Page {
+    Window(Window),
+    Playlist(String),
+    Album(String),
+    Show(String),
+    Audiobook(String),
+    Artist(String),
+}
+
+impl Page {
+    pub fn from_goto(goto: &GoTo) -> Option<Self> {
+        Some(match goto {
+            GoTo::Library => Self::Window(Window::Library),
+            GoTo::Queue => Self::Window(Window::Queue),
+            GoTo::Top => Self::Window(Window::Top),
+            GoTo::History => Self::Window(Window::History),
+            GoTo::Browse => Self::Window(Window::Browse),
+            GoTo::Playlist(uri) => Self::Playlist(uri.to_string()),
+            GoTo::Album(uri) => Self::Album(uri.to_string()),
+            GoTo::Show(uri) => Self::Show(uri.to_string()),
+            GoTo::Audiobook(uri) => Self::Audiobook(uri.to_string()),
+            GoTo::Artist(uri) => Self::Artist(uri.to_string()),
+            _ => return None,
+        })
+    }
+
+    pub fn goto(&self) -> Result<GoTo> {
+        let parse = |uri: &str| uri.parse::<Uri>().map_err(Error::msg);
+        Ok(match self {
+            Self::Window(Window::Library) => GoTo::Library,
+            Self::Window(Window::Queue) => GoTo::Queue,
+            Self::Window(Window::Top) => GoTo::Top,
+            Self::Window(Window::History) => GoTo::History,
+            Self::Window(Window::Browse) => GoTo::Browse,
+            Self::Window(Window::Landing) => return Err(Error::msg("Landing window saved without a uri")),
+            Self::Playlist(uri) => GoTo::Playlist(parse(uri)?),
+            Self::Album(uri) => GoTo::Album(parse(uri)?),
+            Self::Show(uri) => GoTo::Show(parse(uri)?),
+            Self::Audiobook(uri) => GoTo::Audiobook(parse(uri)?),
+            Self::Artist(uri) => GoTo::Artist(parse(uri)?),
+        })
+    }
+}
+
+/// Page in the navigation history that is saved with the session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionEntry {
+    pub page: Page,
+    pub label: String,
+}
+
+impl SessionEntry {
+    fn from_entry(entry: &Entry) -> Option<Self> {
+        Some(Self {
+            page: Page::from_goto(&entry.goto)?,
+            label: entry.label.clone(),
+        })
+    }
+
+    /// Entry in the navigation history. The landing is fetched again when it is navigated to.
+    pub fn into_entry(self) -> Option<Entry> {
+        Some(Entry {
+            goto: self.page.goto().log_error_ok()?,
+            label: self.label,
+            landing: None,
+        })
+    }
+}
+
+/// What was shown when rataify last exited, restored on the next launch
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    pub page: Option<Page>,
+    /// Offset of the page shown in the landing
+    pub landing_offset: usize,
+    pub back: Vec<SessionEntry>,
+    pub forward: Vec<SessionEntry>,
+
+    pub library_tab: LibraryTab,
+    pub spotify_playlist: FromSpotify,
+    /// Offset of the page shown in each library tab
+    pub library_offsets: HashMap<LibraryTab, usize>,
+}
+
+impl Session {
+    pub fn new(state: &State) -> Self {
+        let (page, back, forward) = {
+            let navigation = state.navigation.lock().unwrap();
+            (
+                Page::from_goto(&navigation.current),
+                navigation.back.iter().filter_map(SessionEntry::from_entry).collect(),
+                navigation.forward.iter().filter_map(SessionEntry::from_entry).collect(),
+            )
+        };
+
+        let landing_offset = match state.window {
+            Window::Landing => state.window_state.landing.lock().unwrap().offset(),
+            _ => 0,
+        };
+
+        let library = state.window_state.library.lock().unwrap();
+        Self {
+            page,
+            landing_offset,
+            back,
+            forward,
+
+            library_tab: library.selected_tab,
+            spotify_playlist: library.selected_spotify_playlist,
+            library_offsets: LibraryTab::iter()
+                .map(|tab| (tab, library.offset(tab)))
+                .filter(|(_, offset)| *offset > 0)
+                .collect(),
+        }
+    }
+
+    /// Load the last session if there is one
+    pub fn load() -> Result<Option<Self>> {
+        if !SESSION_FILE_PATH.exists() {
+            return Ok(None);
+        }
+        let session = std::fs::read_to_string(SESSION_FILE_PATH.as_path())?;
+        Ok(Some(serde_json::from_str(&session)?))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = SESSION_FILE_PATH.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(SESSION_FILE_PATH.as_path(), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Record the session so it can be saved if the app panics
+    pub fn record(state: &State) {
+        let session = Self::new(state);
+        *RECORDED.lock().unwrap() = Some(session);
+    }
+
+    /// Save the last recorded session. Doesn't wait on the record, as it is called from the
+    /// panic hook.
+    pub fn save_recorded() {
+        if let Ok(recorded) = RECORDED.try_lock() {
+            if let Some(session) = recorded.as_ref() {
+                session.save().log_error();
+            }
+        }
+    }
+}
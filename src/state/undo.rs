@@ -0,0 +1,100 @@
+use color_eyre::Result;
+use tokio::sync::mpsc;
+use tupy::api::{flow::{AuthFlow, Pkce}, request::PlaylistDetails, Uri, UserApi};
+
+use crate::{app::Event, errors::LogError, Locked, Shared};
+
+use super::{actions, modal::ADD_ITEMS_BATCH, mutation};
+
+/// Most changes kept to be undone, dropping the oldest first
+static MAX_UNDO: usize = 20;
+
+lazy_static::lazy_static! {
+    // Changes that can be undone, with the last one made at the end
+    static ref UNDO: Locked<Vec<Change>> = Locked::default();
+}
+
+/// Request that puts back what a change removed
+pub enum Inverse {
+    /// Save or follow the items again. The callback is the one the items were removed with.
+    Save(Vec<Uri>, Option<Shared<dyn Fn(bool) -> Result<()> + Sync + Send>>),
+    /// Add items back to a playlist at every position they were removed from, in the order of
+    /// the positions
+    AddItems {
+        playlist: Uri,
+        items: Vec<(usize, Uri)>,
+    },
+    /// Put back the details a playlist had before it was edited
+    Details {
+        playlist: Uri,
+        details: PlaylistDetails,
+    },
+}
+
+struct Change {
+    what: String,
+    inverse: Inverse,
+}
+
+/// Remember how to undo a change that was made, offering to undo it in a notice
+pub fn push(what: String, inverse: Inverse) {
+    mutation::notify(format!("{what}, press u to undo"));
+    let mut undo = UNDO.lock().unwrap();
+    undo.push(Change { what, inverse });
+    if undo.len() > MAX_UNDO {
+        undo.remove(0);
+    }
+}
+
+/// Undo the last change that was made
+pub fn undo(api: &Pkce, tx: &mpsc::UnboundedSender<Event>) {
+    let Some(Change { what, inverse }) = UNDO.lock().unwrap().pop() else {
+        mutation::notify("Nothing to undo".into());
+        return;
+    };
+    mutation::notify(format!("Undid: {what}"));
+
+    let api = api.clone();
+    let tx = tx.clone();
+    match inverse {
+        Inverse::Save(uris, callback) => actions::save(&api, uris, true, callback),
+        Inverse::AddItems { playlist, items } => mutation::commit(
+            "undo",
+            async move {
+                if api.token().is_expired() {
+                    api.refresh().await.log_error();
+                }
+                // Adding in the order of the positions puts each item back where it was, and
+                // items at positions next to each other are added together
+                let mut runs: Vec<(usize, Vec<Uri>)> = Vec::new();
+                for (position, uri) in items {
+                    match runs.last_mut() {
+                        Some((start, uris)) if *start + uris.len() == position => uris.push(uri),
+                        _ => runs.push((position, vec![uri])),
+                    }
+                }
+                for (position, uris) in runs {
+                    for (i, batch) in uris.chunks(ADD_ITEMS_BATCH).enumerate() {
+                        let at = position + i * ADD_ITEMS_BATCH;
+                        api.add_items(playlist.id(), batch.iter().cloned(), Some(at)).await?;
+                    }
+                }
+                tx.send(Event::PlaylistUpdated(playlist)).log_error();
+                Ok(())
+            },
+            || {},
+        ),
+        Inverse::Details { playlist, details } => mutation::commit(
+            "undo",
+            async move {
+                if api.token().is_expired() {
+                    api.refresh().await.log_error();
+                }
+                api.update_playlist_details(playlist.id(), details).await?;
+                tx.send(Event::PlaylistUpdated(playlist)).log_error();
+                Ok(())
+            },
+            || {},
+        ),
+    }
+}
@@ -0,0 +1,630 @@
+use std::cmp::Ordering;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use tupy::{
+    api::{response::{
+        Artist, Audiobook, Chapters, FollowedArtists, Item, PagedPlaylists, PlaylistItemInfo, SavedAlbum, SavedAlbums,
+        SavedAudiobooks, SavedShow, SavedShows, SimplifiedAlbum, SimplifiedChapter, SimplifiedEpisode, SimplifiedPlaylist,
+        SimplifiedTrack,
+    }, Uri},
+    DateTime, Duration, Local, NaiveDate,
+};
+
+use super::{component::Handled, wrappers::{GetUri, Saved}};
+
+/// Column a table can be sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortColumn {
+    Name,
+    Artist,
+    Duration,
+    Added,
+    Released,
+}
+
+impl SortColumn {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Name => "Name",
+            Self::Artist => "Artist",
+            Self::Duration => "Duration",
+            Self::Added => "Added",
+            Self::Released => "Released",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sort {
+    pub column: SortColumn,
+    pub descending: bool,
+}
+
+/// Filter and sort applied to the rows of a table
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TableView {
+    pub filter: String,
+    /// True while the filter bar is being typed in
+    pub editing: bool,
+    pub sort: Option<Sort>,
+    /// Items marked for a batch action in the order they were marked
+    pub marks: Vec<Uri>,
+    /// Row a visual selection was started from. It covers every row up to the selected one.
+    pub visual: Option<usize>,
+}
+
+impl TableView {
+    /// True if the rows are filtered or sorted, which needs every page of the table
+    pub fn is_active(&self) -> bool {
+        !self.filter.trim().is_empty() || self.sort.is_some()
+    }
+
+    /// True if the filter bar or sort order is shown under the table
+    pub fn is_shown(&self) -> bool {
+        self.editing || !self.filter.is_empty() || self.sort.is_some()
+    }
+
+    /// True if rows are marked or a visual selection is started
+    pub fn has_marks(&self) -> bool {
+        !self.marks.is_empty() || self.visual.is_some()
+    }
+
+    /// Items marked for a batch action along with the rows of the visual selection, given the
+    /// items shown in the table and the selected row
+    pub fn marked(&self, uris: &[Uri], selected: Option<usize>) -> Vec<Uri> {
+        let mut marked = self.marks.clone();
+        if let (Some(anchor), Some(selected)) = (self.visual, selected) {
+            let (start, end) = (anchor.min(selected), anchor.max(selected));
+            for uri in uris.iter().take(end + 1).skip(start) {
+                if !marked.contains(uri) {
+                    marked.push(uri.clone());
+                }
+            }
+        }
+        marked
+    }
+
+    /// Handle a key for marking rows. Returns false if the key isn't used.
+    ///
+    /// `v` starts a visual selection and `v` again marks the rows it covers. `m` marks or unmarks
+    /// the selected row and `esc` clears every mark.
+    pub fn mark_key(&mut self, key: KeyEvent, uris: &[Uri], selected: Option<usize>) -> bool {
+        if !key.modifiers.difference(KeyModifiers::SHIFT).is_empty() {
+            return false;
+        }
+
+        match key.code {
+            KeyCode::Char('v') if self.visual.is_some() => {
+                self.marks = self.marked(uris, selected);
+                self.visual = None;
+            }
+            KeyCode::Char('v') if selected.is_some_and(|i| i < uris.len()) => self.visual = selected,
+            KeyCode::Char('m') => {
+                let Some(uri) = selected.and_then(|i| uris.get(i)) else {
+                    return false;
+                };
+                match self.marks.iter().position(|u| u == uri) {
+                    Some(i) => {
+                        self.marks.remove(i);
+                    }
+                    None => self.marks.push(uri.clone()),
+                }
+            }
+            KeyCode::Esc if self.has_marks() => {
+                self.marks.clear();
+                self.visual = None;
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// True if every word of the filter is in the name, artist or album of the row
+    pub fn matches<T: Columns>(&self, row: &T) -> bool {
+        let text = format!("{} {} {}", row.name(), row.artist(), row.album()).to_lowercase();
+        self.filter.to_lowercase().split_whitespace().all(|word| text.contains(word))
+    }
+
+    /// Rows that match the filter in the sorted order
+    pub fn apply<T: Columns + Clone>(&self, rows: &[T]) -> Vec<T> {
+        let mut rows = rows.iter().filter(|row| self.matches(*row)).cloned().collect::<Vec<_>>();
+        if let Some(sort) = self.sort {
+            // Stable so rows that are equal keep their original order
+            rows.sort_by(|a, b| {
+                let ordering = compare(sort.column, a, b);
+                if sort.descending { ordering.reverse() } else { ordering }
+            });
+        }
+        rows
+    }
+
+    /// Sort by the next column, going back to the original order after the last one
+    pub fn cycle_sort(&mut self, columns: &[SortColumn]) {
+        let next = match self.sort {
+            None => columns.first(),
+            Some(sort) => columns
+                .iter()
+                .position(|column| *column == sort.column)
+                .and_then(|i| columns.get(i + 1)),
+        };
+        self.sort = next.map(|column| Sort {
+            column: *column,
+            descending: false,
+        });
+    }
+
+    pub fn reverse_sort(&mut self) {
+        if let Some(sort) = self.sort.as_mut() {
+            sort.descending = !sort.descending;
+        }
+    }
+
+    /// Handle a key for the filter bar or sorting. Returns false if the key isn't used.
+    ///
+    /// `f` starts typing in the filter bar, `enter` stops typing and `esc` clears the filter.
+    /// `o` sorts by the next column and `O` reverses the order.
+    pub fn key(&mut self, key: KeyEvent, columns: &[SortColumn]) -> bool {
+        let text = key.modifiers.difference(KeyModifiers::SHIFT).is_empty();
+        if self.editing {
+            match key.code {
+                KeyCode::Char(c) if text => self.filter.push(c),
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                }
+                KeyCode::Enter => self.editing = false,
+                KeyCode::Esc => {
+                    self.editing = false;
+                    self.filter.clear();
+                }
+                _ => return false,
+            }
+            return true;
+        }
+
+        match key.code {
+            KeyCode::Char('f') if text => self.editing = true,
+            KeyCode::Char('o') if text => self.cycle_sort(columns),
+            KeyCode::Char('O') if text => self.reverse_sort(),
+            KeyCode::Esc if !self.filter.is_empty() => self.filter.clear(),
+            _ => return false,
+        }
+        true
+    }
+}
+
+fn compare<T: Columns>(column: SortColumn, a: &T, b: &T) -> Ordering {
+    match column {
+        SortColumn::Name => a.name().to_lowercase().cmp(&b.name().to_lowercase()),
+        SortColumn::Artist => a.artist().to_lowercase().cmp(&b.artist().to_lowercase()),
+        SortColumn::Duration => a.duration().cmp(&b.duration()),
+        SortColumn::Added => a.added_at().cmp(&b.added_at()),
+        SortColumn::Released => a.released().cmp(&b.released()),
+    }
+}
+
+/// Table whose rows can be filtered and sorted
+pub trait Filterable {
+    fn view(&self) -> TableView;
+
+    /// Filter and sort the rows, loading the rest of the table if it is paged
+    fn set_view(&self, view: TableView);
+
+    /// Columns the rows can be sorted by
+    fn sort_columns(&self) -> &'static [SortColumn];
+
+    /// Items of the rows shown in the table
+    fn uris(&self) -> Vec<Uri>;
+}
+
+/// Handle a key for the filter bar or sorting of the table
+pub fn view_key(table: Option<&dyn Filterable>, key: KeyEvent) -> Handled {
+    let Some(table) = table else {
+        return Handled::Ignored;
+    };
+
+    let mut view = table.view();
+    if view.key(key, table.sort_columns()) {
+        table.set_view(view);
+        Handled::Consumed
+    } else {
+        Handled::Ignored
+    }
+}
+
+/// Handle a key for marking rows of the table
+pub fn mark_key(table: Option<&dyn Filterable>, key: KeyEvent, selected: Option<usize>) -> Handled {
+    let Some(table) = table else {
+        return Handled::Ignored;
+    };
+
+    let mut view = table.view();
+    if view.mark_key(key, &table.uris(), selected) {
+        table.set_view(view);
+        Handled::Consumed
+    } else {
+        Handled::Ignored
+    }
+}
+
+/// Items marked in the table, if there are any. A visual selection is turned into marks so the
+/// rows stay marked once the batch action is picked.
+pub fn take_marked(table: Option<&dyn Filterable>, selected: Option<usize>) -> Option<Vec<Uri>> {
+    let table = table?;
+    let mut view = table.view();
+    if !view.has_marks() {
+        return None;
+    }
+
+    view.marks = view.marked(&table.uris(), selected);
+    view.visual = None;
+    let marked = view.marks.clone();
+    table.set_view(view);
+    Some(marked).filter(|marked| !marked.is_empty())
+}
+
+/// Row of a table that can be filtered by its name, artist and album and sorted by its columns
+pub trait Columns {
+    /// Columns the rows can be sorted by
+    const SORT: &'static [SortColumn];
+
+    fn name(&self) -> &str;
+
+    /// Item shown in the row, used to mark it for a batch action
+    fn uri(&self) -> Uri;
+
+    fn artist(&self) -> String {
+        String::new()
+    }
+
+    fn album(&self) -> &str {
+        ""
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        None
+    }
+
+    fn added_at(&self) -> Option<DateTime<Local>> {
+        None
+    }
+
+    fn released(&self) -> Option<NaiveDate> {
+        None
+    }
+}
+
+/// Rows of a table, either a page from spotify or a list of rows
+pub trait Rows {
+    type Row: Columns + Clone + PartialEq;
+
+    fn rows(&self) -> &Vec<Self::Row>;
+    fn rows_mut(&mut self) -> &mut Vec<Self::Row>;
+}
+
+impl<T: Columns + Clone + PartialEq> Rows for Vec<T> {
+    type Row = T;
+
+    fn rows(&self) -> &Vec<Self::Row> {
+        self
+    }
+
+    fn rows_mut(&mut self) -> &mut Vec<Self::Row> {
+        self
+    }
+}
+
+macro_rules! impl_rows {
+    ($($page: ty => $row: ty),* $(,)?) => {
+        $(
+            impl Rows for $page {
+                type Row = $row;
+
+                fn rows(&self) -> &Vec<Self::Row> {
+                    &self.items
+                }
+
+                fn rows_mut(&mut self) -> &mut Vec<Self::Row> {
+                    &mut self.items
+                }
+            }
+        )*
+    };
+}
+
+impl_rows!(
+    PagedPlaylists => SimplifiedPlaylist,
+    FollowedArtists => Artist,
+    SavedAlbums => SavedAlbum,
+    SavedShows => SavedShow,
+    SavedAudiobooks => Audiobook,
+    Chapters => SimplifiedChapter,
+);
+
+fn artists<'a, I: IntoIterator<Item = &'a String>>(names: I) -> String {
+    names.into_iter().map(String::as_str).collect::<Vec<_>>().join(", ")
+}
+
+impl<T: Columns> Columns for Saved<T> {
+    const SORT: &'static [SortColumn] = T::SORT;
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn uri(&self) -> Uri {
+        self.inner.uri()
+    }
+
+    fn artist(&self) -> String {
+        self.inner.artist()
+    }
+
+    fn album(&self) -> &str {
+        self.inner.album()
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        self.inner.duration()
+    }
+
+    fn added_at(&self) -> Option<DateTime<Local>> {
+        self.inner.added_at()
+    }
+
+    fn released(&self) -> Option<NaiveDate> {
+        self.inner.released()
+    }
+}
+
+impl Columns for SimplifiedPlaylist {
+    const SORT: &'static [SortColumn] = &[SortColumn::Name, SortColumn::Artist];
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn uri(&self) -> Uri {
+        self.uri.clone()
+    }
+
+    /// The owner of the playlist
+    fn artist(&self) -> String {
+        self.owner.name.clone().unwrap_or_else(|| self.owner.id.clone())
+    }
+}
+
+impl Columns for Artist {
+    const SORT: &'static [SortColumn] = &[SortColumn::Name];
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn uri(&self) -> Uri {
+        self.uri.clone()
+    }
+}
+
+impl Columns for SavedAlbum {
+    const SORT: &'static [SortColumn] = &[SortColumn::Name, SortColumn::Artist, SortColumn::Added, SortColumn::Released];
+
+    fn name(&self) -> &str {
+        &self.album.name
+    }
+
+    fn uri(&self) -> Uri {
+        self.album.uri.clone()
+    }
+
+    fn artist(&self) -> String {
+        artists(self.album.artists.iter().map(|a| &a.name))
+    }
+
+    fn added_at(&self) -> Option<DateTime<Local>> {
+        Some(self.added_at)
+    }
+
+    fn released(&self) -> Option<NaiveDate> {
+        Some(*self.album.release.as_ref())
+    }
+}
+
+impl Columns for SavedShow {
+    const SORT: &'static [SortColumn] = &[SortColumn::Name, SortColumn::Artist, SortColumn::Added];
+
+    fn name(&self) -> &str {
+        &self.show.name
+    }
+
+    fn uri(&self) -> Uri {
+        self.show.uri.clone()
+    }
+
+    /// The publisher of the show
+    fn artist(&self) -> String {
+        self.show.publisher.clone().unwrap_or_default()
+    }
+
+    fn added_at(&self) -> Option<DateTime<Local>> {
+        Some(self.added_at)
+    }
+}
+
+impl Columns for Audiobook {
+    const SORT: &'static [SortColumn] = &[SortColumn::Name, SortColumn::Artist];
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn uri(&self) -> Uri {
+        self.uri.clone()
+    }
+
+    /// The authors of the audiobook
+    fn artist(&self) -> String {
+        artists(&self.authors)
+    }
+}
+
+impl Columns for SimplifiedChapter {
+    const SORT: &'static [SortColumn] = &[SortColumn::Name, SortColumn::Duration, SortColumn::Released];
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn uri(&self) -> Uri {
+        self.uri.clone()
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        Some(self.duration)
+    }
+
+    fn released(&self) -> Option<NaiveDate> {
+        Some(*self.release.as_ref())
+    }
+}
+
+impl Columns for SimplifiedAlbum {
+    const SORT: &'static [SortColumn] = &[SortColumn::Name, SortColumn::Artist, SortColumn::Released];
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn uri(&self) -> Uri {
+        self.uri.clone()
+    }
+
+    fn artist(&self) -> String {
+        artists(self.artists.iter().map(|a| &a.name))
+    }
+
+    fn released(&self) -> Option<NaiveDate> {
+        Some(*self.release.as_ref())
+    }
+}
+
+impl Columns for SimplifiedTrack {
+    const SORT: &'static [SortColumn] = &[SortColumn::Name, SortColumn::Artist, SortColumn::Duration];
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn uri(&self) -> Uri {
+        self.uri.clone()
+    }
+
+    fn artist(&self) -> String {
+        artists(self.artists.iter().map(|a| &a.name))
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        Some(self.duration)
+    }
+}
+
+impl Columns for SimplifiedEpisode {
+    const SORT: &'static [SortColumn] = &[SortColumn::Name, SortColumn::Duration, SortColumn::Released];
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn uri(&self) -> Uri {
+        self.uri.clone()
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        Some(self.duration)
+    }
+
+    fn released(&self) -> Option<NaiveDate> {
+        Some(*self.release.as_ref())
+    }
+}
+
+impl Columns for Item {
+    const SORT: &'static [SortColumn] = &[SortColumn::Name, SortColumn::Artist, SortColumn::Duration, SortColumn::Released];
+
+    fn name(&self) -> &str {
+        match self {
+            Item::Track(t) => &t.name,
+            Item::Episode(e) => &e.name,
+        }
+    }
+
+    fn uri(&self) -> Uri {
+        self.get_uri()
+    }
+
+    /// The artists of a track or the show of an episode
+    fn artist(&self) -> String {
+        match self {
+            Item::Track(t) => artists(t.artists.iter().map(|a| &a.name)),
+            Item::Episode(e) => e.show.as_ref().map(|s| s.name.clone()).unwrap_or_default(),
+        }
+    }
+
+    fn album(&self) -> &str {
+        match self {
+            Item::Track(t) => &t.album.name,
+            Item::Episode(_) => "",
+        }
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        Some(match self {
+            Item::Track(t) => t.duration,
+            Item::Episode(e) => e.duration,
+        })
+    }
+
+    fn released(&self) -> Option<NaiveDate> {
+        match self {
+            Item::Track(t) => Some(*t.album.release.as_ref()),
+            Item::Episode(e) => e.release.as_ref().map(|r| *r.as_ref()),
+        }
+    }
+}
+
+impl Columns for PlaylistItemInfo {
+    const SORT: &'static [SortColumn] = &[
+        SortColumn::Name,
+        SortColumn::Artist,
+        SortColumn::Duration,
+        SortColumn::Added,
+        SortColumn::Released,
+    ];
+
+    fn name(&self) -> &str {
+        self.item.name()
+    }
+
+    fn uri(&self) -> Uri {
+        self.item.get_uri()
+    }
+
+    fn artist(&self) -> String {
+        self.item.artist()
+    }
+
+    fn album(&self) -> &str {
+        self.item.album()
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        self.item.duration()
+    }
+
+    fn added_at(&self) -> Option<DateTime<Local>> {
+        self.added_at
+    }
+
+    fn released(&self) -> Option<NaiveDate> {
+        self.item.released()
+    }
+}
@@ -0,0 +1,413 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+
+use color_eyre::Result;
+use crossterm::event::KeyEvent;
+use ratatui::{buffer::Buffer, layout::Rect, widgets::{TableState, Widget}};
+use strum::EnumCount;
+use tupy::api::{
+    flow::Pkce,
+    request::Play,
+    response::{Album, Categories, Category, FeaturedPlaylists, NewReleases, Paged, PagedPlaylists, SimplifiedPlaylist},
+    PublicApi,
+};
+
+use super::Pages;
+use crate::{app::Event, key, state::{component::{open_actions, Component, Context, Handled}, actions::{action_label, Action, GoTo}, IterCollection, Loading, Motion}, PAGE_SIZE};
+
+/// Number of category tiles rendered on each row of the category grid
+pub static CATEGORY_COLUMNS: usize = 4;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, strum_macros::EnumIs, strum_macros::EnumIter, strum_macros::FromRepr, strum_macros::EnumCount)]
+pub enum BrowseTab {
+    #[default]
+    Categories,
+    Featured,
+    NewReleases,
+}
+
+impl Add<usize> for BrowseTab {
+    type Output = Self;
+
+    fn add(self, rhs: usize) -> Self::Output {
+        let index = (self as usize + rhs) % Self::COUNT;
+        Self::from_repr(index).unwrap()
+    }
+}
+
+impl AddAssign<usize> for BrowseTab {
+    fn add_assign(&mut self, rhs: usize) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub<usize> for BrowseTab {
+    type Output = Self;
+
+    fn sub(self, rhs: usize) -> Self::Output {
+        let index = (self as isize - rhs as isize) % Self::COUNT as isize;
+        if index < 0 {
+            Self::from_repr((Self::COUNT as isize + index) as usize).unwrap()
+        } else {
+            Self::from_repr(index as usize).unwrap()
+        }
+    }
+}
+
+impl SubAssign<usize> for BrowseTab {
+    fn sub_assign(&mut self, rhs: usize) {
+        *self = *self - rhs;
+    }
+}
+
+impl BrowseTab {
+    #[inline]
+    pub const fn title(&self) -> &'static str {
+        match self {
+            Self::Categories => "Categories",
+            Self::Featured => "Featured",
+            Self::NewReleases => "New Releases",
+        }
+    }
+}
+
+/// Playlists for a category that was drilled into from the category grid
+#[derive(Debug, Clone)]
+pub struct CategoryPlaylists {
+    pub category: Category,
+    pub state: TableState,
+    pub pages: Pages<PagedPlaylists, FeaturedPlaylists>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BrowseState {
+    pub selected_tab: BrowseTab,
+    pub state: TableState,
+
+    pub categories: Pages<Categories, HashMap<String, Categories>>,
+    pub featured: Pages<PagedPlaylists, FeaturedPlaylists>,
+    pub new_releases: Pages<NewReleases, HashMap<String, NewReleases>>,
+
+    pub category: Option<CategoryPlaylists>,
+}
+
+fn len<R, P>(pages: &Pages<R, P>) -> usize
+where
+    R: Clone + Debug + Send + Paged,
+    P: Clone + Debug + Send,
+{
+    match pages.items.lock().unwrap().as_ref() {
+        Some(Loading::Some(items)) => items.items().len(),
+        _ => 0,
+    }
+}
+
+fn playlist_actions(playlist: &SimplifiedPlaylist) -> Vec<(KeyEvent, Action, &'static str)> {
+    vec![
+        (key!(Enter), Action::PlayContext(Play::playlist(playlist.uri.clone(), None, 0)), action_label::PLAY),
+        (key!('C' + SHIFT), Action::GoTo(GoTo::Playlist(playlist.uri.clone())), action_label::GO_TO_PLAYLIST),
+    ]
+}
+
+fn album_actions(album: &Album) -> Vec<(KeyEvent, Action, &'static str)> {
+    let mut actions = vec![
+        (key!(Enter), Action::PlayContext(Play::album(album.uri.clone(), None, 0)), action_label::PLAY),
+        (key!('C' + SHIFT), Action::GoTo(GoTo::Album(album.uri.clone())), action_label::GO_TO_ALBUM),
+    ];
+    if album.artists.len() > 1 {
+        actions.push((key!('A' + SHIFT), Action::GoTo(GoTo::Artists(album.artists.iter().map(|a| (a.uri.clone(), a.name.clone())).collect::<Vec<_>>())), action_label::SELECT_ARTIST));
+    } else if let Some(artist) = album.artists.first() {
+        actions.push((key!('A' + SHIFT), Action::GoTo(GoTo::Artist(artist.uri.clone())), action_label::GO_TO_ARTIST));
+    }
+    actions
+}
+
+impl BrowseState {
+    pub fn new(api: &Pkce) -> Result<Self> {
+        Ok(Self {
+            selected_tab: BrowseTab::default(),
+            state: TableState::default(),
+
+            categories: Pages::new(api.browse_categories::<PAGE_SIZE, _>(None::<()>)?),
+            featured: Pages::new(api.featured_playlists::<PAGE_SIZE, _>(None::<()>)?),
+            new_releases: Pages::new(api.new_releases::<PAGE_SIZE>()?),
+
+            category: None,
+        })
+    }
+
+    /// Fetch the first page of the selected tab if it hasn't been fetched yet
+    pub fn load(&self) {
+        match self.selected_tab {
+            BrowseTab::Categories => self.categories.load(),
+            BrowseTab::Featured => self.featured.load(),
+            BrowseTab::NewReleases => self.new_releases.load(),
+        }
+    }
+
+    /// Close the category that is currently open. Returns false if no category was open.
+    pub fn back(&mut self) -> bool {
+        if self.selected_tab.is_categories() && self.category.is_some() {
+            self.category = None;
+            return true;
+        }
+        false
+    }
+
+    pub fn tab(&mut self) {
+        self.selected_tab += 1;
+        self.state.select(None);
+        self.load();
+    }
+
+    pub fn backtab(&mut self) {
+        self.selected_tab -= 1;
+        self.state.select(None);
+        self.load();
+    }
+
+    pub fn down(&mut self) {
+        match self.selected_tab {
+            BrowseTab::Categories => match self.category.as_mut() {
+                Some(category) => {
+                    let len = len(&category.pages);
+                    if len > 0 {
+                        category.state.next_in_list(len);
+                    }
+                }
+                None => {
+                    let len = len(&self.categories);
+                    match self.state.selected() {
+                        Some(selected) if selected + CATEGORY_COLUMNS < len => self.state.select(Some(selected + CATEGORY_COLUMNS)),
+                        None if len > 0 => self.state.select(Some(0)),
+                        _ => {}
+                    }
+                }
+            },
+            BrowseTab::Featured => {
+                let len = len(&self.featured);
+                if len > 0 {
+                    self.state.next_in_list(len);
+                }
+            }
+            BrowseTab::NewReleases => {
+                let len = len(&self.new_releases);
+                if len > 0 {
+                    self.state.next_in_list(len);
+                }
+            }
+        }
+    }
+
+    pub fn up(&mut self) {
+        match self.selected_tab {
+            BrowseTab::Categories => match self.category.as_mut() {
+                Some(category) => {
+                    let len = len(&category.pages);
+                    if len > 0 {
+                        category.state.prev_in_list(len);
+                    }
+                }
+                None => {
+                    let len = len(&self.categories);
+                    match self.state.selected() {
+                        Some(selected) if selected >= CATEGORY_COLUMNS => self.state.select(Some(selected - CATEGORY_COLUMNS)),
+                        None if len > 0 => self.state.select(Some(0)),
+                        _ => {}
+                    }
+                }
+            },
+            BrowseTab::Featured => {
+                let len = len(&self.featured);
+                if len > 0 {
+                    self.state.prev_in_list(len);
+                }
+            }
+            BrowseTab::NewReleases => {
+                let len = len(&self.new_releases);
+                if len > 0 {
+                    self.state.prev_in_list(len);
+                }
+            }
+        }
+    }
+
+    pub fn jump(&mut self, motion: Motion) {
+        let (state, len) = match self.selected_tab {
+            BrowseTab::Categories => match self.category.as_mut() {
+                Some(category) => (&mut category.state, len(&category.pages)),
+                None => (&mut self.state, len(&self.categories)),
+            },
+            BrowseTab::Featured => (&mut self.state, len(&self.featured)),
+            BrowseTab::NewReleases => (&mut self.state, len(&self.new_releases)),
+        };
+        if len > 0 {
+            state.jump_in_list(motion, len, PAGE_SIZE);
+        }
+    }
+
+    pub fn right(&mut self) {
+        match self.selected_tab {
+            BrowseTab::Categories => match self.category.as_mut() {
+                Some(category) => {
+                    category.pages.next_page();
+                    category.state.select(Some(0));
+                }
+                None => {
+                    // Move across the grid and only page once the last tile is reached
+                    let len = len(&self.categories);
+                    match self.state.selected() {
+                        Some(selected) if selected + 1 < len => self.state.select(Some(selected + 1)),
+                        None if len > 0 => self.state.select(Some(0)),
+                        _ => {
+                            self.categories.next_page();
+                            self.state.select(Some(0));
+                        }
+                    }
+                }
+            },
+            BrowseTab::Featured => {
+                self.featured.next_page();
+                self.state.select(Some(0));
+            }
+            BrowseTab::NewReleases => {
+                self.new_releases.next_page();
+                self.state.select(Some(0));
+            }
+        }
+    }
+
+    pub fn left(&mut self) {
+        match self.selected_tab {
+            BrowseTab::Categories => match self.category.as_mut() {
+                Some(category) => {
+                    category.pages.prev_page();
+                    category.state.select(Some(0));
+                }
+                None => match self.state.selected() {
+                    Some(selected) if selected > 0 => self.state.select(Some(selected - 1)),
+                    _ => {
+                        self.categories.prev_page();
+                        self.state.select(Some(0));
+                    }
+                },
+            },
+            BrowseTab::Featured => {
+                self.featured.prev_page();
+                self.state.select(Some(0));
+            }
+            BrowseTab::NewReleases => {
+                self.new_releases.prev_page();
+                self.state.select(Some(0));
+            }
+        }
+    }
+
+    pub fn refresh(&mut self) {
+        match self.selected_tab {
+            BrowseTab::Categories => match self.category.as_mut() {
+                Some(category) => {
+                    category.pages.refresh_page();
+                    category.state.select(None);
+                }
+                None => {
+                    self.categories.refresh_page();
+                    self.state.select(None);
+                }
+            },
+            BrowseTab::Featured => {
+                self.featured.refresh_page();
+                self.state.select(None);
+            }
+            BrowseTab::NewReleases => {
+                self.new_releases.refresh_page();
+                self.state.select(None);
+            }
+        }
+    }
+
+    /// Open the playlists for the selected category. Returns false if the category grid isn't
+    /// the current view.
+    pub fn open_category(&mut self, api: &Pkce) -> Result<bool> {
+        if !self.selected_tab.is_categories() || self.category.is_some() {
+            return Ok(false);
+        }
+
+        let selected = match self.categories.items.lock().unwrap().as_ref() {
+            Some(Loading::Some(items)) => items.items.get(self.state.selected().unwrap_or(0)).cloned(),
+            _ => None,
+        };
+
+        if let Some(category) = selected {
+            let pages = Pages::new(api.category_playlists::<PAGE_SIZE, _>(category.id.clone())?);
+            pages.load();
+            self.category = Some(CategoryPlaylists {
+                category,
+                state: TableState::default(),
+                pages,
+            });
+        }
+        Ok(true)
+    }
+
+    pub fn select(&self) -> Option<Vec<(KeyEvent, Action, &'static str)>> {
+        match self.selected_tab {
+            BrowseTab::Categories => if let Some(category) = self.category.as_ref() {
+                if let Some(Loading::Some(items)) = category.pages.items.lock().unwrap().as_ref() {
+                    let index = category.state.selected().unwrap_or(0);
+                    return items.items.get(index).map(playlist_actions);
+                }
+            },
+            BrowseTab::Featured => {
+                if let Some(Loading::Some(items)) = self.featured.items.lock().unwrap().as_ref() {
+                    let index = self.state.selected().unwrap_or(0);
+                    return items.items.get(index).map(playlist_actions);
+                }
+            }
+            BrowseTab::NewReleases => {
+                if let Some(Loading::Some(items)) = self.new_releases.items.lock().unwrap().as_ref() {
+                    let index = self.state.selected().unwrap_or(0);
+                    return items.items.get(index).map(album_actions);
+                }
+            }
+        }
+        None
+    }
+}
+
+impl Component for BrowseState {
+    fn event(&mut self, event: &Event, ctx: &Context) -> Result<Handled> {
+        Ok(match event {
+            Event::Down => { self.down(); Handled::Consumed },
+            Event::Up => { self.up(); Handled::Consumed },
+            Event::Motion(motion) => { self.jump(*motion); Handled::Consumed },
+            Event::Right => { self.right(); Handled::Consumed },
+            Event::Left => { self.left(); Handled::Consumed },
+            Event::Tab => { self.tab(); Handled::Consumed },
+            Event::Backtab => { self.backtab(); Handled::Consumed },
+            // Selecting a category drills into its playlists instead of opening actions
+            Event::Select => if self.open_category(ctx.api)? {
+                Handled::Consumed
+            } else {
+                open_actions(self.select(), ctx)
+            },
+            // Closing an open category goes back to the category grid
+            Event::Close if self.back() => Handled::Consumed,
+            _ => Handled::Ignored,
+        })
+    }
+
+    fn focus(&mut self) {
+        self.load();
+    }
+
+    fn refresh(&mut self, _ctx: &Context) -> bool {
+        self.refresh();
+        true
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Widget::render(&*self, area, buf);
+    }
+}
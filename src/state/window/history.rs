@@ -0,0 +1,141 @@
+use color_eyre::Result;
+use crossterm::event::KeyEvent;
+use ratatui::{buffer::Buffer, layout::Rect, widgets::{TableState, Widget}};
+use tupy::api::{flow::Pkce, request::Timestamp, response::{PlayHistory, RecentlyPlayed}, UserApi};
+
+use super::MappedPages;
+use crate::{app::Event, errors::LogError, key, tasks::spawn_window, state::{library_index, component::{open_actions, Component, Context, Handled}, actions::{action_label, Action}, wrappers::Saved, IterCollection, Loading, Motion}, PAGE_SIZE};
+
+pub type History = MappedPages<Vec<Saved<PlayHistory>>, RecentlyPlayed, RecentlyPlayed>;
+
+#[derive(Clone)]
+pub struct HistoryState {
+    pub state: TableState,
+    pub pages: History,
+}
+
+impl std::fmt::Debug for HistoryState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HistoryState")
+            .field("state", &self.state)
+            .finish()
+    }
+}
+
+impl HistoryState {
+    pub fn new(api: &Pkce) -> Result<Self> {
+        Ok(Self {
+            state: TableState::default(),
+            pages: MappedPages::new(
+                api.recently_played::<PAGE_SIZE>(Timestamp::before_now())?,
+                |data, api| Box::pin(async move {
+                    Ok(match data {
+                        Some(data) => {
+                            library_index::check(&api, data.items.iter().map(|h| h.track.uri.clone())).await.log_error();
+                            Some(data.items.into_iter().map(Saved::new).collect())
+                        },
+                        None => None
+                    })
+                })
+            ),
+        })
+    }
+
+    fn len(&self) -> usize {
+        match self.pages.items.lock().unwrap().as_ref() {
+            Some(Loading::Some(items)) => items.len(),
+            _ => 0,
+        }
+    }
+
+    /// Fetch the most recently played tracks if they haven't been fetched yet
+    pub fn load(&self) {
+        if self.pages.items.lock().unwrap().is_none() {
+            let p = self.pages.clone();
+            spawn_window(async move {
+                p.next().await.log_error();
+            });
+        }
+    }
+
+    pub fn down(&mut self) {
+        let len = self.len();
+        if len > 0 {
+            self.state.next_in_list(len);
+        }
+    }
+
+    pub fn up(&mut self) {
+        let len = self.len();
+        if len > 0 {
+            self.state.prev_in_list(len);
+        }
+    }
+
+    /// Rows aren't tracked for mouse input here, so a page is taken to be a full page of items
+    pub fn jump(&mut self, motion: Motion) {
+        let len = self.len();
+        if len > 0 {
+            self.state.jump_in_list(motion, len, PAGE_SIZE);
+        }
+    }
+
+    /// Recently played is cursor based so only older items can be requested
+    pub fn right(&mut self) {
+        let p = self.pages.clone();
+        spawn_window(async move {
+            if p.has_next().await {
+                p.next().await.log_error();
+            }
+        });
+        self.state.select(Some(0));
+    }
+
+    pub fn refresh(&mut self) {
+        let p = self.pages.clone();
+        spawn_window(async move {
+            p.refresh().await.log_error();
+        });
+        self.state.select(None);
+    }
+
+    pub fn select(&self) -> Option<Vec<(KeyEvent, Action, &'static str)>> {
+        let index = self.state.selected().unwrap_or(0);
+        if let Some(Loading::Some(items)) = self.pages.items.lock().unwrap().as_ref() {
+            return items.get(index).map(|h| {
+                let mut actions = vec![
+                    (key!(Enter), Action::Play(h.as_ref().track.uri.clone()), action_label::PLAY)
+                ];
+                actions.extend(h.into_actions(true));
+                actions
+            });
+        }
+        None
+    }
+}
+
+impl Component for HistoryState {
+    fn event(&mut self, event: &Event, ctx: &Context) -> Result<Handled> {
+        Ok(match event {
+            Event::Down => { self.down(); Handled::Consumed },
+            Event::Up => { self.up(); Handled::Consumed },
+            Event::Motion(motion) => { self.jump(*motion); Handled::Consumed },
+            Event::Right => { self.right(); Handled::Consumed },
+            Event::Select => open_actions(self.select(), ctx),
+            _ => Handled::Ignored,
+        })
+    }
+
+    fn focus(&mut self) {
+        self.load();
+    }
+
+    fn refresh(&mut self, _ctx: &Context) -> bool {
+        self.refresh();
+        true
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Widget::render(&*self, area, buf);
+    }
+}
@@ -1,15 +1,17 @@
 use color_eyre::Result;
 
 use crossterm::event::KeyEvent;
-use ratatui::{layout::Rect, widgets::TableState};
+use ratatui::{buffer::Buffer, layout::Rect, widgets::{TableState, Widget}};
 use ratatui_image::{picker::Picker, protocol::Protocol, Resize};
 use strum::EnumCount;
-use tupy::api::{flow::Pkce, request::{IncludeGroup, Play}, response::{Album, AlbumTracks, Artist, ArtistAlbums, Audiobook, Chapters, Item, Playlist, PlaylistItemInfo, PlaylistItems, Show, ShowEpisodes, SimplifiedAlbum, SimplifiedEpisode, SimplifiedTrack, Track}, PublicApi, Uri, UserApi};
+use tokio::sync::Mutex;
+use tupy::api::{flow::{AuthFlow, Pkce}, request::{IncludeGroup, Play, PlaylistAction}, response::{Album, AlbumTracks, Artist, ArtistAlbums, Audiobook, Chapters, Playlist, PlaylistItemInfo, PlaylistItems, Show, ShowEpisodes, SimplifiedAlbum, SimplifiedEpisode, SimplifiedTrack, Track}, PublicApi, Uri, UserApi};
+use tupy::Pagination;
 
 use super::{MappedPages, Pages};
-use crate::{errors::LogError, key, state::{actions::{action_label, Action, IntoActions}, wrappers::{GetUri, Saved}, IterCollection, Loading}, Locked, Shared};
+use crate::{app::Event, errors::LogError, key, tasks::spawn, state::{library_index, mutation, undo::{self, Inverse}, view::{mark_key, take_marked, view_key, Filterable}, component::{open_actions, Component, Context, Handled}, actions::{action_label, batch_actions, queue_actions, Action, IntoActions, PlaylistForm}, wrappers::{GetUri, Saved}, Click, IterCollection, Loading, Motion, TableArea}, Locked, Shared};
 
-#[derive(Default, Debug, Clone, Copy, strum_macros::EnumIs)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, strum_macros::EnumIs)]
 pub enum ArtistLanding {
     #[default]
     Tracks,
@@ -41,13 +43,22 @@ pub enum Landing {
         section: ArtistLanding,
         state: TableState,
         landing_section: LandingSection,
+        tracks_area: TableArea,
+        albums_area: TableArea,
     },
     Playlist {
         cover: Shared<Locked<Loading<Cover>>>,
         playlist: Playlist,
-        pages: MappedPages<Vec<Saved<PlaylistItemInfo>>, PlaylistItems, PlaylistItems>,
+        /// The playlist belongs to the user, so it can be edited and deleted
+        owned: bool,
+        /// Version of the playlist the items shown were fetched from, which edits are made against
+        snapshot: Shared<Mutex<String>>,
+        /// Positions across every page of the items marked to be moved
+        cut: Option<(usize, usize)>,
+        pages: PlaylistPages,
         state: TableState,
         section: LandingSection,
+        area: TableArea,
     },
     Album {
         cover: Shared<Locked<Loading<Cover>>>,
@@ -55,6 +66,7 @@ pub enum Landing {
         pages: MappedPages<Vec<Saved<SimplifiedTrack>>, AlbumTracks, AlbumTracks>,
         state: TableState,
         section: LandingSection,
+        area: TableArea,
     },
     Show {
         cover: Shared<Locked<Loading<Cover>>>,
@@ -62,6 +74,7 @@ pub enum Landing {
         pages: MappedPages<Vec<Saved<SimplifiedEpisode>>, ShowEpisodes, ShowEpisodes>,
         state: TableState,
         section: LandingSection,
+        area: TableArea,
     },
     Audiobook{
         cover: Shared<Locked<Loading<Cover>>>,
@@ -69,6 +82,7 @@ pub enum Landing {
         pages: Pages<Chapters, Chapters>,
         state: TableState,
         section: LandingSection,
+        area: TableArea,
     },
 }
 
@@ -85,6 +99,114 @@ impl std::fmt::Debug for Landing {
     }
 }
 
+/// Select the row under the position, moving focus to the content section
+fn click_row(area: &TableArea, state: &mut TableState, section: &mut LandingSection, column: u16, row: u16, len: usize) -> Click {
+    match area.row(column, row, len) {
+        Some(index) if section.is_content() && state.selected() == Some(index) => Click::Activated,
+        Some(index) => {
+            *section = LandingSection::Content;
+            state.select(Some(index));
+            Click::Selected
+        }
+        None => Click::Miss,
+    }
+}
+
+type PlaylistPages = MappedPages<Vec<Saved<PlaylistItemInfo>>, PlaylistItems, PlaylistItems>;
+
+/// Change to the items of a playlist, with positions across every page
+#[derive(Debug, Clone, PartialEq)]
+enum ItemEdit {
+    Move { start: usize, length: usize, insert: usize },
+    /// Remove the items starting at `start`. Spotify removes every occurrence of them, so items
+    /// that are also elsewhere in the playlist aren't removed.
+    Remove { start: usize, uris: Vec<Uri> },
+}
+
+/// Send an edit that was already made to the page shown
+///
+/// The edit is only sent if the playlist is still at the snapshot the page was fetched from.
+/// If it was changed somewhere else the edit is dropped and the page is fetched again, so the
+/// other change isn't overwritten by positions that no longer line up. `refresh` fetches the page
+/// again once the edit is made, for edits that couldn't be shown on the page.
+fn edit_items(api: &Pkce, id: String, snapshot: Shared<Mutex<String>>, pages: PlaylistPages, edit: ItemEdit, refresh: bool) {
+    let api = api.clone();
+    spawn(async move {
+        if api.token().is_expired() {
+            api.refresh().await.log_error();
+        }
+
+        // Edits wait on each other so each is made against the snapshot the last one left
+        let mut snapshot = snapshot.lock().await;
+        // Every position the removed items were at, for undo
+        let mut removed = Vec::new();
+        let result = match api.playlist_snapshot(&id).await {
+            Ok(latest) if latest != *snapshot => {
+                mutation::notify("The playlist was changed somewhere else, showing the latest version".into());
+                *snapshot = latest;
+                pages.refresh().await.log_error();
+                return;
+            }
+            Ok(_) => match &edit {
+                ItemEdit::Move { start, length, insert } => {
+                    let action = PlaylistAction::Reorder { start: *start, length: *length, insert: *insert };
+                    api.update_playlist_items(&id, action, Some(snapshot.clone())).await
+                }
+                ItemEdit::Remove { start, uris } => match positions(&api, &id, uris).await {
+                    Ok(positions) if positions.iter().any(|(position, _)| !(*start..start + uris.len()).contains(position)) => {
+                        mutation::notify("Spotify would remove every copy of an item that is in the playlist more than once".into());
+                        pages.refresh().await.log_error();
+                        return;
+                    }
+                    Ok(positions) => {
+                        removed = positions;
+                        api.remove_items(&id, uris.clone(), Some(snapshot.clone())).await
+                    }
+                    Err(error) => Err(error),
+                },
+            },
+            Err(error) => Err(error),
+        };
+
+        match result {
+            Ok(latest) => {
+                *snapshot = latest;
+                if let ItemEdit::Remove { uris, .. } = edit {
+                    let what = match uris.len() {
+                        1 => "Removed from the playlist".to_string(),
+                        n => format!("Removed {n} items from the playlist"),
+                    };
+                    undo::push(what, Inverse::AddItems { playlist: Uri::playlist(id.clone()), items: removed });
+                }
+                if refresh {
+                    pages.refresh().await.log_error();
+                }
+            }
+            Err(error) => {
+                mutation::notify(format!("Couldn't edit the playlist: {error}"));
+                pages.refresh().await.log_error();
+            }
+        }
+    });
+}
+
+/// Every position of the items in the playlist, in order
+async fn positions(api: &Pkce, id: &str, uris: &[Uri]) -> Result<Vec<(usize, Uri)>, tupy::Error> {
+    let mut pager = api.playlist_items::<100, _, _>(id, None)?;
+    let mut positions = Vec::new();
+    let mut position = 0;
+    while let Some(page) = pager.next().await? {
+        for item in page.items {
+            let uri = item.get_uri();
+            if uris.contains(&uri) {
+                positions.push((position, uri));
+            }
+            position += 1;
+        }
+    }
+    Ok(positions)
+}
+
 async fn get_cover(image: String) -> Option<Cover> {
     image::load_from_memory_with_format(
         reqwest::Client::new()
@@ -104,42 +226,20 @@ async fn get_cover(image: String) -> Option<Cover> {
 }
 
 impl Landing {
-    pub async fn playlist(api: &Pkce, playlist: Uri) -> Result<Self> {
+    pub async fn playlist(api: &Pkce, playlist: Uri, user_id: &str) -> Result<Self> {
         let pages = MappedPages::new(
             api.playlist_items(playlist.id(), None)?,
             |data, api| Box::pin(async move {
                 Ok(match data {
                     Some(data) => {
-                        // Saved tracks
-                        let mut tracks = api.check_saved_tracks(data.items.iter().filter_map(|v| match &v.item {
-                            Item::Track(t) => Some(t.id.clone()),
-                            _ => None
-                        })).await.log_error_or(vec![]).into_iter();
-
-                        // Saved episodes
-                        let mut episodes = api.check_saved_episodes(data.items.iter().filter_map(|v| match &v.item {
-                            Item::Episode(e) => Some(e.id.clone()),
-                            _ => None
-                        })).await.log_error_or(vec![]).into_iter();
-
-                        // Map to saved items popping from appropriate list
-                        let items = data.items.into_iter().map(|i| Saved::new(match i.item {
-                            Item::Track(_) => tracks.next().unwrap_or_default(),
-                            Item::Episode(_) => episodes.next().unwrap_or_default(),
-                        }, i)).collect();
-
-                        Some(items)
+                        library_index::check(&api, data.items.iter().map(|i| i.get_uri())).await.log_error();
+                        Some(data.items.into_iter().map(Saved::new).collect())
                     },
                     None => None
                 })
             })
         );
 
-        let p = pages.clone();
-        tokio::spawn(async move {
-            p.next().await.log_error();
-        });
-
         let playlist = api.playlist(playlist.id(), None).await?;
         let cover = match playlist.images.as_ref() {
             None => Shared::new(Locked::new(Loading::None)),
@@ -150,7 +250,9 @@ impl Landing {
 
                     let c = cover.clone();
                     let url = i.url.clone();
-                    tokio::spawn(async move {
+                    // Covers are kept with the landing in the navigation history, so let them
+                    // finish after navigating away
+                    spawn(async move {
                         *c.lock().unwrap() = get_cover(url).await.into();
                     });
 
@@ -161,10 +263,14 @@ impl Landing {
 
         Ok(Self::Playlist {
             cover,
+            owned: playlist.owner.id == user_id,
+            snapshot: Shared::new(Mutex::new(playlist.snapshot.clone())),
+            cut: None,
             playlist,
             pages,
             state: TableState::default(),
             section: LandingSection::default(),
+            area: TableArea::default(),
         })
     }
 
@@ -174,19 +280,14 @@ impl Landing {
             |data, api| Box::pin(async move {
                 Ok(match data {
                     Some(data) => {
-                        let tracks = api.check_saved_tracks(data.items.iter().map(|t| t.id.clone())).await.log_error_or(vec![false]);
-                        Some(data.items.into_iter().zip(tracks.into_iter()).map(|(i, s)| Saved::new(s, i)).collect())
+                        library_index::check(&api, data.items.iter().map(|t| t.uri.clone())).await.log_error();
+                        Some(data.items.into_iter().map(Saved::new).collect())
                     },
                     None => None
                 })
             })
         );
 
-        let p = pages.clone();
-        tokio::spawn(async move {
-            p.next().await.log_error();
-        });
-
         let album = api.album(album.id(), None).await?;
         let cover = match album.images.first().as_ref() {
             None => Shared::new(Locked::new(Loading::None)),
@@ -195,7 +296,9 @@ impl Landing {
 
                 let c = cover.clone();
                 let url = i.url.clone();
-                tokio::spawn(async move {
+                // Covers are kept with the landing in the navigation history, so let them finish
+                // after navigating away
+                spawn(async move {
                     *c.lock().unwrap() = get_cover(url).await.into();
                 });
 
@@ -208,7 +311,8 @@ impl Landing {
             album,
             pages,
             state: TableState::default(),
-            section: LandingSection::default()
+            section: LandingSection::default(),
+            area: TableArea::default(),
         })
     }
 
@@ -218,19 +322,14 @@ impl Landing {
             |data, api| Box::pin(async move {
                 Ok(match data {
                     Some(data) => {
-                        let checked = api.check_saved_episodes(data.items.iter().map(|v| v.id.clone())).await?;
-                        Some(data.items.into_iter().zip(checked.into_iter()).map(|(d, s)| Saved::new(s, d)).collect())
+                        library_index::check(&api, data.items.iter().map(|e| e.uri.clone())).await?;
+                        Some(data.items.into_iter().map(Saved::new).collect())
                     },
                     None => None
                 })
             })
         );
 
-        let p = pages.clone();
-        tokio::spawn(async move {
-            p.next().await.log_error();
-        });
-
         let show = api.show(show.id(), None).await?;
         let cover = match show.images.first().as_ref() {
             None => Shared::new(Locked::new(Loading::None)),
@@ -239,7 +338,9 @@ impl Landing {
 
                 let c = cover.clone();
                 let url = i.url.clone();
-                tokio::spawn(async move {
+                // Covers are kept with the landing in the navigation history, so let them finish
+                // after navigating away
+                spawn(async move {
                     *c.lock().unwrap() = get_cover(url).await.into();
                 });
 
@@ -252,18 +353,14 @@ impl Landing {
             show,
             pages,
             state: TableState::default(),
-            section: LandingSection::default()
+            section: LandingSection::default(),
+            area: TableArea::default(),
         })
     }
 
     pub async fn audiobook(api: &Pkce, audiobook: Uri) -> Result<Self> {
         let pages = Pages::new(api.audiobook_chapters(audiobook.id(), None)?);
 
-        let p = pages.clone();
-        tokio::spawn(async move {
-            p.next().await.log_error();
-        });
-
         let audiobook = api.audiobook(audiobook.id(), None).await?;
         let cover = match audiobook.images.first().as_ref() {
             None => Shared::new(Locked::new(Loading::None)),
@@ -272,7 +369,9 @@ impl Landing {
 
                 let c = cover.clone();
                 let url = i.url.clone();
-                tokio::spawn(async move {
+                // Covers are kept with the landing in the navigation history, so let them finish
+                // after navigating away
+                spawn(async move {
                     *c.lock().unwrap() = get_cover(url).await.into();
                 });
 
@@ -285,7 +384,8 @@ impl Landing {
             audiobook,
             pages,
             state: TableState::default(),
-            section: LandingSection::default()
+            section: LandingSection::default(),
+            area: TableArea::default(),
         })
     }
 
@@ -295,19 +395,14 @@ impl Landing {
             |data, api| Box::pin(async move {
                 Ok(match data {
                     Some(data) => {
-                        let checked = api.check_saved_albums(data.items.iter().map(|v| v.id.clone())).await?;
-                        Some(data.items.into_iter().zip(checked.into_iter()).map(|(d, s)| Saved::new(s, d)).collect())
+                        library_index::check(&api, data.items.iter().map(|a| a.uri.clone())).await?;
+                        Some(data.items.into_iter().map(Saved::new).collect())
                     },
                     None => None
                 })
             })
         );
 
-        let p = pages.clone();
-        tokio::spawn(async move {
-            p.next().await.log_error();
-        });
-
         let artist = api.artist(uri.id()).await?;
         let cover = match artist.images.first().as_ref() {
             None => Shared::new(Locked::new(Loading::None)),
@@ -316,7 +411,9 @@ impl Landing {
 
                 let c = cover.clone();
                 let url = i.url.clone();
-                tokio::spawn(async move {
+                // Covers are kept with the landing in the navigation history, so let them finish
+                // after navigating away
+                spawn(async move {
                     *c.lock().unwrap() = get_cover(url).await.into();
                 });
 
@@ -325,22 +422,151 @@ impl Landing {
         };
 
         let top_tracks = api.artist_top_tracks(uri.id(), None).await?;
-        let saved_top_tracks = api.check_saved_tracks(top_tracks.iter().map(|t| t.id.clone())).await?;
+        library_index::check(api, top_tracks.iter().map(|t| t.uri.clone()).chain([artist.uri.clone()])).await?;
 
         Ok(Self::Artist {
             cover,
-            artist: Shared::new(Locked::new(Saved::new(
-                api.check_follow_artists([artist.id.clone()]).await?[0],
-                artist
-            ))),
-            top_tracks: Shared::new(Locked::new(top_tracks.into_iter().zip(saved_top_tracks.into_iter()).map(|(t, s)| Saved::new(s, t)).collect())),
+            artist: Shared::new(Locked::new(Saved::new(artist))),
+            top_tracks: Shared::new(Locked::new(top_tracks.into_iter().map(Saved::new).collect())),
             albums: pages,
             section: ArtistLanding::default(),
             state: TableState::default(),
-            landing_section: LandingSection::default()
+            landing_section: LandingSection::default(),
+            tracks_area: TableArea::default(),
+            albums_area: TableArea::default(),
         })
     }
 
+    /// Table of the landing that can be filtered and sorted
+    pub fn table(&self) -> Option<&dyn Filterable> {
+        match self {
+            Landing::None => None,
+            Landing::Playlist{ pages, .. } => Some(pages),
+            Landing::Album{ pages, .. } => Some(pages),
+            Landing::Show{ pages, .. } => Some(pages),
+            Landing::Audiobook{ pages, .. } => Some(pages),
+            Landing::Artist{ albums, .. } => Some(albums),
+        }
+    }
+
+    /// Table that rows can be marked in with the selected row, while the selection is in the table
+    fn marked_table(&self) -> Option<(&dyn Filterable, Option<usize>)> {
+        match self {
+            Landing::Artist{ albums, state, section, landing_section, .. } => {
+                (section.is_albums() && landing_section.is_content()).then_some((albums as &dyn Filterable, state.selected()))
+            }
+            Landing::Playlist{ pages, state, section, .. } => section.is_content().then_some((pages as &dyn Filterable, state.selected())),
+            Landing::Album{ pages, state, section, .. } => section.is_content().then_some((pages as &dyn Filterable, state.selected())),
+            Landing::Show{ pages, state, section, .. } => section.is_content().then_some((pages as &dyn Filterable, state.selected())),
+            Landing::Audiobook{ pages, state, section, .. } => section.is_content().then_some((pages as &dyn Filterable, state.selected())),
+            Landing::None => None,
+        }
+    }
+
+    /// Name of the landing shown in its title and the navigation breadcrumb
+    pub fn title(&self) -> String {
+        match self {
+            Landing::None => String::new(),
+            Landing::Playlist{ playlist, .. } => format!("Playlist: {}", playlist.name),
+            Landing::Album{ album, .. } => format!("Album: {}", album.name),
+            Landing::Show{ show, .. } => format!("Show: {}", show.name),
+            Landing::Audiobook{ audiobook, .. } => format!("Audiobook: {}", audiobook.name),
+            Landing::Artist{ artist, .. } => format!("Artist: {}", artist.lock().unwrap().as_ref().name),
+        }
+    }
+
+    /// Offset of the first item on the page that is shown
+    pub fn offset(&self) -> usize {
+        match self {
+            Landing::None => 0,
+            Landing::Playlist{ pages, .. } => pages.page.lock().unwrap().offset,
+            Landing::Album{ pages, .. } => pages.page.lock().unwrap().offset,
+            Landing::Show{ pages, .. } => pages.page.lock().unwrap().offset,
+            Landing::Audiobook{ pages, .. } => pages.page.lock().unwrap().offset,
+            Landing::Artist{ albums, .. } => albums.page.lock().unwrap().offset,
+        }
+    }
+
+    /// Start from the page containing the item offset once the landing is shown
+    pub fn start_at(&self, offset: usize) {
+        match self {
+            Landing::None => {},
+            Landing::Playlist{ pages, .. } => pages.start_at(offset),
+            Landing::Album{ pages, .. } => pages.start_at(offset),
+            Landing::Show{ pages, .. } => pages.start_at(offset),
+            Landing::Audiobook{ pages, .. } => pages.start_at(offset),
+            Landing::Artist{ albums, .. } => albums.start_at(offset),
+        }
+    }
+
+    /// Fetch the page when the landing is shown, including pages that were cancelled while the
+    /// landing was in the navigation history
+    pub fn load(&self) {
+        match self {
+            Landing::None => {},
+            Landing::Playlist{ pages, .. } => pages.load(),
+            Landing::Album{ pages, .. } => pages.load(),
+            Landing::Show{ pages, .. } => pages.load(),
+            Landing::Audiobook{ pages, .. } => pages.load(),
+            Landing::Artist{ albums, .. } => albums.load(),
+        }
+    }
+
+    pub fn click(&mut self, column: u16, row: u16) -> Click {
+        match self {
+            Landing::Playlist{ pages, state, section, area, .. } => {
+                let len = match pages.items.lock().unwrap().as_ref() {
+                    Some(Loading::Some(items)) => items.len(),
+                    _ => 0,
+                };
+                click_row(area, state, section, column, row, len)
+            },
+            Landing::Album{ pages, state, section, area, .. } => {
+                let len = match pages.items.lock().unwrap().as_ref() {
+                    Some(Loading::Some(items)) => items.len(),
+                    _ => 0,
+                };
+                click_row(area, state, section, column, row, len)
+            },
+            Landing::Show{ pages, state, section, area, .. } => {
+                let len = match pages.items.lock().unwrap().as_ref() {
+                    Some(Loading::Some(items)) => items.len(),
+                    _ => 0,
+                };
+                click_row(area, state, section, column, row, len)
+            },
+            Landing::Audiobook{ pages, state, section, area, .. } => {
+                let len = match pages.items.lock().unwrap().as_ref() {
+                    Some(Loading::Some(items)) => items.items.len(),
+                    _ => 0,
+                };
+                click_row(area, state, section, column, row, len)
+            },
+            Landing::Artist{ state, section, landing_section, top_tracks, albums, tracks_area, albums_area, .. } => {
+                let albums_len = match albums.items.lock().unwrap().as_ref() {
+                    Some(Loading::Some(items)) => items.len(),
+                    _ => 0,
+                };
+
+                let (clicked, index) = match (tracks_area.row(column, row, top_tracks.lock().unwrap().len()), albums_area.row(column, row, albums_len)) {
+                    (Some(index), _) => (ArtistLanding::Tracks, index),
+                    (None, Some(index)) => (ArtistLanding::Albums, index),
+                    _ => return Click::Miss,
+                };
+
+                if landing_section.is_content() && *section == clicked && state.selected() == Some(index) {
+                    return Click::Activated;
+                }
+
+                *landing_section = LandingSection::Content;
+                *section = clicked;
+                state.select(Some(index));
+                Click::Selected
+            },
+            Landing::None => Click::Miss,
+        }
+    }
+
     pub fn down(&mut self) {
         match self {
             Landing::Playlist{pages, state, ..} => if let Some(Loading::Some(items)) = pages.items.lock().unwrap().as_ref() {
@@ -404,74 +630,83 @@ impl Landing {
         }
     }
 
-    pub async fn right(&mut self) -> Result<()> {
+    pub fn jump(&mut self, motion: Motion) {
         match self {
-            Landing::Playlist{ pages, state, section, .. } if section.is_content() => {
-                if pages.items.lock().unwrap().is_some() && pages.has_next().await {
-                    pages.next().await?;
-                    state.select(Some(0));
-                }
+            Landing::Playlist{ pages, state, area, .. } => if let Some(Loading::Some(items)) = pages.items.lock().unwrap().as_ref() {
+                state.jump_in_list(motion, items.len(), area.area.height as usize);
             },
-            Landing::Album{ pages, state, section, .. } if section.is_content() => {
-                if pages.items.lock().unwrap().is_some() && pages.has_next().await {
-                    pages.next().await?;
-                    state.select(Some(0));
-                }
+            Landing::Album{ pages, state, area, .. } => if let Some(Loading::Some(items)) = pages.items.lock().unwrap().as_ref() {
+                state.jump_in_list(motion, items.len(), area.area.height as usize);
             },
-            Landing::Show{ pages, state, section, .. } if section.is_content() => {
-                if pages.items.lock().unwrap().is_some() && pages.has_next().await {
-                    pages.next().await?;
-                    state.select(Some(0));
-                }
+            Landing::Show{ pages, state, area, .. } => if let Some(Loading::Some(items)) = pages.items.lock().unwrap().as_ref() {
+                state.jump_in_list(motion, items.len(), area.area.height as usize);
             },
-            Landing::Audiobook{ pages, state, section, .. } if section.is_content() => {
-                if pages.items.lock().unwrap().is_some() && pages.has_next().await {
-                    pages.next().await?;
-                    state.select(Some(0));
-                }
+            Landing::Audiobook{ pages, state, area, .. } => if let Some(Loading::Some(items)) = pages.items.lock().unwrap().as_ref() {
+                state.jump_in_list(motion, items.items.len(), area.area.height as usize);
+            },
+            // Jumps stay in the section that is selected
+            Landing::Artist{ state, section, top_tracks, albums, tracks_area, albums_area, .. } => match section {
+                ArtistLanding::Tracks => {
+                    state.jump_in_list(motion, top_tracks.lock().unwrap().len(), tracks_area.area.height as usize)
+                },
+                ArtistLanding::Albums => if let Some(Loading::Some(items)) = albums.items.lock().unwrap().as_ref() {
+                    state.jump_in_list(motion, items.len(), albums_area.area.height as usize);
+                },
             },
-            Landing::Artist{ state, section: ArtistLanding::Albums, albums, landing_section, .. } if landing_section.is_content() => if albums.items.lock().unwrap().is_some() && albums.has_next().await {
-                albums.next().await?;
+            _ => {},
+        }
+    }
+
+    pub fn right(&mut self) {
+        match self {
+            Landing::Playlist{ pages, state, section, .. } if section.is_content() && pages.items.lock().unwrap().is_some() => {
+                pages.next_page();
                 state.select(Some(0));
-            }
+            },
+            Landing::Album{ pages, state, section, .. } if section.is_content() && pages.items.lock().unwrap().is_some() => {
+                pages.next_page();
+                state.select(Some(0));
+            },
+            Landing::Show{ pages, state, section, .. } if section.is_content() && pages.items.lock().unwrap().is_some() => {
+                pages.next_page();
+                state.select(Some(0));
+            },
+            Landing::Audiobook{ pages, state, section, .. } if section.is_content() && pages.items.lock().unwrap().is_some() => {
+                pages.next_page();
+                state.select(Some(0));
+            },
+            Landing::Artist{ state, section: ArtistLanding::Albums, albums, landing_section, .. } if landing_section.is_content() && albums.items.lock().unwrap().is_some() => {
+                albums.next_page();
+                state.select(Some(0));
+            },
             _ => {},
         }
-        Ok(())
     }
 
-    pub async fn left(&mut self) -> Result<()> {
+    pub fn left(&mut self) {
         match self {
-            Landing::Playlist{ pages, state, section, .. } if section.is_content() => {
-                if pages.items.lock().unwrap().is_some() && pages.has_prev().await {
-                    pages.prev().await?;
-                    state.select(Some(0));
-                }
+            Landing::Playlist{ pages, state, section, .. } if section.is_content() && pages.items.lock().unwrap().is_some() => {
+                pages.prev_page();
+                state.select(Some(0));
             },
-            Landing::Album{ pages, state, section, .. } if section.is_content() => {
-                if pages.items.lock().unwrap().is_some() && pages.has_prev().await {
-                    pages.prev().await?;
-                    state.select(Some(0));
-                }
+            Landing::Album{ pages, state, section, .. } if section.is_content() && pages.items.lock().unwrap().is_some() => {
+                pages.prev_page();
+                state.select(Some(0));
             },
-            Landing::Show{ pages, state, section, .. } if section.is_content() => {
-                if pages.items.lock().unwrap().is_some() && pages.has_prev().await {
-                    pages.prev().await?;
-                    state.select(Some(0));
-                }
+            Landing::Show{ pages, state, section, .. } if section.is_content() && pages.items.lock().unwrap().is_some() => {
+                pages.prev_page();
+                state.select(Some(0));
             },
-            Landing::Audiobook{ pages, state, section, .. } if section.is_content() => {
-                if pages.items.lock().unwrap().is_some() && pages.has_prev().await {
-                    pages.prev().await?;
-                    state.select(Some(0));
-                }
+            Landing::Audiobook{ pages, state, section, .. } if section.is_content() && pages.items.lock().unwrap().is_some() => {
+                pages.prev_page();
+                state.select(Some(0));
             },
-            Landing::Artist{state, section: ArtistLanding::Albums, albums, landing_section, ..} if landing_section.is_content() => if albums.items.lock().unwrap().is_some() && albums.has_prev().await {
-                albums.prev().await?;
+            Landing::Artist{state, section: ArtistLanding::Albums, albums, landing_section, ..} if landing_section.is_content() && albums.items.lock().unwrap().is_some() => {
+                albums.prev_page();
                 state.select(Some(0));
-            }
+            },
             _ => {},
         }
-        Ok(())
     }
 
     pub fn tab(&mut self) -> Result<()> {
@@ -523,86 +758,184 @@ impl Landing {
         Ok(())
     }
 
-    pub async fn refresh(&mut self) -> Result<()> {
+    pub fn refresh(&mut self) {
         match self {
-            Landing::Playlist{ pages, state, .. } => {
-                if pages.items.lock().unwrap().is_some() {
-                    pages.refresh().await?;
-                    state.select(None);
-                }
+            Landing::Playlist{ pages, state, .. } if pages.items.lock().unwrap().is_some() => {
+                pages.refresh_page();
+                state.select(None);
             },
-            Landing::Album{ pages, state, .. } => {
-                if pages.items.lock().unwrap().is_some() && pages.has_prev().await {
-                    pages.refresh().await?;
-                    state.select(None);
-                }
+            Landing::Album{ pages, state, .. } if pages.items.lock().unwrap().is_some() => {
+                pages.refresh_page();
+                state.select(None);
             },
-            Landing::Show{ pages, state, .. } => {
-                if pages.items.lock().unwrap().is_some() && pages.has_prev().await {
-                    pages.refresh().await?;
-                    state.select(None);
-                }
+            Landing::Show{ pages, state, .. } if pages.items.lock().unwrap().is_some() => {
+                pages.refresh_page();
+                state.select(None);
             },
-            Landing::Audiobook{ pages, state, .. } => {
-                if pages.items.lock().unwrap().is_some() && pages.has_prev().await {
-                    pages.refresh().await?;
-                    state.select(None);
-                }
+            Landing::Audiobook{ pages, state, .. } if pages.items.lock().unwrap().is_some() => {
+                pages.refresh_page();
+                state.select(None);
             },
-            Landing::Artist{state, section: ArtistLanding::Albums, albums, ..} => if albums.items.lock().unwrap().is_some() && albums.has_prev().await {
-                albums.refresh().await?;
+            Landing::Artist{state, section: ArtistLanding::Albums, albums, ..} if albums.items.lock().unwrap().is_some() => {
+                albums.refresh_page();
                 state.select(None);
-            }
+            },
             _ => {},
         }
-        Ok(())
+    }
+
+    /// Handle the keys that edit the items of a playlist the user can change
+    fn edit_key(&mut self, key: KeyEvent, api: &Pkce) -> Handled {
+        let Landing::Playlist { playlist, owned, snapshot, cut, pages, state, section, .. } = self else {
+            return Handled::Ignored;
+        };
+        if !(*owned || playlist.collaborative) || !section.is_content() {
+            return Handled::Ignored;
+        }
+        let edit = key == key!('K' + SHIFT)
+            || key == key!('J' + SHIFT)
+            || key == key!('x')
+            || key == key!('p')
+            || key == key!('P' + SHIFT)
+            || key == key!('D' + SHIFT);
+        if !edit {
+            return Handled::Ignored;
+        }
+        // Positions only line up with the playlist while it is shown in its own order
+        if pages.view.lock().unwrap().is_shown() {
+            mutation::notify("Clear the filter and sort to edit the playlist".into());
+            return Handled::Consumed;
+        }
+
+        let offset = pages.page.lock().unwrap().offset;
+        let (len, selected) = match pages.items.lock().unwrap().as_ref() {
+            Some(Loading::Some(items)) if !items.is_empty() => {
+                (items.len(), state.selected().unwrap_or(0).min(items.len() - 1))
+            }
+            _ => return Handled::Consumed,
+        };
+        let position = offset + selected;
+
+        let edit = if key == key!('x') {
+            *cut = match *cut {
+                Some((start, end)) if start == end && start == position => None,
+                Some((start, end)) => Some((start.min(position), end.max(position))),
+                None => Some((position, position)),
+            };
+            return Handled::Consumed;
+        } else if key == key!('K' + SHIFT) {
+            if position == 0 {
+                return Handled::Consumed;
+            }
+            ItemEdit::Move { start: position, length: 1, insert: position - 1 }
+        } else if key == key!('J' + SHIFT) {
+            if position + 1 >= playlist.total_items {
+                return Handled::Consumed;
+            }
+            ItemEdit::Move { start: position, length: 1, insert: position + 2 }
+        } else if key == key!('D' + SHIFT) {
+            let (start, end) = cut.take().unwrap_or((position, position));
+            if start < offset || end >= offset + len {
+                mutation::notify("Only items on the page shown can be removed".into());
+                return Handled::Consumed;
+            }
+            let uris = match pages.items.lock().unwrap().as_ref() {
+                Some(Loading::Some(items)) => items[start - offset..=end - offset].iter().map(|item| item.as_ref().get_uri()).collect(),
+                _ => return Handled::Consumed,
+            };
+            ItemEdit::Remove { start, uris }
+        } else {
+            let Some((start, end)) = cut.take() else {
+                mutation::notify("Mark items to move with x first".into());
+                return Handled::Consumed;
+            };
+            let insert = if key == key!('p') { position + 1 } else { position };
+            if (start..=end + 1).contains(&insert) {
+                return Handled::Consumed;
+            }
+            ItemEdit::Move { start, length: end - start + 1, insert }
+        };
+
+        // Show the edit right away when everything it touches is on the page
+        let mut shown = false;
+        if let Some(Loading::Some(items)) = pages.items.lock().unwrap().as_mut() {
+            match &edit {
+                ItemEdit::Move { start, length, insert } => {
+                    if *start >= offset && start + length <= offset + len && (offset..=offset + len).contains(insert) {
+                        let moved = items.drain(start - offset..start - offset + length).collect::<Vec<_>>();
+                        // Positions after the moved items shift back once they are taken out
+                        let at = if insert > start { insert - length } else { *insert } - offset;
+                        items.splice(at..at, moved);
+                        state.select(Some(at));
+                        shown = true;
+                    }
+                }
+                ItemEdit::Remove { uris, .. } => {
+                    // Spotify removes every occurrence of the items
+                    items.retain(|item| !uris.contains(&item.as_ref().get_uri()));
+                    state.select((!items.is_empty()).then(|| selected.min(items.len() - 1)));
+                    shown = true;
+                }
+            }
+        }
+
+        edit_items(api, playlist.id.clone(), snapshot.clone(), pages.clone(), edit, !shown);
+        Handled::Consumed
+    }
+
+    /// Positions on the page shown of the items marked to be moved
+    pub fn cut(&self) -> Option<(usize, usize)> {
+        match self {
+            Landing::Playlist { cut: Some((start, end)), pages, .. } => {
+                let offset = pages.page.lock().unwrap().offset;
+                (*end >= offset).then(|| (start.saturating_sub(offset), end - offset))
+            }
+            _ => None,
+        }
     }
 
     pub fn select(&self) -> Option<Vec<(KeyEvent, Action, &'static str)>> {
         match self {
-            Landing::Playlist{ playlist, pages, state, section, .. } => {
-                let i = pages.items.clone();
+            Landing::Playlist{ playlist, owned, pages, state, section, .. } => {
                 return match section {
                     LandingSection::Content => if let Some(Loading::Some(items)) = pages.items.lock().unwrap().as_ref() {
                         let index = state.selected().unwrap_or(0);
+                        let saved = items.get(index)?;
                         let mut actions = vec![
-                            (key!(Enter), Action::PlayContext(Play::playlist(playlist.id.clone(), Some(pages.page.lock().unwrap().offset + index), 0)), action_label::PLAY)
+                            (key!(Enter), Action::PlayContext(Play::playlist(playlist.id.clone(), Some(pages.context_offset(saved, index)), 0)), action_label::PLAY)
                         ];
-                        actions.extend(match items.get(index) {
-                            Some(saved) => saved.into_actions(false, move |saved| {
-                                if let Some(Loading::Some(items)) = i.lock().unwrap().as_mut().map(|v| v.as_mut()) {
-                                    items[index].saved = saved;
-                                }
-                                Ok(())
-                            }),
-                            None => return None
-                        });
+                        actions.extend(saved.into_actions(false));
                         Some(actions)
                     } else {
                         None
                     },
-                    LandingSection::Context => Some(vec![
-                        (key!(Enter), Action::PlayContext(Play::playlist(playlist.id.clone(), None, 0)), action_label::PLAY)
-                    ])
+                    LandingSection::Context => {
+                        let uri = Uri::playlist(playlist.id.clone());
+                        let mut actions = vec![
+                            (key!(Enter), Action::PlayContext(Play::playlist(playlist.id.clone(), None, 0)), action_label::PLAY)
+                        ];
+                        actions.extend(queue_actions(&uri));
+                        if *owned {
+                            actions.extend([
+                                (key!('e'), Action::ManagePlaylist(PlaylistForm::Edit(uri.clone())), action_label::EDIT_PLAYLIST),
+                                (key!('i'), Action::ManagePlaylist(PlaylistForm::Cover(uri.clone())), action_label::UPLOAD_COVER),
+                                (key!('D' + SHIFT), Action::ManagePlaylist(PlaylistForm::Delete(uri.clone(), Box::new(Action::remove(uri)))), action_label::DELETE_PLAYLIST),
+                            ]);
+                        }
+                        Some(actions)
+                    },
                 };
             },
             Landing::Album{ album, section, pages, state, .. } => {
-                let i = pages.items.clone();
                 return match section {
                     // Play context from offset instead of playing normally
                     LandingSection::Content => if let Some(Loading::Some(items)) = pages.items.lock().unwrap().as_ref() {
                         let index = state.selected().unwrap_or(0);
-                        let page = pages.page.lock().unwrap();
                         items.get(index).map(|t| {
                             let mut actions = vec![
-                                (key!(Enter), Action::PlayContext(Play::album(album.id.clone(), Some(page.offset + index), 0)), action_label::PLAY)
+                                (key!(Enter), Action::PlayContext(Play::album(album.id.clone(), Some(pages.context_offset(t, index)), 0)), action_label::PLAY)
                             ];
-                            actions.extend(t.into_actions(false, move |saved| {
-                                if let Some(Loading::Some(items)) = i.lock().unwrap().as_mut().map(|v| v.as_mut()) {
-                                    items[index].saved = saved;
-                                }
-                                Ok(())
-                            }));
+                            actions.extend(t.into_actions(false));
                             actions
                         })
                     } else {
@@ -610,25 +943,18 @@ impl Landing {
                     },
                     LandingSection::Context => Some(vec![
                         (key!(Enter), Action::PlayContext(Play::album(album.id.clone(), None, 0)), action_label::PLAY)
-                    ]),
+                    ].into_iter().chain(queue_actions(&album.uri)).collect()),
                 };
             },
             Landing::Show{ show, section, pages, state, .. } => {
-                let i = pages.items.clone();
                 return match section {
                     LandingSection::Content => if let Some(Loading::Some(items)) = pages.items.lock().unwrap().as_ref() {
-                        let page = pages.page.lock().unwrap();
                         let index = state.selected().unwrap_or(0);
                         items.get(index).map(|e| {
                             let mut actions = vec![
-                                (key!(Enter), Action::PlayContext(Play::show(show.id.clone(), Some(page.offset + index), 0)), action_label::PLAY)
+                                (key!(Enter), Action::PlayContext(Play::show(show.id.clone(), Some(pages.context_offset(e, index)), 0)), action_label::PLAY)
                             ];
-                            actions.extend(e.into_actions(false, move |saved| {
-                                if let Some(Loading::Some(items)) = i.lock().unwrap().as_mut().map(|v| v.as_mut()) {
-                                    items[index].saved = saved;
-                                }
-                                Ok(())
-                            }));
+                            actions.extend(e.into_actions(false));
                             actions
                         })
                     } else {
@@ -636,7 +962,7 @@ impl Landing {
                     },
                     LandingSection::Context => Some(vec![
                         (key!(Enter), Action::PlayContext(Play::show(show.id.clone(), None, 0)), action_label::PLAY)
-                    ])
+                    ].into_iter().chain(queue_actions(&show.uri)).collect())
                 }
             },
             Landing::Audiobook{ audiobook, section, pages, state, .. } => {
@@ -645,7 +971,7 @@ impl Landing {
                         let index = state.selected().unwrap_or(0);
                         items.items.get(index).map(|t| {
                             let mut actions = vec![
-                                (key!(Enter), Action::PlayContext(Play::show(audiobook.id.clone(), Some(items.offset + index), 0)), action_label::PLAY)
+                                (key!(Enter), Action::PlayContext(Play::show(audiobook.id.clone(), Some(pages.context_offset(t, index)), 0)), action_label::PLAY)
                             ];
                             actions.extend(t.into_actions(false));
                             actions
@@ -662,19 +988,13 @@ impl Landing {
                 return match landing_section {
                     LandingSection::Content => match section {
                         ArtistLanding::Albums => {
-                            let i = albums.items.clone();
                             if let Some(Loading::Some(items)) = albums.items.lock().unwrap().as_ref() {
                                 let index = state.selected().unwrap_or(0);
                                 items.get(index).map(|t| {
                                     let mut actions = vec![
                                         (key!(Enter), Action::PlayContext(Play::album(t.as_ref().id.clone(), None, 0)), action_label::PLAY)
                                     ];
-                                    actions.extend(t.into_actions(false, move |saved| {
-                                        if let Some(Loading::Some(items)) = i.lock().unwrap().as_mut().map(|v| v.as_mut()) {
-                                            items[index].saved = saved;
-                                        } 
-                                        Ok(())
-                                    }));
+                                    actions.extend(t.into_actions(false));
                                     actions
                                 })
                             } else {
@@ -683,34 +1003,27 @@ impl Landing {
                         }
                         ArtistLanding::Tracks => {
                             let index = state.selected().unwrap_or(0);
-                            let tt = top_tracks.clone();
                             return top_tracks.lock().unwrap().get(index).map(|t| {
                                 let mut actions = vec![
                                     (key!(Enter), Action::Play(t.as_ref().uri.clone()), action_label::PLAY)
                                 ];
-                                actions.extend(t.into_actions(false, move |saved| {
-                                    tt.lock().unwrap()[index].saved = saved;
-                                    Ok(())
-                                }));
+                                actions.extend(t.into_actions(false));
                                 actions
                             })
                         },
                     },
                     LandingSection::Context => {
-                        let a = artist.clone();
-                        let update_saved = move |saved| {
-                            a.lock().unwrap().saved = saved;
-                            Ok(())
-                        };
                         let artist = artist.lock().unwrap();
-                        Some(vec![
+                        let mut actions = vec![
                             (key!(Enter), Action::PlayContext(Play::artist(artist.as_ref().id.clone())), action_label::PLAY),
-                            if artist.saved {
-                                (key!('r'), Action::remove(artist.as_ref().uri.clone(), update_saved), action_label::REMOVE)
+                            if artist.saved() {
+                                (key!('r'), Action::remove(artist.as_ref().uri.clone()), action_label::REMOVE)
                             } else {
-                                (key!('f'), Action::save(artist.as_ref().uri.clone(), update_saved), action_label::SAVE)
+                                (key!('f'), Action::Save(artist.as_ref().uri.clone()), action_label::SAVE)
                             },
-                        ])
+                        ];
+                        actions.extend(queue_actions(&artist.as_ref().uri));
+                        Some(actions)
                     }
                 }
             },
@@ -719,3 +1032,69 @@ impl Landing {
         None
     }
 }
+
+impl Component for Landing {
+    fn key(&mut self, key: KeyEvent, ctx: &Context) -> Handled {
+        let handled = view_key(self.table(), key);
+        if handled.is_ignored() {
+            let marked = match self.marked_table() {
+                Some((table, selected)) => mark_key(Some(table), key, selected),
+                None => Handled::Ignored,
+            };
+            if !marked.is_ignored() {
+                return marked;
+            }
+            return self.edit_key(key, ctx.api);
+        }
+        if handled.is_consumed() {
+            match self {
+                Landing::Artist{ state, section, .. } if section.is_albums() => state.select(Some(0)),
+                Landing::Artist{ .. } | Landing::None => {},
+                Landing::Playlist{ state, .. }
+                | Landing::Album{ state, .. }
+                | Landing::Show{ state, .. }
+                | Landing::Audiobook{ state, .. } => state.select(Some(0)),
+            }
+        }
+        handled
+    }
+
+    fn text_input(&self) -> bool {
+        self.table().is_some_and(|table| table.view().editing)
+    }
+
+    fn event(&mut self, event: &Event, ctx: &Context) -> Result<Handled> {
+        Ok(match event {
+            Event::Down => { self.down(); Handled::Consumed },
+            Event::Up => { self.up(); Handled::Consumed },
+            Event::Motion(motion) => { self.jump(*motion); Handled::Consumed },
+            Event::Right => { self.right(); Handled::Consumed },
+            Event::Left => { self.left(); Handled::Consumed },
+            Event::Tab => { self.tab()?; Handled::Consumed },
+            Event::Backtab => { self.backtab()?; Handled::Consumed },
+            Event::Select => {
+                let marked = self.marked_table().and_then(|(table, selected)| take_marked(Some(table), selected));
+                // Landings aren't lists of saved items, removing only changes whether rows are saved
+                open_actions(marked.map(|uris| batch_actions(uris, None)).or_else(|| self.select()), ctx)
+            },
+            _ => Handled::Ignored,
+        })
+    }
+
+    fn mouse(&mut self, column: u16, row: u16) -> Click {
+        self.click(column, row)
+    }
+
+    fn focus(&mut self) {
+        self.load();
+    }
+
+    fn refresh(&mut self, _ctx: &Context) -> bool {
+        self.refresh();
+        true
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Widget::render(self, area, buf);
+    }
+}
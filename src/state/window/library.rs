@@ -6,12 +6,14 @@ use strum::EnumCount;
 
 use color_eyre::Result;
 use color_eyre::eyre::Error;
-use ratatui::widgets::TableState;
+use serde::{Deserialize, Serialize};
+use ratatui::{buffer::Buffer, layout::{Position, Rect}, widgets::{TableState, Widget}};
 use tupy::{api::{flow::{AuthFlow, Pkce}, request::{Query, SearchType, Play}, response::{Paged, FollowedArtists, SavedAudiobooks, SimplifiedPlaylist, SavedAlbums, Paginated, PagedPlaylists, SavedShows}, PublicApi, UserApi, Uri}, Pagination};
 
 use crate::key;
-use crate::{state::{IterCollection, Loading, actions::{action_label, Action, GoTo}, wrappers::Saved}, PAGE_SIZE};
+use crate::{app::Event, errors::LogError, state::{component::{open_actions, Component, Context, Handled}, library_index, Click, IterCollection, Loading, Motion, TableArea, actions::{action_label, batch_actions, queue_actions, Action, GoTo, PlaylistForm}}, Locked, Shared, PAGE_SIZE};
 use super::Pages;
+use crate::state::view::{mark_key, take_marked, view_key, Columns, Filterable, Rows};
 
 static USER_PLAYLISTS_FILENAME: &str = "user.playlists.cache";
 static USER_FILENAME: &str = "user.id.cache";
@@ -40,7 +42,8 @@ impl UserPlaylists {
     }
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, strum_macros::IntoStaticStr, strum_macros::EnumIter, strum_macros::FromRepr, strum_macros::EnumCount)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize, strum_macros::IntoStaticStr, strum_macros::EnumIter, strum_macros::FromRepr, strum_macros::EnumCount)]
+#[serde(rename_all = "kebab-case")]
 pub enum FromSpotify {
     #[default]
     #[strum(serialize = "Release Radar")]
@@ -98,7 +101,8 @@ impl FromSpotify {
     }
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, strum_macros::IntoStaticStr, strum_macros::EnumIter, strum_macros::FromRepr, strum_macros::EnumCount)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, strum_macros::IntoStaticStr, strum_macros::EnumIter, strum_macros::FromRepr, strum_macros::EnumCount)]
+#[serde(rename_all = "kebab-case")]
 pub enum LibraryTab {
     #[default]
     Playlists,
@@ -177,6 +181,11 @@ pub struct LibraryState {
     pub selected_tab: LibraryTab,
     pub result_state: TableState,
 
+    // Areas last rendered to, used for mouse interactions
+    pub tile_areas: Vec<Rect>,
+    pub tab_areas: Vec<Rect>,
+    pub result_area: TableArea,
+
     // State for paginated results
     pub playlists: Pages<PagedPlaylists, PagedPlaylists>,
     pub artists: Pages<FollowedArtists, HashMap<String, FollowedArtists>>,
@@ -185,157 +194,313 @@ pub struct LibraryState {
     pub audiobooks: Pages<SavedAudiobooks, SavedAudiobooks>,
 }
 
-impl LibraryState {
-    pub async fn tab(&mut self) -> Result<()> {
-        self.selected_tab += 1;
-        if self.result_state.selected().is_some() {
-            self.result_state.select(Some(0));
+/// Callback for removing an item from a page of the library. The item is dropped from the page
+/// right away and put back where it was if removing it fails.
+fn remove_from_page<R, P>(
+    pages: &Pages<R, P>,
+    index: usize,
+    item: R::Row,
+) -> impl Fn(bool) -> Result<()> + Send + Sync + 'static
+where
+    R: Clone + Debug + Send + Paged + Rows + 'static,
+    P: Clone + Debug + Send,
+    R::Row: Send + Sync + 'static,
+{
+    let items = pages.items.clone();
+    let all = pages.all.clone();
+    // Position across every page when they are loaded to filter or sort the items
+    let all_index = all.lock().unwrap().as_ref().and_then(|all| all.rows().iter().position(|i| *i == item));
+    move |saved| {
+        if let Some(Loading::Some(page)) = items.lock().unwrap().as_mut() {
+            remove_or_restore(page.rows_mut(), index, &item, saved);
         }
-        match self.selected_tab {
-            LibraryTab::Playlists if self.playlists.items.lock().unwrap().is_none() && self.playlists.pager.lock().await.has_next() => {
-                self.playlists.next().await?;
-            },
-            LibraryTab::Artists if self.artists.items.lock().unwrap().is_none() && self.artists.pager.lock().await.has_next() => {
-                self.artists.next().await?;
-            },
-            LibraryTab::Albums if self.albums.items.lock().unwrap().is_none() && self.albums.pager.lock().await.has_next() => {
-                self.albums.next().await?;
-            },
-            LibraryTab::Shows if self.shows.items.lock().unwrap().is_none() && self.shows.pager.lock().await.has_next() => {
-                self.shows.next().await?;
-            },
-            LibraryTab::Audiobooks if self.audiobooks.items.lock().unwrap().is_none() && self.audiobooks.pager.lock().await.has_next() => {
-                self.audiobooks.next().await?;
-            }
-            _ =>{}
+        if let (Some(all), Some(all_index)) = (all.lock().unwrap().as_mut(), all_index) {
+            remove_or_restore(all.rows_mut(), all_index, &item, saved);
         }
         Ok(())
     }
+}
+
+/// Callback for removing the marked items from a page of the library. The items are dropped from
+/// the page right away and put back where they were if removing them fails.
+fn remove_all_from_page<R, P>(pages: &Pages<R, P>, uris: Vec<Uri>) -> impl Fn(bool) -> Result<()> + Send + Sync + 'static
+where
+    R: Clone + Debug + Send + Paged + Rows + 'static,
+    P: Clone + Debug + Send + Deserialize<'static> + 'static,
+    R::Row: Send + Sync + 'static,
+{
+    let pages = pages.clone();
+    // Rows dropped from the page and from every page, along with where they were
+    let page_rows = Locked::new(Vec::new());
+    let all_rows = Locked::new(Vec::new());
+    move |saved| {
+        if let Some(Loading::Some(page)) = pages.items.lock().unwrap().as_mut() {
+            take_or_restore(page.rows_mut(), &uris, &mut page_rows.lock().unwrap(), saved);
+        }
+        if let Some(all) = pages.all.lock().unwrap().as_mut() {
+            take_or_restore(all.rows_mut(), &uris, &mut all_rows.lock().unwrap(), saved);
+        }
+        Ok(())
+    }
+}
+
+/// Take the rows of the items out of the list, or put back the rows that were taken for items
+/// that are saved again. Rows for items that stayed removed are kept in case they are undone.
+fn take_or_restore<T: Columns + Clone>(list: &mut Vec<T>, uris: &[Uri], taken: &mut Vec<(usize, T)>, saved: bool) {
+    if saved {
+        // Taken in order, so each row goes back in front of the rows that came after it
+        let (restored, kept): (Vec<_>, Vec<_>) = taken.drain(..).partition(|(_, row)| library_index::is_saved(&row.uri()));
+        *taken = kept;
+        for (index, row) in restored {
+            list.insert(index.min(list.len()), row);
+        }
+        return;
+    }
+    let mut index = 0;
+    list.retain(|row| {
+        let keep = !uris.contains(&row.uri());
+        if !keep {
+            taken.push((index, row.clone()));
+        }
+        index += 1;
+        keep
+    });
+}
+
+fn remove_or_restore<T: Clone + PartialEq>(list: &mut Vec<T>, index: usize, item: &T, saved: bool) {
+    if !saved {
+        list.retain(|i| i != item);
+    } else if !list.contains(item) {
+        list.insert(index.min(list.len()), item.clone());
+    }
+}
+
+/// Search for a playlist made by spotify if it isn't already known
+async fn find_spotify_playlist(api: &Pkce, known: Option<Uri>, query: &str, matches: impl Fn(&str) -> bool) -> Result<Option<Uri>> {
+    if known.is_some() {
+        return Ok(known);
+    }
+
+    let mut search = api.search::<2, _>(&[Query::text(query)], &[SearchType::Playlist], None, false)?;
+    if let Some(playlists) = search.playlists() {
+        if let Some(page) = playlists.next().await? {
+            return Ok(page.items.into_iter()
+                .find(|playlist| matches(playlist.name.as_str()) && playlist.owner.id.as_str() == "spotify")
+                .map(|playlist| playlist.uri));
+        }
+    }
+    Ok(None)
+}
+
+impl LibraryState {
+    /// Results of the selected tab, which can be filtered and sorted
+    pub fn table(&self) -> &dyn Filterable {
+        match self.selected_tab {
+            LibraryTab::Playlists => &self.playlists,
+            LibraryTab::Artists => &self.artists,
+            LibraryTab::Albums => &self.albums,
+            LibraryTab::Shows => &self.shows,
+            LibraryTab::Audiobooks => &self.audiobooks,
+        }
+    }
+
+    pub fn tab(&mut self) {
+        self.select_tab(self.selected_tab + 1);
+    }
+
+    pub fn backtab(&mut self) {
+        self.select_tab(self.selected_tab - 1);
+    }
 
-    pub async fn backtab(&mut self) -> Result<()> {
-        self.selected_tab -= 1;
+    /// Switch to the tab, fetching its first page in the background if it hasn't been fetched yet
+    pub fn select_tab(&mut self, tab: LibraryTab) {
+        self.selected_tab = tab;
         if self.result_state.selected().is_some() {
             self.result_state.select(Some(0));
         }
+        self.load();
+    }
+
+    /// Offset of the first item on the page shown in the tab
+    pub fn offset(&self, tab: LibraryTab) -> usize {
+        match tab {
+            LibraryTab::Playlists => self.playlists.page.lock().unwrap().offset,
+            LibraryTab::Artists => self.artists.page.lock().unwrap().offset,
+            LibraryTab::Albums => self.albums.page.lock().unwrap().offset,
+            LibraryTab::Shows => self.shows.page.lock().unwrap().offset,
+            LibraryTab::Audiobooks => self.audiobooks.page.lock().unwrap().offset,
+        }
+    }
+
+    /// Start the tab from the page containing the item offset when it is first fetched
+    pub fn start_at(&self, tab: LibraryTab, offset: usize) {
+        match tab {
+            LibraryTab::Playlists => self.playlists.start_at(offset),
+            LibraryTab::Artists => self.artists.start_at(offset),
+            LibraryTab::Albums => self.albums.start_at(offset),
+            LibraryTab::Shows => self.shows.start_at(offset),
+            LibraryTab::Audiobooks => self.audiobooks.start_at(offset),
+        }
+    }
+
+    /// Fetch the selected tab if it hasn't been fetched yet
+    pub fn load(&self) {
         match self.selected_tab {
-            LibraryTab::Playlists if self.playlists.items.lock().unwrap().is_none() && self.playlists.pager.lock().await.has_next() => {
-                self.playlists.next().await?;
-            },
-            LibraryTab::Artists if self.artists.items.lock().unwrap().is_none() && self.artists.pager.lock().await.has_next() => {
-                self.artists.next().await?;
-            },
-            LibraryTab::Albums if self.albums.items.lock().unwrap().is_none() && self.albums.pager.lock().await.has_next() => {
-                self.albums.next().await?;
-            },
-            LibraryTab::Shows if self.shows.items.lock().unwrap().is_none() && self.shows.pager.lock().await.has_next() => {
-                self.shows.next().await?;
-            },
-            LibraryTab::Audiobooks if self.audiobooks.items.lock().unwrap().is_none() && self.audiobooks.pager.lock().await.has_next() => {
-                self.audiobooks.next().await?;
+            LibraryTab::Playlists => self.playlists.load(),
+            LibraryTab::Artists => self.artists.load(),
+            LibraryTab::Albums => self.albums.load(),
+            LibraryTab::Shows => self.shows.load(),
+            LibraryTab::Audiobooks => self.audiobooks.load(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self.selected_tab {
+            LibraryTab::Playlists => if let Some(Loading::Some(items)) = self.playlists.items.lock().unwrap().as_ref() {
+                items.items().len()
+            } else { 0 },
+            LibraryTab::Artists => if let Some(Loading::Some(items)) = self.artists.items.lock().unwrap().as_ref() {
+                items.items().len()
+            } else { 0 },
+            LibraryTab::Albums => if let Some(Loading::Some(items)) = self.albums.items.lock().unwrap().as_ref() {
+                items.items().len()
+            } else { 0 },
+            LibraryTab::Shows => if let Some(Loading::Some(items)) = self.shows.items.lock().unwrap().as_ref() {
+                items.items().len()
+            } else { 0 },
+            LibraryTab::Audiobooks => if let Some(Loading::Some(items)) = self.audiobooks.items.lock().unwrap().as_ref() {
+                items.items().len()
+            } else { 0 },
+        }
+    }
+
+    /// Select the spotify playlist, tab, or result under the position
+    pub fn click(&mut self, column: u16, row: u16) -> Click {
+        let position = Position::new(column, row);
+
+        if let Some(tile) = self.tile_areas.iter().position(|a| a.contains(position)).and_then(FromSpotify::from_repr) {
+            if self.selection.is_spotify_playlist() && self.selected_spotify_playlist == tile {
+                return Click::Activated;
             }
-            _ =>{}
+            self.selection = Selection::SpotifyPlaylist;
+            self.selected_spotify_playlist = tile;
+            self.result_state.select(None);
+            return Click::Selected;
+        }
+
+        if let Some(tab) = self.tab_areas.iter().position(|a| a.contains(position)).and_then(LibraryTab::from_repr) {
+            if self.selected_tab != tab {
+                self.select_tab(tab);
+            }
+            return Click::Selected;
+        }
+
+        match self.result_area.row(column, row, self.len()) {
+            Some(index) if self.selection.is_results() && self.result_state.selected() == Some(index) => Click::Activated,
+            Some(index) => {
+                self.selection = Selection::Results;
+                self.result_state.select(Some(index));
+                Click::Selected
+            }
+            None => Click::Miss,
         }
-        Ok(())
     }
 
-    pub async fn right(&mut self) -> Result<()> {
+    pub fn right(&mut self) {
         match self.selection {
             Selection::SpotifyPlaylist => {
                 self.selected_spotify_playlist += 1;
             },
             Selection::Results => {
                 match self.selected_tab {
-                    LibraryTab::Playlists if self.playlists.items.lock().unwrap().is_some() && self.playlists.pager.lock().await.has_next() => {
-                        self.playlists.next().await?;
+                    LibraryTab::Playlists if self.playlists.items.lock().unwrap().is_some() => {
+                        self.playlists.next_page();
                         self.result_state.select(Some(0));
                     },
-                    LibraryTab::Artists if self.artists.items.lock().unwrap().is_some() && self.artists.pager.lock().await.has_next() => {
-                        self.artists.next().await?;
+                    LibraryTab::Artists if self.artists.items.lock().unwrap().is_some() => {
+                        self.artists.next_page();
                         self.result_state.select(Some(0));
                     },
-                    LibraryTab::Albums if self.albums.items.lock().unwrap().is_some() && self.albums.pager.lock().await.has_next() => {
-                        self.albums.next().await?;
+                    LibraryTab::Albums if self.albums.items.lock().unwrap().is_some() => {
+                        self.albums.next_page();
                         self.result_state.select(Some(0));
                     },
-                    LibraryTab::Shows if self.shows.items.lock().unwrap().is_some() && self.shows.pager.lock().await.has_next() => {
-                        self.shows.next().await?;
+                    LibraryTab::Shows if self.shows.items.lock().unwrap().is_some() => {
+                        self.shows.next_page();
                         self.result_state.select(Some(0));
                     },
-                    LibraryTab::Audiobooks if self.audiobooks.items.lock().unwrap().is_some() && self.audiobooks.pager.lock().await.has_next() => {
-                        self.audiobooks.next().await?;
+                    LibraryTab::Audiobooks if self.audiobooks.items.lock().unwrap().is_some() => {
+                        self.audiobooks.next_page();
                         self.result_state.select(Some(0));
-                    }
+                    },
                     _ =>{}
                 }
             }
         }
-        Ok(())
     }
 
-    pub async fn left(&mut self) -> Result<()> {
+    pub fn left(&mut self) {
         match self.selection {
             Selection::SpotifyPlaylist => {
                 self.selected_spotify_playlist -= 1;
             },
             Selection::Results => {
                 match self.selected_tab {
-                    LibraryTab::Playlists if self.playlists.items.lock().unwrap().is_some() && self.playlists.pager.lock().await.has_prev() => {
-                        self.playlists.prev().await?;
+                    LibraryTab::Playlists if self.playlists.items.lock().unwrap().is_some() => {
+                        self.playlists.prev_page();
                         self.result_state.select(Some(0));
                     },
-                    LibraryTab::Artists if self.artists.items.lock().unwrap().is_some() && self.artists.pager.lock().await.has_prev() => {
-                        self.artists.prev().await?;
+                    LibraryTab::Artists if self.artists.items.lock().unwrap().is_some() => {
+                        self.artists.prev_page();
                         self.result_state.select(Some(0));
                     },
-                    LibraryTab::Albums if self.albums.items.lock().unwrap().is_some() && self.albums.pager.lock().await.has_prev() => {
-                        self.albums.prev().await?;
+                    LibraryTab::Albums if self.albums.items.lock().unwrap().is_some() => {
+                        self.albums.prev_page();
                         self.result_state.select(Some(0));
                     },
-                    LibraryTab::Shows if self.shows.items.lock().unwrap().is_some() && self.shows.pager.lock().await.has_prev() => {
-                        self.shows.prev().await?;
+                    LibraryTab::Shows if self.shows.items.lock().unwrap().is_some() => {
+                        self.shows.prev_page();
                         self.result_state.select(Some(0));
                     },
-                    LibraryTab::Audiobooks if self.audiobooks.items.lock().unwrap().is_some() && self.audiobooks.pager.lock().await.has_prev() => {
-                        self.audiobooks.prev().await?;
+                    LibraryTab::Audiobooks if self.audiobooks.items.lock().unwrap().is_some() => {
+                        self.audiobooks.prev_page();
                         self.result_state.select(Some(0));
-                    }
+                    },
                     _ =>{}
                 }
             }
         }
-        Ok(())
     }
 
-    pub async fn refresh(&mut self) -> Result<()> {
+    pub fn refresh(&mut self) {
         if let Selection::Results = self.selection {
             match self.selected_tab {
                 LibraryTab::Playlists if self.playlists.items.lock().unwrap().is_some() => {
-                    self.playlists.refresh().await?;
+                    self.playlists.refresh_page();
                     self.result_state.select(None);
                 },
                 LibraryTab::Artists if self.artists.items.lock().unwrap().is_some() => {
-                    self.artists.refresh().await?;
+                    self.artists.refresh_page();
                     self.result_state.select(None);
                 },
                 LibraryTab::Albums if self.albums.items.lock().unwrap().is_some() => {
-                    self.albums.refresh().await?;
+                    self.albums.refresh_page();
                     self.result_state.select(None);
                 },
                 LibraryTab::Shows if self.shows.items.lock().unwrap().is_some() => {
-                    self.shows.refresh().await?;
+                    self.shows.refresh_page();
                     self.result_state.select(None);
                 },
                 LibraryTab::Audiobooks if self.audiobooks.items.lock().unwrap().is_some() => {
-                    self.audiobooks.refresh().await?;
+                    self.audiobooks.refresh_page();
                     self.result_state.select(None);
-                }
+                },
                 _ =>{}
             }
         }
-        Ok(())
     }
 
-    pub async fn down(&mut self) {
+    pub fn down(&mut self) {
         match self.selection {
             Selection::SpotifyPlaylist => {
                 let len = match self.selected_tab {
@@ -382,7 +547,20 @@ impl LibraryState {
         }
     }
 
-    pub async fn up(&mut self) {
+    /// Jump through the results, moving into them from the playlists spotify makes
+    pub fn jump(&mut self, motion: Motion) {
+        let len = self.len();
+        if len == 0 {
+            return;
+        }
+        if self.selection == Selection::SpotifyPlaylist {
+            self.selection = Selection::Results;
+            self.result_state.select(Some(0));
+        }
+        self.result_state.jump_in_list(motion, len, self.result_area.area.height as usize);
+    }
+
+    pub fn up(&mut self) {
         match self.selection {
             Selection::SpotifyPlaylist => {},
             Selection::Results => {
@@ -413,6 +591,27 @@ impl LibraryState {
         }
     }
 
+    /// Actions for every item marked in the results, if there are any
+    pub fn marked_actions(&self) -> Option<Vec<(KeyEvent, Action, &'static str)>> {
+        if !self.selection.is_results() {
+            return None;
+        }
+        let uris = take_marked(Some(self.table()), self.result_state.selected())?;
+        let removed: Shared<dyn Fn(bool) -> Result<()> + Sync + Send> = match self.selected_tab {
+            // Unfollowing the user's own playlists deletes them, which is always asked about first
+            LibraryTab::Playlists => {
+                let mut actions = batch_actions(uris, None);
+                actions.retain(|(_, action, _)| !matches!(action, Action::RemoveAll(..)));
+                return Some(actions);
+            }
+            LibraryTab::Artists => Shared::new(remove_all_from_page(&self.artists, uris.clone())),
+            LibraryTab::Albums => Shared::new(remove_all_from_page(&self.albums, uris.clone())),
+            LibraryTab::Shows => Shared::new(remove_all_from_page(&self.shows, uris.clone())),
+            LibraryTab::Audiobooks => Shared::new(remove_all_from_page(&self.audiobooks, uris.clone())),
+        };
+        Some(batch_actions(uris, Some(removed)))
+    }
+
     pub fn select(&self) -> Option<Vec<(KeyEvent, Action, &'static str)>> {
         match self.selection {
             Selection::SpotifyPlaylist => match self.selected_spotify_playlist {
@@ -420,19 +619,19 @@ impl LibraryState {
                     return Some(vec![
                         (key!(Enter), Action::PlayContext(Play::playlist(release.clone(), None, 0)), action_label::PLAY),
                         (key!('C' + SHIFT), Action::GoTo(GoTo::Playlist(release.clone())), action_label::GO_TO_PLAYLIST),
-                    ])
+                    ].into_iter().chain(queue_actions(release)).collect())
                 },
                 FromSpotify::DiscoverWeekly => if let Some(discover) = self.user_playlists.discover.as_ref() {
                     return Some(vec![
                         (key!(Enter), Action::PlayContext(Play::playlist(discover.clone(), None, 0)), action_label::PLAY),
                         (key!('C' + SHIFT), Action::GoTo(GoTo::Playlist(discover.clone())), action_label::GO_TO_PLAYLIST),
-                    ])
+                    ].into_iter().chain(queue_actions(discover)).collect())
                 },
                 FromSpotify::Daylist => if let Some(daylist) = self.user_playlists.daylist.as_ref() {
                     return Some(vec![
                         (key!(Enter), Action::PlayContext(Play::playlist(daylist.clone(), None, 0)), action_label::PLAY),
                         (key!('C' + SHIFT), Action::GoTo(GoTo::Playlist(daylist.clone())), action_label::GO_TO_PLAYLIST),
-                    ])
+                    ].into_iter().chain(queue_actions(daylist)).collect())
                 },
                 FromSpotify::LikedSongs => {
                     let uri = Uri::collection(self.user_id.clone());
@@ -449,92 +648,80 @@ impl LibraryState {
             },
             Selection::Results => match self.selected_tab {
                 LibraryTab::Playlists => {
-                    let i = self.playlists.items.clone();
                     if let Some(Loading::Some(items)) = self.playlists.items.lock().unwrap().as_ref() {
                         let index = self.result_state.selected().unwrap_or(0);
                         let item = items.items.get(index)?;
-                        return Some(vec![
+                        let remove = Action::remove_from(item.uri.clone(), remove_from_page(&self.playlists, index, item.clone()));
+                        let mut actions = vec![
                             (key!(Enter), Action::PlayContext(Play::playlist(item.uri.clone(), None, 0)), action_label::PLAY),
-                            (key!('r'), Action::remove(item.uri.clone(), move |_| {
-                                if let Some(Loading::Some(items)) = i.lock().unwrap().as_mut().map(|v| v.as_mut()) {
-                                    items.items.remove(index);
-                                }
-                                Ok(())
-                            }), action_label::REMOVE),
-                            // TODO: Action to add entire playlist to queue
                             (key!('C' + SHIFT), Action::GoTo(GoTo::Playlist(item.uri.clone())), action_label::GO_TO_PLAYLIST),
-                        ])
+                            (key!('n'), Action::ManagePlaylist(PlaylistForm::Create), action_label::NEW_PLAYLIST),
+                        ];
+                        actions.extend(queue_actions(&item.uri));
+                        if item.owner.id == self.user_id {
+                            // Unfollowing is the only way to delete a playlist, so ask first
+                            actions.extend([
+                                (key!('e'), Action::ManagePlaylist(PlaylistForm::Edit(item.uri.clone())), action_label::EDIT_PLAYLIST),
+                                (key!('i'), Action::ManagePlaylist(PlaylistForm::Cover(item.uri.clone())), action_label::UPLOAD_COVER),
+                                (key!('D' + SHIFT), Action::ManagePlaylist(PlaylistForm::Delete(item.uri.clone(), Box::new(remove))), action_label::DELETE_PLAYLIST),
+                            ]);
+                        } else {
+                            actions.push((key!('r'), remove, action_label::REMOVE));
+                        }
+                        return Some(actions);
                     }
                 },
                 LibraryTab::Artists => {
-                    let i = self.artists.items.clone();
                     if let Some(Loading::Some(items)) = self.artists.items.lock().unwrap().as_ref() {
                         let index = self.result_state.selected().unwrap_or(0);
                         let item = items.items.get(index)?;
-                        return Some(vec![
+                        let mut actions = vec![
                             (key!(Enter), Action::PlayContext(Play::artist(item.uri.clone())), action_label::PLAY),
-                            (key!('r'), Action::remove(item.uri.clone(), move |_| {
-                                if let Some(Loading::Some(items)) = i.lock().unwrap().as_mut().map(|v| v.as_mut()) {
-                                    items.items.remove(index);
-                                }
-                                Ok(())
-                            }), action_label::REMOVE),
+                            (key!('r'), Action::remove_from(item.uri.clone(), remove_from_page(&self.artists, index, item.clone())), action_label::REMOVE),
                             (key!('C' + SHIFT), Action::GoTo(GoTo::Artist(item.uri.clone())), action_label::GO_TO_ARTIST),
-                        ])
+                        ];
+                        actions.extend(queue_actions(&item.uri));
+                        return Some(actions);
                     }
                 },
                 LibraryTab::Albums => {
-                    let i = self.albums.items.clone();
                     if let Some(Loading::Some(items)) = self.albums.items.lock().unwrap().as_ref() {
                         let index = self.result_state.selected().unwrap_or_default();
                         let item = items.items.get(index)?;
-                        return Some(vec![
+                        let mut actions = vec![
                             (key!(Enter), Action::PlayContext(Play::album(item.album.uri.clone(), None, 0)), action_label::PLAY),
-                            (key!('r'), Action::remove(item.album.uri.clone(), move |_| {
-                                    if let Some(Loading::Some(items)) = i.lock().unwrap().as_mut().map(|v| v.as_mut()) {
-                                        items.items.remove(index);
-                                    }
-                                    Ok(())
-                                }), action_label::REMOVE),
+                            (key!('r'), Action::remove_from(item.album.uri.clone(), remove_from_page(&self.albums, index, item.clone())), action_label::REMOVE),
                             (key!('C' + SHIFT), Action::GoTo(GoTo::Album(item.album.uri.clone())), action_label::GO_TO_ALBUM),
                             if item.album.artists.len() > 1 {
                                 (key!('A' + SHIFT), Action::GoTo(GoTo::Artists(item.album.artists.iter().map(|a| (a.uri.clone(), a.name.clone())).collect::<Vec<_>>())), action_label::SELECT_ARTIST)
                             } else {
                                 (key!('A' + SHIFT), Action::GoTo(GoTo::Artist(item.album.artists[0].uri.clone())), action_label::GO_TO_ARTIST)
                             }
-                        ])
+                        ];
+                        actions.extend(queue_actions(&item.album.uri));
+                        return Some(actions);
                     }
                 },
                 LibraryTab::Shows => {
-                    let i = self.shows.items.clone();
                     if let Some(Loading::Some(items)) = self.shows.items.lock().unwrap().as_ref() {
                         let index = self.result_state.selected().unwrap_or_default();
                         let item = items.items.get(index)?;
-                        return Some(vec![
+                        let mut actions = vec![
                             (key!(Enter), Action::PlayContext(Play::show(item.show.uri.clone(), None, 0)), action_label::PLAY),
-                            (key!('r'), Action::remove(item.show.uri.clone(), move |_| {
-                                        if let Some(Loading::Some(items)) = i.lock().unwrap().as_mut().map(|v| v.as_mut()) {
-                                            items.items.remove(index);
-                                        }
-                                        Ok(())
-                                    }), action_label::REMOVE),
+                            (key!('r'), Action::remove_from(item.show.uri.clone(), remove_from_page(&self.shows, index, item.clone())), action_label::REMOVE),
                             (key!('C' + SHIFT), Action::GoTo(GoTo::Show(item.show.uri.clone())), action_label::GO_TO_SHOW),
-                        ])
+                        ];
+                        actions.extend(queue_actions(&item.show.uri));
+                        return Some(actions);
                     }
                 },
                 LibraryTab::Audiobooks => {
-                    let i = self.audiobooks.items.clone();
                     if let Some(Loading::Some(items)) = self.audiobooks.items.lock().unwrap().as_ref() {
                         let index = self.result_state.selected().unwrap_or_default();
                         let item = items.items.get(index)?;
                         return Some(vec![
                             (key!(Enter), Action::PlayContext(Play::show(item.uri.clone(), None, 0)), action_label::PLAY),
-                            (key!('r'), Action::remove(item.uri.clone(), move |_| {
-                                if let Some(Loading::Some(items)) = i.lock().unwrap().as_mut().map(|v| v.as_mut()) {
-                                    items.items.remove(index);
-                                }
-                                Ok(())
-                            }), action_label::REMOVE),
+                            (key!('r'), Action::remove_from(item.uri.clone(), remove_from_page(&self.audiobooks, index, item.clone())), action_label::REMOVE),
                             (key!('C' + SHIFT), Action::GoTo(GoTo::Audiobook(item.uri.clone())), action_label::GO_TO_AUDIOBOOK)
                         ])
                     }
@@ -544,7 +731,7 @@ impl LibraryState {
         None
     }
 
-    pub async fn new(dir: &str, api: &Pkce) -> Result<Self> {
+    pub fn new(dir: &str, api: &Pkce) -> Result<Self> {
         let cache_playlist_path = dirs::cache_dir().unwrap().join(dir).join(USER_PLAYLISTS_FILENAME);
         let cache_user_id_path = dirs::cache_dir().unwrap().join(dir).join(USER_FILENAME);
         let mut user_playlists = UserPlaylists::default();
@@ -568,70 +755,11 @@ impl LibraryState {
             }
         }
 
-        if api.token().is_expired() {
-            api.refresh().await?;
-        }
-
-
-        // Search for Release Radar playlist on spotify. It is done this way as the user may
-        // not follow the playlist.
-        if user_playlists.release.is_none() {
-            let mut search = api.search::<2, _>(&[Query::text("Release Radar")], &[SearchType::Playlist], None, false)?;
-            if let Some(playlists) = search.playlists() {
-                if let Some(page) = playlists.next().await? {
-                    for playlist in page.items {
-                        if playlist.name.as_str() == "Release Radar" && playlist.owner.id.as_str() == "spotify" {
-                            user_playlists.release = Some(playlist.uri);
-                            break;
-                        }
-                    }
-                }
-            }
-        }
-
-        // Search for Discover Weekly playlist on spotify. It is done this way as the user may
-        // not follow the playlist.
-        if user_playlists.discover.is_none() {
-            let mut search = api.search::<2, _>(&[Query::text("Discover Weekly")], &[SearchType::Playlist], None, false)?;
-            if let Some(playlists) = search.playlists() {
-                if let Some(page) = playlists.next().await? {
-                    for playlist in page.items {
-                        if playlist.name.as_str() == "Discover Weekly" && playlist.owner.id.as_str() == "spotify" {
-                            user_playlists.discover = Some(playlist.uri);
-                            break;
-                        }
-                    }
-                }
-            }
-        }
-        
-        // Search for daylist playlist on spotify. It is done this way as the user may
-        // not follow the playlist.
-        if user_playlists.daylist.is_none() {
-            let mut search = api.search::<2, _>(&[Query::text("daylist")], &[SearchType::Playlist], None, false)?;
-            if let Some(playlists) = search.playlists() {
-                if let Some(page) = playlists.next().await? {
-                    for playlist in page.items {
-                        if playlist.name.starts_with("daylist") && playlist.owner.id.as_str() == "spotify" {
-                            user_playlists.daylist = Some(playlist.uri);
-                            break;
-                        }
-                    }
-                }
-            }
-        }
-
-        std::fs::write(cache_playlist_path, user_playlists.to_cache_string())?;
-
-        let user_id = if !cache_user_id_path.exists() {
-            if api.token().is_expired() {
-                api.refresh().await?;
-            }
-            let user_id = api.current_user_profile().await?.id.clone();
-            std::fs::write(cache_user_id_path, user_id.clone())?;
-            user_id
-        } else {
+        // Fetched in the background with `load_user` if it isn't cached
+        let user_id = if cache_user_id_path.exists() {
             std::fs::read_to_string(cache_user_id_path)?
+        } else {
+            String::new()
         };
 
         let layout_state = Self {
@@ -642,19 +770,119 @@ impl LibraryState {
             selected_tab: LibraryTab::default(),
             result_state: TableState::default(),
 
+            tile_areas: Vec::new(),
+            tab_areas: Vec::new(),
+            result_area: TableArea::default(),
+
             playlists: Pages::new(api.playlists::<PAGE_SIZE, _>(None)?),
             artists: Pages::new(api.followed_artists::<PAGE_SIZE>()?),
             albums: Pages::new(api.saved_albums::<PAGE_SIZE, _>(None)?),
             audiobooks: Pages::new(api.saved_audiobooks::<PAGE_SIZE>()?),
             shows: Pages::new(api.saved_shows::<PAGE_SIZE>()?),
         };
-        match layout_state.selected_tab {
-            LibraryTab::Playlists => layout_state.playlists.next().await?,
-            LibraryTab::Artists => layout_state.artists.next().await?,
-            LibraryTab::Albums => layout_state.albums.next().await?,
-            LibraryTab::Shows => layout_state.shows.next().await?,
-            LibraryTab::Audiobooks => layout_state.audiobooks.next().await?,
-        }
+        // The selected tab is fetched when the library is first shown, after the session is restored
         Ok(layout_state)
     }
+
+    /// True if the user and their spotify playlists still need to be fetched
+    pub fn needs_user(&self) -> bool {
+        let playlists = &self.user_playlists;
+        self.user_id.is_empty() || playlists.release.is_none() || playlists.discover.is_none() || playlists.daylist.is_none()
+    }
+
+    /// Fetch the user and find their spotify playlists that aren't cached yet
+    pub async fn load_user(library: Shared<Locked<Self>>, dir: &str, api: &Pkce) -> Result<()> {
+        let (user_id, mut user_playlists) = {
+            let library = library.lock().unwrap();
+            (library.user_id.clone(), library.user_playlists.clone())
+        };
+
+        if api.token().is_expired() {
+            api.refresh().await?;
+        }
+
+        // Search for the playlists on spotify. It is done this way as the user may not follow the
+        // playlists.
+        let (release, discover, daylist, user_id) = tokio::join!(
+            find_spotify_playlist(api, user_playlists.release.clone(), "Release Radar", |name| name == "Release Radar"),
+            find_spotify_playlist(api, user_playlists.discover.clone(), "Discover Weekly", |name| name == "Discover Weekly"),
+            find_spotify_playlist(api, user_playlists.daylist.clone(), "daylist", |name| name.starts_with("daylist")),
+            async {
+                if user_id.is_empty() {
+                    api.current_user_profile().await.map(|user| user.id)
+                } else {
+                    Ok(user_id)
+                }
+            },
+        );
+        user_playlists.release = release?;
+        user_playlists.discover = discover?;
+        user_playlists.daylist = daylist?;
+        let user_id = user_id?;
+
+        let cache = dirs::cache_dir().unwrap().join(dir);
+        std::fs::write(cache.join(USER_PLAYLISTS_FILENAME), user_playlists.to_cache_string())?;
+        std::fs::write(cache.join(USER_FILENAME), user_id.clone())?;
+
+        let mut library = library.lock().unwrap();
+        library.user_playlists = user_playlists;
+        library.user_id = user_id;
+        Ok(())
+    }
+}
+
+impl Component for LibraryState {
+    fn key(&mut self, key: KeyEvent, ctx: &Context) -> Handled {
+        let handled = view_key(Some(self.table()), key);
+        if handled.is_ignored() && self.selection.is_results() {
+            let marked = mark_key(Some(self.table()), key, self.result_state.selected());
+            if !marked.is_ignored() {
+                return marked;
+            }
+        }
+        if handled.is_ignored() && key == key!('n') && self.selected_tab == LibraryTab::Playlists {
+            ctx.tx.send(Event::OpenPlaylistForm(PlaylistForm::Create)).log_error();
+            return Handled::Consumed;
+        }
+        if handled.is_consumed() && self.selection.is_results() {
+            self.result_state.select(Some(0));
+        }
+        handled
+    }
+
+    fn text_input(&self) -> bool {
+        self.table().view().editing
+    }
+
+    fn event(&mut self, event: &Event, ctx: &Context) -> Result<Handled> {
+        Ok(match event {
+            Event::Down => { self.down(); Handled::Consumed },
+            Event::Up => { self.up(); Handled::Consumed },
+            Event::Motion(motion) => { self.jump(*motion); Handled::Consumed },
+            Event::Right => { self.right(); Handled::Consumed },
+            Event::Left => { self.left(); Handled::Consumed },
+            Event::Tab => { self.tab(); Handled::Consumed },
+            Event::Backtab => { self.backtab(); Handled::Consumed },
+            Event::Select => open_actions(self.marked_actions().or_else(|| self.select()), ctx),
+            _ => Handled::Ignored,
+        })
+    }
+
+    fn mouse(&mut self, column: u16, row: u16) -> Click {
+        self.click(column, row)
+    }
+
+    fn focus(&mut self) {
+        // Fetches are cancelled when navigating away, so the tab may not have been fetched
+        self.load();
+    }
+
+    fn refresh(&mut self, _ctx: &Context) -> bool {
+        self.refresh();
+        true
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Widget::render(self, area, buf);
+    }
 }
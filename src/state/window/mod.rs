@@ -1,35 +1,87 @@
-use color_eyre::Result;
+use color_eyre::{Report, Result};
 
 pub mod library;
 pub mod queue;
 pub mod landing;
+pub mod top;
+pub mod history;
+pub mod browse;
 
 use std::future::Future;
 use std::pin::Pin;
 use std::fmt::Debug;
 use serde::Deserialize;
 
-use tupy::{api::{flow::Pkce, response::{Paginated, Paged}}, Pagination};
+use tupy::{api::{flow::Pkce, response::{Paginated, Paged}, Uri}, Pagination};
 use tokio::sync::Mutex;
 
-use super::Loading;
-use crate::{Shared, Locked, PAGE_SIZE, errors::LogErrorDefault};
+use super::{component::Component, connection, mutation, view::{Filterable, Rows, SortColumn, TableView, Columns}, Loading, Window};
+use crate::{Shared, Locked, PAGE_SIZE, errors::LogError, tasks::spawn_window};
+
+/// Log the error of a page request, show it and pass it on to the connection state
+fn page_failed(what: &str, error: Report) {
+    if let Some(error) = error.downcast_ref::<tupy::Error>() {
+        connection::report_later(error);
+    }
+    mutation::notify(format!("Couldn't {what}: {error}"));
+    Err::<(), _>(error).log_error();
+}
+
+/// Give up loading every page when one can't be fetched. The page that was shown is kept, so
+/// the filter and sort that needed every page are cleared.
+fn load_all_failed(view: &Locked<TableView>, error: Report) {
+    *view.lock().unwrap() = TableView::default();
+    page_failed("load every page", error);
+}
+
+/// Fetch a page with the pager. If it fails the pager is put back on the page of the items
+/// shown, which are kept, and the task returns.
+macro_rules! fetch_page {
+    ($pager: ident . $fetch: ident ()) => {{
+        let shown = $pager.clone();
+        match $pager.$fetch().await {
+            Ok(page) => page,
+            Err(error) => {
+                *$pager = shown;
+                page_failed("load the page", error.into());
+                return;
+            }
+        }
+    }};
+}
 
 #[derive(Debug, Clone)]
 pub struct WindowState {
     pub library: Shared<Locked<library::LibraryState>>,
     pub queue: Shared<Locked<queue::QueueState>>,
-    pub landing: Shared<Locked<landing::Landing>>
+    pub landing: Shared<Locked<landing::Landing>>,
+    pub top: Shared<Locked<top::TopState>>,
+    pub history: Shared<Locked<history::HistoryState>>,
+    pub browse: Shared<Locked<browse::BrowseState>>,
 }
 
 impl WindowState {
-    pub async fn new(dir: &str, api: &Pkce) -> Result<Self> {
+    pub fn new(dir: &str, api: &Pkce) -> Result<Self> {
         Ok(Self {
-            library: Shared::new(Locked::new(library::LibraryState::new(dir, api).await?)),
+            library: Shared::new(Locked::new(library::LibraryState::new(dir, api)?)),
             queue: Shared::default(),
-            landing: Shared::default()
+            landing: Shared::default(),
+            top: Shared::new(Locked::new(top::TopState::new(api)?)),
+            history: Shared::new(Locked::new(history::HistoryState::new(api)?)),
+            browse: Shared::new(Locked::new(browse::BrowseState::new(api)?)),
         })
     }
+
+    pub fn component(&self, window: Window) -> Shared<Locked<dyn Component>> {
+        match window {
+            Window::Queue => self.queue.clone(),
+            Window::Library => self.library.clone(),
+            Window::Landing => self.landing.clone(),
+            Window::Top => self.top.clone(),
+            Window::History => self.history.clone(),
+            Window::Browse => self.browse.clone(),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -42,6 +94,17 @@ pub struct Page {
 }
 
 impl Page {
+    /// Every item on a single page, used once all the pages are loaded
+    pub fn all(total: usize) -> Self {
+        Self {
+            offset: 0,
+            total,
+            limit: total,
+            page: 1,
+            max_page: 1,
+        }
+    }
+
     pub fn from_paged<P: Paged>(paged: &P) -> Self {
         Self {
             offset: paged.offset(),
@@ -53,6 +116,37 @@ impl Page {
     }
 }
 
+/// Marks items as loading until a fetch finishes. If the fetch is cancelled before it finishes,
+/// the items are put back to what they were so the page isn't stuck loading.
+struct LoadingGuard<T> {
+    items: Shared<Locked<Option<Loading<T>>>>,
+    previous: Option<Loading<T>>,
+}
+
+impl<T> LoadingGuard<T> {
+    fn new(items: &Shared<Locked<Option<Loading<T>>>>) -> Self {
+        let previous = items
+            .lock()
+            .unwrap()
+            .replace(Loading::Loading)
+            .filter(|previous| !matches!(previous, Loading::Loading));
+        Self {
+            items: items.clone(),
+            previous,
+        }
+    }
+}
+
+impl<T> Drop for LoadingGuard<T> {
+    fn drop(&mut self) {
+        if let Ok(mut items) = self.items.lock() {
+            if let Some(Loading::Loading) = items.as_ref() {
+                *items = self.previous.take();
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Pages<R, P>
     where 
@@ -62,6 +156,10 @@ pub struct Pages<R, P>
     pub pager: Shared<Mutex<Paginated<R, P, Pkce, PAGE_SIZE>>>,
     pub items: Shared<Locked<Option<Loading<R>>>>,
     pub page: Shared<Locked<Page>>,
+
+    /// Every page in its original order once they are loaded to filter or sort the items
+    pub all: Shared<Locked<Option<R>>>,
+    pub view: Shared<Locked<TableView>>,
 }
 
 impl<R, P> Pages<R, P>
@@ -74,6 +172,26 @@ impl<R, P> Pages<R, P>
             pager: Shared::new(Mutex::new(pager)),
             items: Shared::default(),
             page: Shared::default(),
+            all: Shared::default(),
+            view: Shared::default(),
+        }
+    }
+
+    /// Fetch the first page in the background if nothing has been fetched yet
+    pub fn load(&self) {
+        if self.items.lock().unwrap().is_none() {
+            let p = self.clone();
+            spawn_window(async move {
+                p.next().await.log_error();
+            });
+        }
+    }
+
+    /// Start from the page containing the item offset instead of the first page. Only has an
+    /// effect before anything is fetched.
+    pub fn start_at(&self, offset: usize) {
+        if let Ok(mut pager) = self.pager.try_lock() {
+            pager.start_at(offset);
         }
     }
 
@@ -81,19 +199,63 @@ impl<R, P> Pages<R, P>
         self.pager.lock().await.has_next()
     }
 
+    /// Fetch the next page in the background if there is one
+    pub fn next_page(&self) {
+        if self.all.lock().unwrap().is_some() {
+            return;
+        }
+        let p = self.clone();
+        spawn_window(async move {
+            if p.has_next().await {
+                p.next().await.log_error();
+            }
+        });
+    }
+
+    /// Fetch the previous page in the background if there is one
+    pub fn prev_page(&self) {
+        if self.all.lock().unwrap().is_some() {
+            return;
+        }
+        let p = self.clone();
+        spawn_window(async move {
+            if p.has_prev().await {
+                p.prev().await.log_error();
+            }
+        });
+    }
+
+    /// Fetch the current page again in the background. If every page was loaded to filter or
+    /// sort the items, the filter and sort are cleared and the first page is fetched instead.
+    pub fn refresh_page(&self) {
+        let p = self.clone();
+        if self.all.lock().unwrap().take().is_some() {
+            *self.view.lock().unwrap() = TableView::default();
+            spawn_window(async move {
+                p.pager.lock().await.restart();
+                p.next().await.log_error();
+            });
+            return;
+        }
+        spawn_window(async move {
+            p.refresh().await.log_error();
+        });
+    }
+
     pub async fn has_prev(&self) -> bool {
         self.pager.lock().await.has_prev()
     }
 
     pub async fn next(&self) -> Result<()> {
-        *self.items.lock().unwrap() = Some(Loading::Loading);
+        let guard = LoadingGuard::new(&self.items);
 
         let items = self.items.clone();
         let pager = self.pager.clone();
         let page = self.page.clone();
-        tokio::spawn(async move {
+        spawn_window(async move {
+            let _guard = guard;
             let mut pager = pager.lock().await;
-            let next = pager.next().await.log_error_or_default();
+            let next = fetch_page!(pager.next());
             if let Some(n) = next.as_ref() {
                 *page.lock().unwrap() = Page::from_paged(n);
             }
@@ -103,31 +265,33 @@ impl<R, P> Pages<R, P>
     }
 
     pub async fn refresh(&self) -> Result<()> {
-        *self.items.lock().unwrap() = Some(Loading::Loading);
+        let guard = LoadingGuard::new(&self.items);
 
         let items = self.items.clone();
         let pager = self.pager.clone();
         let page = self.page.clone();
-        tokio::spawn(async move {
+        spawn_window(async move {
+            let _guard = guard;
             let mut pager = pager.lock().await;
-            let current = pager.current().await.log_error_or_default();
+            let current = fetch_page!(pager.current());
             if let Some(c) = current.as_ref() {
                 *page.lock().unwrap() = Page::from_paged(c);
             }
-            *items.lock().unwrap() = Some(Loading::from(pager.current().await.log_error_or_default()));
+            *items.lock().unwrap() = Some(Loading::from(current));
         });
         Ok(())
     }
 
     pub async fn prev(&self) -> Result<()> {
-        *self.items.lock().unwrap() = Some(Loading::Loading);
+        let guard = LoadingGuard::new(&self.items);
 
         let items = self.items.clone();
         let pager = self.pager.clone();
         let page = self.page.clone();
-        tokio::spawn(async move {
+        spawn_window(async move {
+            let _guard = guard;
             let mut pager = pager.lock().await;
-            let prev = pager.prev().await.log_error_or_default();
+            let prev = fetch_page!(pager.prev());
             if let Some(p) = prev.as_ref() {
                 *page.lock().unwrap() = Page::from_paged(p);
             }
@@ -137,6 +301,107 @@ impl<R, P> Pages<R, P>
     }
 }
 
+impl<R, P> Pages<R, P>
+    where 
+        R: Clone + Debug + Send + Paged + Rows + 'static,
+        P: Clone + Debug + Send + Deserialize<'static> + 'static,
+{
+    /// Offset in the context of the item shown at the index. While the items are filtered or
+    /// sorted it is the position of the item across every page.
+    pub fn context_offset(&self, item: &R::Row, index: usize) -> usize {
+        match self.all.lock().unwrap().as_ref() {
+            Some(all) => all.rows().iter().position(|i| i == item).unwrap_or(index),
+            None => self.page.lock().unwrap().offset + index,
+        }
+    }
+
+    /// Show every loaded item that matches the filter in the sorted order
+    fn apply_view(&self) {
+        let view = self.view.lock().unwrap().clone();
+        // The lock on every page is released before locking the items so it is never held
+        // while waiting on the items
+        let items = self.all.lock().unwrap().as_ref().map(|all| {
+            let mut items = all.clone();
+            *items.rows_mut() = view.apply(all.rows());
+            items
+        });
+        if let Some(items) = items {
+            *self.items.lock().unwrap() = Some(Loading::Some(items));
+        }
+    }
+
+    /// Fetch every page in the background, then filter and sort them
+    fn load_all(&self) {
+        if let Some(Loading::Loading) = self.items.lock().unwrap().as_ref() {
+            return;
+        }
+        let guard = LoadingGuard::new(&self.items);
+
+        let p = self.clone();
+        spawn_window(async move {
+            let _guard = guard;
+            let mut pager = p.pager.lock().await;
+            // Put back if a page fails so paging carries on from the page shown
+            let shown = pager.clone();
+            pager.restart();
+
+            let mut all: Option<R> = None;
+            loop {
+                let mut page = match pager.next().await {
+                    Ok(Some(page)) => page,
+                    Ok(None) => break,
+                    Err(error) => {
+                        *pager = shown;
+                        load_all_failed(&p.view, error.into());
+                        return;
+                    }
+                };
+                match all.as_mut() {
+                    Some(all) => all.rows_mut().append(page.rows_mut()),
+                    None => all = Some(page),
+                }
+            }
+
+            if let Some(all) = all {
+                *p.page.lock().unwrap() = Page::all(all.rows().len());
+                *p.all.lock().unwrap() = Some(all);
+                p.apply_view();
+            }
+        });
+    }
+}
+
+impl<R, P> Filterable for Pages<R, P>
+    where 
+        R: Clone + Debug + Send + Paged + Rows + 'static,
+        P: Clone + Debug + Send + Deserialize<'static> + 'static,
+{
+    fn view(&self) -> TableView {
+        self.view.lock().unwrap().clone()
+    }
+
+    fn set_view(&self, view: TableView) {
+        let active = view.is_active();
+        *self.view.lock().unwrap() = view;
+        if self.all.lock().unwrap().is_some() {
+            self.apply_view();
+        } else if active {
+            self.load_all();
+        }
+    }
+
+    fn sort_columns(&self) -> &'static [SortColumn] {
+        <R::Row as Columns>::SORT
+    }
+
+    fn uris(&self) -> Vec<Uri> {
+        match self.items.lock().unwrap().as_ref() {
+            Some(Loading::Some(items)) => items.rows().iter().map(Columns::uri).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct MappedPages<M, R, P>
     where 
@@ -149,6 +414,10 @@ pub struct MappedPages<M, R, P>
 
     pub mapper: Shared<dyn Fn(Option<R>, Pkce) -> Pin<Box<dyn Future<Output = Result<Option<M>>> + Send>> + Send + Sync>,
     pub items: Shared<Locked<Option<Loading<M>>>>,
+
+    /// Every page in its original order once they are loaded to filter or sort the items
+    pub all: Shared<Locked<Option<M>>>,
+    pub view: Shared<Locked<TableView>>,
 }
 
 impl<M, R, P> MappedPages<M, R, P>
@@ -166,6 +435,26 @@ impl<M, R, P> MappedPages<M, R, P>
             page: Shared::default(),
             mapper: Shared::new(mapper),
             items: Shared::default(),
+            all: Shared::default(),
+            view: Shared::default(),
+        }
+    }
+
+    /// Fetch the first page in the background if nothing has been fetched yet
+    pub fn load(&self) {
+        if self.items.lock().unwrap().is_none() {
+            let p = self.clone();
+            spawn_window(async move {
+                p.next().await.log_error();
+            });
+        }
+    }
+
+    /// Start from the page containing the item offset instead of the first page. Only has an
+    /// effect before anything is fetched.
+    pub fn start_at(&self, offset: usize) {
+        if let Ok(mut pager) = self.pager.try_lock() {
+            pager.start_at(offset);
         }
     }
 
@@ -173,61 +462,243 @@ impl<M, R, P> MappedPages<M, R, P>
         self.pager.lock().await.has_next()
     }
 
+    /// Fetch the next page in the background if there is one
+    pub fn next_page(&self) {
+        if self.all.lock().unwrap().is_some() {
+            return;
+        }
+        let p = self.clone();
+        spawn_window(async move {
+            if p.has_next().await {
+                p.next().await.log_error();
+            }
+        });
+    }
+
+    /// Fetch the previous page in the background if there is one
+    pub fn prev_page(&self) {
+        if self.all.lock().unwrap().is_some() {
+            return;
+        }
+        let p = self.clone();
+        spawn_window(async move {
+            if p.has_prev().await {
+                p.prev().await.log_error();
+            }
+        });
+    }
+
+    /// Fetch the current page again in the background. If every page was loaded to filter or
+    /// sort the items, the filter and sort are cleared and the first page is fetched instead.
+    pub fn refresh_page(&self) {
+        let p = self.clone();
+        if self.all.lock().unwrap().take().is_some() {
+            *self.view.lock().unwrap() = TableView::default();
+            spawn_window(async move {
+                p.pager.lock().await.restart();
+                p.next().await.log_error();
+            });
+            return;
+        }
+        spawn_window(async move {
+            p.refresh().await.log_error();
+        });
+    }
+
     pub async fn has_prev(&self) -> bool {
         self.pager.lock().await.has_prev()
     }
 
     pub async fn next(&self) -> Result<()> {
-        *self.items.lock().unwrap() = Some(Loading::Loading);
+        let guard = LoadingGuard::new(&self.items);
 
         let items = self.items.clone();
         let pager = self.pager.clone();
         let page = self.page.clone();
         let mapper = self.mapper.clone();
-        tokio::spawn(async move {
+        spawn_window(async move {
+            let _guard = guard;
             let mut pager = pager.lock().await;
-            let next = pager.next().await.unwrap();
-            if let Some(n) = next.as_ref() {
-                *page.lock().unwrap() = Page::from_paged(n);
+            let next = fetch_page!(pager.next());
+            let shown = next.as_ref().map(Page::from_paged);
+            let mapped = match mapper(next, pager.flow().clone()).await {
+                Ok(mapped) => mapped,
+                Err(error) => {
+                    page_failed("load the page", error);
+                    return;
+                }
+            };
+            if let Some(shown) = shown {
+                *page.lock().unwrap() = shown;
             }
-            *items.lock().unwrap() = Some(Loading::from(mapper(next, pager.flow().clone()).await.log_error_or_default()));
+            *items.lock().unwrap() = Some(Loading::from(mapped));
         });
         Ok(())
     }
 
     pub async fn refresh(&self) -> Result<()> {
-        *self.items.lock().unwrap() = Some(Loading::Loading);
+        let guard = LoadingGuard::new(&self.items);
 
         let items = self.items.clone();
         let pager = self.pager.clone();
         let page = self.page.clone();
         let mapper = self.mapper.clone();
-        tokio::spawn(async move {
+        spawn_window(async move {
+            let _guard = guard;
             let mut pager = pager.lock().await;
-            let current = pager.current().await.unwrap();
-            if let Some(c) = current.as_ref() {
-                *page.lock().unwrap() = Page::from_paged(c);
+            let current = fetch_page!(pager.current());
+            let shown = current.as_ref().map(Page::from_paged);
+            let mapped = match mapper(current, pager.flow().clone()).await {
+                Ok(mapped) => mapped,
+                Err(error) => {
+                    page_failed("load the page", error);
+                    return;
+                }
+            };
+            if let Some(shown) = shown {
+                *page.lock().unwrap() = shown;
             }
-            *items.lock().unwrap() = Some(Loading::from(mapper(current, pager.flow().clone()).await.log_error_or_default()));
+            *items.lock().unwrap() = Some(Loading::from(mapped));
         });
         Ok(())
     }
 
     pub async fn prev(&self) -> Result<()> {
-        *self.items.lock().unwrap() = Some(Loading::Loading);
+        let guard = LoadingGuard::new(&self.items);
 
         let items = self.items.clone();
         let pager = self.pager.clone();
         let page = self.page.clone();
         let mapper = self.mapper.clone();
-        tokio::spawn(async move {
+        spawn_window(async move {
+            let _guard = guard;
             let mut pager = pager.lock().await;
-            let prev = pager.prev().await.unwrap();
-            if let Some(p) = prev.as_ref() {
-                *page.lock().unwrap() = Page::from_paged(p);
+            let prev = fetch_page!(pager.prev());
+            let shown = prev.as_ref().map(Page::from_paged);
+            let mapped = match mapper(prev, pager.flow().clone()).await {
+                Ok(mapped) => mapped,
+                Err(error) => {
+                    page_failed("load the page", error);
+                    return;
+                }
+            };
+            if let Some(shown) = shown {
+                *page.lock().unwrap() = shown;
             }
-            *items.lock().unwrap() = Some(Loading::from(mapper(prev, pager.flow().clone()).await.log_error_or_default()));
+            *items.lock().unwrap() = Some(Loading::from(mapped));
         });
         Ok(())
     }
 }
+
+impl<M, R, P> MappedPages<M, R, P>
+    where 
+        M: Clone + Send + Rows + 'static,
+        R: Clone + Send + Paged + 'static,
+        P: Clone + Send + Deserialize<'static> + 'static,
+{
+    /// Offset in the context of the item shown at the index. While the items are filtered or
+    /// sorted it is the position of the item across every page.
+    pub fn context_offset(&self, item: &M::Row, index: usize) -> usize {
+        match self.all.lock().unwrap().as_ref() {
+            Some(all) => all.rows().iter().position(|i| i == item).unwrap_or(index),
+            None => self.page.lock().unwrap().offset + index,
+        }
+    }
+
+    /// Show every loaded item that matches the filter in the sorted order
+    fn apply_view(&self) {
+        let view = self.view.lock().unwrap().clone();
+        // The lock on every page is released before locking the items so it is never held
+        // while waiting on the items
+        let items = self.all.lock().unwrap().as_ref().map(|all| {
+            let mut items = all.clone();
+            *items.rows_mut() = view.apply(all.rows());
+            items
+        });
+        if let Some(items) = items {
+            *self.items.lock().unwrap() = Some(Loading::Some(items));
+        }
+    }
+
+    /// Fetch and map every page in the background, then filter and sort them
+    fn load_all(&self) {
+        if let Some(Loading::Loading) = self.items.lock().unwrap().as_ref() {
+            return;
+        }
+        let guard = LoadingGuard::new(&self.items);
+
+        let p = self.clone();
+        spawn_window(async move {
+            let _guard = guard;
+            let mut pager = p.pager.lock().await;
+            // Put back if a page fails so paging carries on from the page shown
+            let shown = pager.clone();
+            pager.restart();
+
+            let mut all: Option<M> = None;
+            loop {
+                let page = match pager.next().await {
+                    Ok(Some(page)) => page,
+                    Ok(None) => break,
+                    Err(error) => {
+                        *pager = shown;
+                        load_all_failed(&p.view, error.into());
+                        return;
+                    }
+                };
+                let mut page = match (p.mapper)(Some(page), pager.flow().clone()).await {
+                    Ok(Some(page)) => page,
+                    Ok(None) => break,
+                    Err(error) => {
+                        *pager = shown;
+                        load_all_failed(&p.view, error);
+                        return;
+                    }
+                };
+                match all.as_mut() {
+                    Some(all) => all.rows_mut().append(page.rows_mut()),
+                    None => all = Some(page),
+                }
+            }
+
+            if let Some(all) = all {
+                *p.page.lock().unwrap() = Page::all(all.rows().len());
+                *p.all.lock().unwrap() = Some(all);
+                p.apply_view();
+            }
+        });
+    }
+}
+
+impl<M, R, P> Filterable for MappedPages<M, R, P>
+    where 
+        M: Clone + Send + Rows + 'static,
+        R: Clone + Send + Paged + 'static,
+        P: Clone + Send + Deserialize<'static> + 'static,
+{
+    fn view(&self) -> TableView {
+        self.view.lock().unwrap().clone()
+    }
+
+    fn set_view(&self, view: TableView) {
+        let active = view.is_active();
+        *self.view.lock().unwrap() = view;
+        if self.all.lock().unwrap().is_some() {
+            self.apply_view();
+        } else if active {
+            self.load_all();
+        }
+    }
+
+    fn sort_columns(&self) -> &'static [SortColumn] {
+        <M::Row as Columns>::SORT
+    }
+
+    fn uris(&self) -> Vec<Uri> {
+        match self.items.lock().unwrap().as_ref() {
+            Some(Loading::Some(items)) => items.rows().iter().map(Columns::uri).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
@@ -1,53 +1,96 @@
 use crossterm::event::KeyEvent;
-use ratatui::widgets::TableState;
-use tupy::api::response::{self, Item};
+use ratatui::{buffer::Buffer, layout::Rect, style::Style, widgets::{StatefulWidget, TableState}};
+use color_eyre::Result;
+use tupy::api::{response::{self, Item}, Uri};
 
-use crate::{key, state::{IterCollection, Loading, actions::{Action, action_label}, wrappers::{Saved, GetUri}}};
+use crate::{app::Event, errors::LogError, key, state::{local_queue, mutation, component::{open_actions, Component, Context, Handled}, Click, IterCollection, Loading, Motion, TableArea, actions::{batch_actions, Action, action_label}, wrappers::{Saved, GetUri}, view::{mark_key, take_marked, view_key, Columns, Filterable, SortColumn, TableView}}, Locked, Shared};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Queue {
+    /// Items shown, filtered and sorted by the view
     pub items: Vec<Saved<Item>>,
+    /// Every item in the order it will be played
+    pub all: Vec<Saved<Item>>,
 }
 
-impl From<(response::Queue, Vec<bool>, Vec<bool>)> for Queue {
-    fn from(q: (response::Queue, Vec<bool>, Vec<bool>)) -> Self {
-        let mut saved_tracks = q.1.into_iter();
-        let mut saved_episodes = q.2.into_iter();
+impl Queue {
+    /// Queue with the items filtered and sorted by the view
+    pub fn new(q: response::Queue, view: &TableView) -> Self {
+        Self::from_items(q.queue, view)
+    }
+
+    /// Queue of items kept by rataify, with the items filtered and sorted by the view
+    pub fn from_items(items: Vec<Item>, view: &TableView) -> Self {
+        let all = items.into_iter().map(Saved::new).collect::<Vec<_>>();
         Self {
-            items: q.0.queue.into_iter().map(|i| match &i {
-                Item::Track(_) => Saved::new(saved_tracks.next().unwrap_or_default(), i),
-                Item::Episode(_) => Saved::new(saved_episodes.next().unwrap_or_default(), i),
-            }).collect(),
+            items: view.apply(&all),
+            all,
+        }
+    }
+
+    /// Remove the item that plays next, returning it and where it was shown
+    pub fn pop_next(&mut self) -> Option<(Saved<Item>, Option<usize>)> {
+        if self.all.is_empty() {
+            return None;
+        }
+        let item = self.all.remove(0);
+        let shown = self.items.iter().position(|i| *i == item);
+        if let Some(index) = shown {
+            self.items.remove(index);
         }
+        Some((item, shown))
+    }
+
+    /// Put back an item removed with `pop_next`
+    pub fn push_next(&mut self, item: Saved<Item>, shown: Option<usize>) {
+        if let Some(index) = shown {
+            self.items.insert(index.min(self.items.len()), item.clone());
+        }
+        self.all.insert(0, item);
     }
 }
 
-#[derive(Debug, Default, Clone, PartialEq)]
+#[derive(Debug, Default, Clone)]
 pub struct QueueState {
     pub state: TableState,
-    pub queue: Loading<Queue>,
+    pub queue: Shared<Locked<Loading<Queue>>>,
+    pub view: Shared<Locked<TableView>>,
+    pub area: TableArea,
 }
 
 impl QueueState {
     pub fn next(&mut self) {
-        if let Loading::Some(ref mut q) = self.queue {
+        if let Loading::Some(ref q) = *self.queue.lock().unwrap() {
             self.state.next_in_list(q.items.len());
         }
     }
     
     pub fn prev(&mut self) {
-        if let Loading::Some(ref mut q) = self.queue {
+        if let Loading::Some(ref q) = *self.queue.lock().unwrap() {
             self.state.prev_in_list(q.items.len());
         }
     }
 
+    pub fn jump(&mut self, motion: Motion) {
+        if let Loading::Some(ref q) = *self.queue.lock().unwrap() {
+            self.state.jump_in_list(motion, q.items.len(), self.area.area.height as usize);
+        }
+    }
+
+    pub fn click(&mut self, column: u16, row: u16) -> Click {
+        match *self.queue.lock().unwrap() {
+            Loading::Some(ref q) => self.area.click(&mut self.state, column, row, q.items.len()),
+            _ => Click::Miss,
+        }
+    }
+
     pub fn select(&self) -> Option<Vec<(KeyEvent, Action, &'static str)>> {
-        if let Loading::Some(ref q) = self.queue {
+        if let Loading::Some(ref q) = *self.queue.lock().unwrap() {
             q.items.get(self.state.selected().unwrap_or(0)).map(|i| {
                 let mut actions = vec![
                     (key!(Enter), Action::Play(i.as_ref().get_uri()), action_label::PLAY)
                 ];
-                actions.extend(i.into_actions(true, |saved| Ok(())));
+                actions.extend(i.into_actions(true));
                 actions
             })
         } else {
@@ -56,3 +99,118 @@ impl QueueState {
     }
 }
 
+impl Filterable for QueueState {
+    fn view(&self) -> TableView {
+        self.view.lock().unwrap().clone()
+    }
+
+    /// The whole queue is always fetched, so the view is applied right away
+    fn set_view(&self, view: TableView) {
+        if let Loading::Some(ref mut q) = *self.queue.lock().unwrap() {
+            q.items = view.apply(&q.all);
+        }
+        *self.view.lock().unwrap() = view;
+    }
+
+    fn sort_columns(&self) -> &'static [SortColumn] {
+        <Item as Columns>::SORT
+    }
+
+    fn uris(&self) -> Vec<Uri> {
+        match *self.queue.lock().unwrap() {
+            Loading::Some(ref q) => q.items.iter().map(Columns::uri).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl QueueState {
+    /// Handle the keys that edit the local queue
+    fn edit_key(&mut self, key: KeyEvent, ctx: &Context) -> Handled {
+        let edit = key == key!('K' + SHIFT)
+            || key == key!('J' + SHIFT)
+            || key == key!('D' + SHIFT)
+            || key == key!('C' + SHIFT);
+        if !edit || !local_queue::is_active() {
+            return Handled::Ignored;
+        }
+        // Positions only line up with the queue while it is shown in play order
+        if self.view.lock().unwrap().is_shown() {
+            mutation::notify("Clear the filter and sort to edit the queue".into());
+            return Handled::Consumed;
+        }
+
+        let len = match *self.queue.lock().unwrap() {
+            Loading::Some(ref q) => q.all.len(),
+            _ => return Handled::Consumed,
+        };
+        let selected = self.state.selected().unwrap_or(0);
+        if key == key!('K' + SHIFT) && selected > 0 && selected < len {
+            local_queue::move_item(selected, selected - 1);
+            self.state.select(Some(selected - 1));
+        } else if key == key!('J' + SHIFT) && selected + 1 < len {
+            local_queue::move_item(selected, selected + 1);
+            self.state.select(Some(selected + 1));
+        } else if key == key!('D' + SHIFT) {
+            let uris = take_marked(Some(&*self), self.state.selected())
+                .unwrap_or_else(|| self.uris().into_iter().skip(selected).take(1).collect());
+            local_queue::remove(&uris);
+            self.view.lock().unwrap().marks.clear();
+        } else if key == key!('C' + SHIFT) {
+            local_queue::clear();
+            let mut view = self.view.lock().unwrap();
+            view.marks.clear();
+            view.visual = None;
+            self.state.select(Some(0));
+        }
+        ctx.tx.send(Event::UpdateQueue).log_error();
+        Handled::Consumed
+    }
+}
+
+impl Component for QueueState {
+    fn key(&mut self, key: KeyEvent, ctx: &Context) -> Handled {
+        let handled = view_key(Some(self), key);
+        if handled.is_ignored() {
+            let handled = self.edit_key(key, ctx);
+            if !handled.is_ignored() {
+                return handled;
+            }
+            return mark_key(Some(self), key, self.state.selected());
+        }
+        if handled.is_consumed() {
+            self.state.select(Some(0));
+        }
+        handled
+    }
+
+    fn text_input(&self) -> bool {
+        self.view().editing
+    }
+
+    fn event(&mut self, event: &Event, ctx: &Context) -> Result<Handled> {
+        Ok(match event {
+            Event::Down => { self.next(); Handled::Consumed },
+            Event::Up => { self.prev(); Handled::Consumed },
+            Event::Motion(motion) => { self.jump(*motion); Handled::Consumed },
+            Event::Select => open_actions(
+                take_marked(Some(self), self.state.selected()).map(|uris| batch_actions(uris, None)).or_else(|| self.select()),
+                ctx,
+            ),
+            _ => Handled::Ignored,
+        })
+    }
+
+    fn mouse(&mut self, column: u16, row: u16) -> Click {
+        self.click(column, row)
+    }
+
+    fn refresh(&mut self, ctx: &Context) -> bool {
+        ctx.tx.send(Event::UpdateQueue).log_error();
+        true
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        StatefulWidget::render(self, area, buf, &mut Style::default());
+    }
+}
@@ -0,0 +1,359 @@
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+
+use color_eyre::Result;
+use crossterm::event::KeyEvent;
+use ratatui::{buffer::Buffer, layout::Rect, widgets::{TableState, Widget}};
+use strum::EnumCount;
+use tupy::api::{flow::Pkce, request::{Play, TimeRange}, response::{Artist, TopItems, Track}, UserApi};
+
+use super::MappedPages;
+use crate::{app::Event, errors::LogError, key, tasks::spawn_window, state::{library_index, component::{open_actions, Component, Context, Handled}, actions::{action_label, Action}, wrappers::Saved, IterCollection, Loading, Motion}, PAGE_SIZE};
+
+pub type TopTracks = MappedPages<Vec<Saved<Track>>, TopItems<Track>, TopItems<Track>>;
+pub type TopArtists = MappedPages<Vec<Saved<Artist>>, TopItems<Artist>, TopItems<Artist>>;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, strum_macros::EnumIs, strum_macros::EnumIter, strum_macros::FromRepr, strum_macros::EnumCount)]
+pub enum TopTab {
+    #[default]
+    ShortTracks,
+    MediumTracks,
+    LongTracks,
+    ShortArtists,
+    MediumArtists,
+    LongArtists,
+}
+
+impl Add<usize> for TopTab {
+    type Output = Self;
+
+    fn add(self, rhs: usize) -> Self::Output {
+        let index = (self as usize + rhs) % Self::COUNT;
+        Self::from_repr(index).unwrap()
+    }
+}
+
+impl AddAssign<usize> for TopTab {
+    fn add_assign(&mut self, rhs: usize) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub<usize> for TopTab {
+    type Output = Self;
+
+    fn sub(self, rhs: usize) -> Self::Output {
+        let index = (self as isize - rhs as isize) % Self::COUNT as isize;
+        if index < 0 {
+            Self::from_repr((Self::COUNT as isize + index) as usize).unwrap()
+        } else {
+            Self::from_repr(index as usize).unwrap()
+        }
+    }
+}
+
+impl SubAssign<usize> for TopTab {
+    fn sub_assign(&mut self, rhs: usize) {
+        *self = *self - rhs;
+    }
+}
+
+impl TopTab {
+    #[inline]
+    pub const fn title(&self) -> &'static str {
+        match self {
+            Self::ShortTracks => "Tracks (4 Weeks)",
+            Self::MediumTracks => "Tracks (6 Months)",
+            Self::LongTracks => "Tracks (1 Year)",
+            Self::ShortArtists => "Artists (4 Weeks)",
+            Self::MediumArtists => "Artists (6 Months)",
+            Self::LongArtists => "Artists (1 Year)",
+        }
+    }
+
+    #[inline]
+    pub const fn time_range(&self) -> TimeRange {
+        match self {
+            Self::ShortTracks | Self::ShortArtists => TimeRange::Short,
+            Self::MediumTracks | Self::MediumArtists => TimeRange::Medium,
+            Self::LongTracks | Self::LongArtists => TimeRange::Long,
+        }
+    }
+}
+
+/// Borrowed pages for the currently selected top tab
+pub enum TopPages<'a> {
+    Tracks(&'a TopTracks),
+    Artists(&'a TopArtists),
+}
+
+#[derive(Clone)]
+pub struct TopState {
+    pub selected_tab: TopTab,
+    pub state: TableState,
+
+    pub short_tracks: TopTracks,
+    pub medium_tracks: TopTracks,
+    pub long_tracks: TopTracks,
+    pub short_artists: TopArtists,
+    pub medium_artists: TopArtists,
+    pub long_artists: TopArtists,
+}
+
+impl std::fmt::Debug for TopState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TopState")
+            .field("selected_tab", &self.selected_tab)
+            .field("state", &self.state)
+            .finish()
+    }
+}
+
+fn top_tracks(api: &Pkce, range: TimeRange) -> Result<TopTracks> {
+    Ok(MappedPages::new(
+        api.user_top_items::<Track, PAGE_SIZE>(range)?,
+        |data, api| Box::pin(async move {
+            Ok(match data {
+                Some(data) => {
+                    library_index::check(&api, data.items.iter().map(|t| t.uri.clone())).await.log_error();
+                    Some(data.items.into_iter().map(Saved::new).collect())
+                },
+                None => None
+            })
+        })
+    ))
+}
+
+fn top_artists(api: &Pkce, range: TimeRange) -> Result<TopArtists> {
+    Ok(MappedPages::new(
+        api.user_top_items::<Artist, PAGE_SIZE>(range)?,
+        |data, api| Box::pin(async move {
+            Ok(match data {
+                Some(data) => {
+                    library_index::check(&api, data.items.iter().map(|a| a.uri.clone())).await.log_error();
+                    Some(data.items.into_iter().map(Saved::new).collect())
+                },
+                None => None
+            })
+        })
+    ))
+}
+
+impl TopState {
+    pub fn new(api: &Pkce) -> Result<Self> {
+        Ok(Self {
+            selected_tab: TopTab::default(),
+            state: TableState::default(),
+
+            short_tracks: top_tracks(api, TimeRange::Short)?,
+            medium_tracks: top_tracks(api, TimeRange::Medium)?,
+            long_tracks: top_tracks(api, TimeRange::Long)?,
+            short_artists: top_artists(api, TimeRange::Short)?,
+            medium_artists: top_artists(api, TimeRange::Medium)?,
+            long_artists: top_artists(api, TimeRange::Long)?,
+        })
+    }
+
+    pub fn pages(&self) -> TopPages<'_> {
+        match self.selected_tab {
+            TopTab::ShortTracks => TopPages::Tracks(&self.short_tracks),
+            TopTab::MediumTracks => TopPages::Tracks(&self.medium_tracks),
+            TopTab::LongTracks => TopPages::Tracks(&self.long_tracks),
+            TopTab::ShortArtists => TopPages::Artists(&self.short_artists),
+            TopTab::MediumArtists => TopPages::Artists(&self.medium_artists),
+            TopTab::LongArtists => TopPages::Artists(&self.long_artists),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self.pages() {
+            TopPages::Tracks(pages) => match pages.items.lock().unwrap().as_ref() {
+                Some(Loading::Some(items)) => items.len(),
+                _ => 0,
+            },
+            TopPages::Artists(pages) => match pages.items.lock().unwrap().as_ref() {
+                Some(Loading::Some(items)) => items.len(),
+                _ => 0,
+            },
+        }
+    }
+
+    /// Fetch the first page of the selected tab if it hasn't been fetched yet
+    pub fn load(&self) {
+        match self.pages() {
+            TopPages::Tracks(pages) => if pages.items.lock().unwrap().is_none() {
+                let p = pages.clone();
+                spawn_window(async move {
+                    p.next().await.log_error();
+                });
+            },
+            TopPages::Artists(pages) => if pages.items.lock().unwrap().is_none() {
+                let p = pages.clone();
+                spawn_window(async move {
+                    p.next().await.log_error();
+                });
+            },
+        }
+    }
+
+    pub fn tab(&mut self) {
+        self.selected_tab += 1;
+        self.state.select(None);
+        self.load();
+    }
+
+    pub fn backtab(&mut self) {
+        self.selected_tab -= 1;
+        self.state.select(None);
+        self.load();
+    }
+
+    pub fn down(&mut self) {
+        let len = self.len();
+        if len > 0 {
+            self.state.next_in_list(len);
+        }
+    }
+
+    pub fn up(&mut self) {
+        let len = self.len();
+        if len > 0 {
+            self.state.prev_in_list(len);
+        }
+    }
+
+    /// Jump the selection, counting a whole page of items as the rows shown
+    pub fn jump(&mut self, motion: Motion) {
+        let len = self.len();
+        if len > 0 {
+            self.state.jump_in_list(motion, len, PAGE_SIZE);
+        }
+    }
+
+    pub fn right(&mut self) {
+        let page = match self.pages() {
+            TopPages::Tracks(pages) => pages.page.lock().unwrap().clone(),
+            TopPages::Artists(pages) => pages.page.lock().unwrap().clone(),
+        };
+        if page.page >= page.max_page {
+            return;
+        }
+
+        match self.pages() {
+            TopPages::Tracks(pages) => {
+                let p = pages.clone();
+                spawn_window(async move {
+                    p.next().await.log_error();
+                });
+            },
+            TopPages::Artists(pages) => {
+                let p = pages.clone();
+                spawn_window(async move {
+                    p.next().await.log_error();
+                });
+            },
+        }
+        self.state.select(Some(0));
+    }
+
+    pub fn left(&mut self) {
+        let page = match self.pages() {
+            TopPages::Tracks(pages) => pages.page.lock().unwrap().clone(),
+            TopPages::Artists(pages) => pages.page.lock().unwrap().clone(),
+        };
+        if page.page <= 1 {
+            return;
+        }
+
+        match self.pages() {
+            TopPages::Tracks(pages) => {
+                let p = pages.clone();
+                spawn_window(async move {
+                    p.prev().await.log_error();
+                });
+            },
+            TopPages::Artists(pages) => {
+                let p = pages.clone();
+                spawn_window(async move {
+                    p.prev().await.log_error();
+                });
+            },
+        }
+        self.state.select(Some(0));
+    }
+
+    pub fn refresh(&mut self) {
+        match self.pages() {
+            TopPages::Tracks(pages) => {
+                let p = pages.clone();
+                spawn_window(async move {
+                    p.refresh().await.log_error();
+                });
+            },
+            TopPages::Artists(pages) => {
+                let p = pages.clone();
+                spawn_window(async move {
+                    p.refresh().await.log_error();
+                });
+            },
+        }
+        self.state.select(None);
+    }
+
+    pub fn select(&self) -> Option<Vec<(KeyEvent, Action, &'static str)>> {
+        let index = self.state.selected().unwrap_or(0);
+        match self.pages() {
+            TopPages::Tracks(pages) => {
+                if let Some(Loading::Some(items)) = pages.items.lock().unwrap().as_ref() {
+                    return items.get(index).map(|t| {
+                        let mut actions = vec![
+                            (key!(Enter), Action::Play(t.as_ref().uri.clone()), action_label::PLAY)
+                        ];
+                        actions.extend(t.into_actions(true));
+                        actions
+                    });
+                }
+            },
+            TopPages::Artists(pages) => {
+                if let Some(Loading::Some(items)) = pages.items.lock().unwrap().as_ref() {
+                    return items.get(index).map(|a| {
+                        let mut actions = vec![
+                            (key!(Enter), Action::PlayContext(Play::artist(a.as_ref().uri.clone())), action_label::PLAY)
+                        ];
+                        actions.extend(a.into_actions(true));
+                        actions
+                    });
+                }
+            },
+        }
+        None
+    }
+}
+
+impl Component for TopState {
+    fn event(&mut self, event: &Event, ctx: &Context) -> Result<Handled> {
+        Ok(match event {
+            Event::Down => { self.down(); Handled::Consumed },
+            Event::Up => { self.up(); Handled::Consumed },
+            Event::Motion(motion) => { self.jump(*motion); Handled::Consumed },
+            Event::Right => { self.right(); Handled::Consumed },
+            Event::Left => { self.left(); Handled::Consumed },
+            Event::Tab => { self.tab(); Handled::Consumed },
+            Event::Backtab => { self.backtab(); Handled::Consumed },
+            Event::Select => open_actions(self.select(), ctx),
+            _ => Handled::Ignored,
+        })
+    }
+
+    fn focus(&mut self) {
+        self.load();
+    }
+
+    fn refresh(&mut self, _ctx: &Context) -> bool {
+        self.refresh();
+        true
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Widget::render(&*self, area, buf);
+    }
+}
@@ -1,9 +1,9 @@
 use std::fmt::Debug;
 
 use crossterm::event::KeyEvent;
-use tupy::api::{response::{Episode, PlaylistItemInfo, SimplifiedAlbum, SimplifiedEpisode, SimplifiedTrack, Track}, Uri};
+use tupy::api::{response::{Artist, Episode, PlayHistory, PlaylistItemInfo, SimplifiedAlbum, SimplifiedEpisode, SimplifiedTrack, Track}, Uri};
 
-use crate::{key, state::actions::{Action, action_label, IntoActions}};
+use crate::{key, state::{actions::{Action, action_label, IntoActions}, library_index}};
 
 pub trait GetUri {
     fn get_uri(&self) -> Uri;
@@ -14,6 +14,16 @@ impl GetUri for Track {
         self.uri.clone()
     }
 }
+impl GetUri for Artist {
+    fn get_uri(&self) -> Uri {
+        self.uri.clone()
+    }
+}
+impl GetUri for PlayHistory {
+    fn get_uri(&self) -> Uri {
+        self.track.uri.clone()
+    }
+}
 impl GetUri for Episode {
     fn get_uri(&self) -> Uri {
         self.uri.clone()
@@ -40,14 +50,15 @@ impl GetUri for SimplifiedEpisode {
     }
 }
 
+/// Item that can be saved or followed. Whether it is saved is read from the library index so
+/// every view shows the same ♥.
 pub struct Saved<T> {
-    pub saved: bool,
     pub(crate) inner: T
 }
 
 impl<T: PartialEq> PartialEq for Saved<T> {
     fn eq(&self, other: &Self) -> bool {
-        self.saved == other.saved && self.inner.eq(other.as_ref())
+        self.inner.eq(other.as_ref())
     }
 }
 impl<T: PartialEq> PartialEq<T> for Saved<T> {
@@ -59,7 +70,6 @@ impl<T: PartialEq> PartialEq<T> for Saved<T> {
 impl<T: Debug> Debug for Saved<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Saved")
-            .field("saved", &self.saved)
             .field("inner", &self.inner)
             .finish()
     }
@@ -68,22 +78,24 @@ impl<T: Debug> Debug for Saved<T> {
 impl<T: Clone> Clone for Saved<T> {
     fn clone(&self) -> Self {
         Self {
-            saved: self.saved,
             inner: self.inner.clone()
         }
     }
 }
 
+impl<T: GetUri> Saved<T> {
+    pub fn saved(&self) -> bool {
+        library_index::is_saved(&self.inner.get_uri())
+    }
+}
+
 impl<T: IntoActions + GetUri> Saved<T> {
-    pub fn into_actions<F>(&self, context: bool, callback: F) -> Vec<(KeyEvent, Action, &'static str)>
-    where
-        F: Fn(bool) -> color_eyre::Result<()> + Send + Sync + 'static
-    {
+    pub fn into_actions(&self, context: bool) -> Vec<(KeyEvent, Action, &'static str)> {
         let mut actions = vec![
-            if self.saved {
-                (key!('r'), Action::remove(self.inner.get_uri(), callback), action_label::REMOVE)
+            if self.saved() {
+                (key!('r'), Action::remove(self.inner.get_uri()), action_label::REMOVE)
             } else {
-                (key!('f'), Action::save(self.inner.get_uri(), callback), action_label::SAVE)
+                (key!('f'), Action::Save(self.inner.get_uri()), action_label::SAVE)
             }
         ];
         actions.extend(self.inner.into_actions(context));
@@ -104,8 +116,8 @@ impl<T> std::convert::AsMut<T> for Saved<T> {
 }
 
 impl<T> Saved<T> {
-    pub fn new(saved: bool, inner: T) -> Self {
-        Self { saved, inner } 
+    pub fn new(inner: T) -> Self {
+        Self { inner } 
     }
 
     pub fn unwrap(self) -> T {
@@ -0,0 +1,97 @@
+use std::{collections::HashMap, future::Future};
+
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+
+use crate::{render, state::Window, Locked};
+
+/// Group of background tasks that are cancelled together
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scope {
+    /// Lives until the app quits, e.g. playback commands and polling
+    App,
+    /// Fetches for a window, cancelled when navigating away from it
+    Window(Window),
+    /// Adding a context to the queue, which can be stopped part way through
+    Enqueue,
+}
+
+#[derive(Default)]
+struct Tasks {
+    token: CancellationToken,
+    set: JoinSet<()>,
+}
+
+lazy_static::lazy_static! {
+    static ref SCOPES: Locked<HashMap<Scope, Tasks>> = Locked::default();
+    // Window that `spawn_window` scopes tasks to
+    static ref CURRENT: Locked<Window> = Locked::default();
+}
+
+/// Spawn a background task in the scope
+///
+/// Background tasks write their results into the shared state, so the state is marked as dirty
+/// once the task finishes. Cancelled tasks are dropped without marking the state.
+pub fn spawn_in<F>(scope: Scope, future: F)
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let mut scopes = SCOPES.lock().unwrap();
+    let tasks = scopes.entry(scope).or_default();
+    // Reap finished tasks so the set doesn't grow for the lifetime of the app
+    while tasks.set.try_join_next().is_some() {}
+
+    let token = tasks.token.clone();
+    tasks.set.spawn(async move {
+        tokio::select! {
+            _ = token.cancelled() => {},
+            _ = future => render::mark_dirty(),
+        }
+    });
+}
+
+/// Spawn a background task that lives until the app quits
+pub fn spawn<F>(future: F)
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    spawn_in(Scope::App, future)
+}
+
+/// Spawn a background task for the current window, cancelled when navigating away from it
+pub fn spawn_window<F>(future: F)
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let window = *CURRENT.lock().unwrap();
+    spawn_in(Scope::Window(window), future)
+}
+
+/// Cancel every task in the scope. New tasks can still be spawned in it afterwards.
+pub fn cancel(scope: Scope) {
+    let tasks = SCOPES.lock().unwrap().remove(&scope);
+    if let Some(tasks) = tasks {
+        tasks.token.cancel();
+        // Dropping the set aborts anything that hasn't seen the cancellation yet
+    }
+}
+
+/// Scope window tasks to the window, cancelling the tasks of the window being left
+pub fn enter(window: Window) {
+    let previous = std::mem::replace(&mut *CURRENT.lock().unwrap(), window);
+    if previous != window {
+        cancel(Scope::Window(previous));
+    }
+}
+
+/// Cancel every task and wait for them to finish
+pub async fn shutdown() {
+    let scopes = std::mem::take(&mut *SCOPES.lock().unwrap());
+    for (_, mut tasks) in scopes {
+        tasks.token.cancel();
+        while tasks.set.join_next().await.is_some() {}
+    }
+}
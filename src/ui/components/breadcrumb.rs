@@ -0,0 +1,48 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{block::Title, Block, Widget},
+};
+
+use crate::ui::THEME;
+
+/// Most pages shown before older ones are collapsed
+static MAX_CRUMBS: usize = 4;
+
+/// Trail of the pages that were navigated through, rendered over the top border of a window
+#[derive(Debug, Clone, PartialEq)]
+pub struct Breadcrumb {
+    pub crumbs: Vec<String>,
+}
+
+impl Widget for Breadcrumb {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        // Nothing to go back to
+        if self.crumbs.len() < 2 {
+            return;
+        }
+
+        let skipped = self.crumbs.len().saturating_sub(MAX_CRUMBS);
+        let last = self.crumbs.len() - skipped - 1;
+
+        let mut spans = vec![Span::from("[")];
+        if skipped > 0 {
+            spans.push(Span::from("… › ").dim());
+        }
+        for (i, crumb) in self.crumbs.into_iter().skip(skipped).enumerate() {
+            if i == last {
+                spans.push(Span::from(crumb));
+            } else {
+                spans.push(Span::from(crumb).dim());
+                spans.push(Span::from(" › ").style(THEME.border));
+            }
+        }
+        spans.push(Span::from("]"));
+
+        Block::new()
+            .title(Title::from(Line::from(spans)).alignment(Alignment::Center))
+            .render(area, buf);
+    }
+}
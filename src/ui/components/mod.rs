@@ -1,3 +1,5 @@
 mod link;
+mod breadcrumb;
 
 pub use link::OpenInSpotify;
+pub use breadcrumb::Breadcrumb;
@@ -1,17 +1,18 @@
 use lazy_static::lazy_static;
-use ratatui::{layout::{Constraint, Direction, Layout, Rect}, symbols::DOT, text::{Line, Span}, widgets::{Cell, Row, StatefulWidget, Widget}, style::{Color, Style, Stylize}};
-use tupy::{api::response::{Episode, Track}, Duration};
+use ratatui::{buffer::Buffer, layout::{Constraint, Direction, Layout, Rect}, symbols::DOT, text::{Line, Span}, widgets::{Cell, Row, Widget}};
+use tupy::{api::{response::{Episode, Track}, Uri}, Duration};
 
 pub mod modal;
 pub mod window;
 pub mod playback;
 pub mod components;
+pub mod theme;
 
 pub use playback::NoPlayback;
+pub use theme::Theme;
 
-use crate::state::{Modal, State, Viewport, Window};
-
-use self::modal::goto::UiGoto;
+use crate::{errors::LogError, keymap, state::{connection::Connection, enqueue, mutation, view::TableView, State, TableArea, Window}};
+use components::Breadcrumb;
 
 /// helper function to create a centered rect using up certain percentage of the available rect `r`
 pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
@@ -58,6 +59,21 @@ pub fn centered_rect_limited(percent_x: u16, percent_y: u16, w: u16, h: u16, r:
         .split(popup_layout[1])[1] // Return the middle chunk
 }
 
+/// Areas of each title in a `Tabs` widget rendered with a single space of padding on either side
+/// and a single character divider, used to map mouse clicks back to tabs
+pub fn tab_areas<'a>(area: Rect, titles: impl IntoIterator<Item = &'a str>) -> Vec<Rect> {
+    let mut x = area.x;
+    titles
+        .into_iter()
+        .map(|title| {
+            let width = (title.chars().count() as u16 + 2).min(area.right().saturating_sub(x));
+            let tab = Rect::new(x, area.y, width, area.height.min(1));
+            x = (x + width + 1).min(area.right());
+            tab
+        })
+        .collect()
+}
+
 fn format_duration(duration: Duration) -> String {
     if duration >= Duration::hours(1) {
         format!(
@@ -78,9 +94,9 @@ fn format_duration(duration: Duration) -> String {
 /// Icon | Duration | Name | By | Context
 fn format_track<'l>(track: &Track) -> Row<'l> {
     Row::new(vec![
-        Cell::from(Line::from(format_duration(track.duration)).right_aligned().style(COLORS.duration)),
+        Cell::from(Line::from(format_duration(track.duration)).right_aligned().style(THEME.duration)),
         Cell::default(),
-        Cell::from(track.name.clone()).cyan().style(COLORS.track),
+        Cell::from(track.name.clone()).style(THEME.track),
         Cell::from(
             track
                 .artists
@@ -88,24 +104,24 @@ fn format_track<'l>(track: &Track) -> Row<'l> {
                 .map(|a| a.name.clone())
                 .collect::<Vec<String>>()
                 .join(", "),
-        ).style(COLORS.artists),
+        ).style(THEME.artists),
     ])
 }
 
 /// Icon | Duration | Name | By | Context
 fn format_episode<'l>(episode: &Episode) -> Row<'l> {
     let mut cells = vec![
-        Cell::from(Line::from(format_duration(episode.duration)).right_aligned().style(COLORS.duration)),
+        Cell::from(Line::from(format_duration(episode.duration)).right_aligned().style(THEME.duration)),
         if episode.resume_point.fully_played {
-            Cell::from("✓").style(COLORS.finished)
+            Cell::from(THEME.glyphs.finished.as_str()).style(THEME.finished)
         } else {
             Cell::default()
         },
-        Cell::from(episode.name.clone()).style(COLORS.episode)
+        Cell::from(episode.name.clone()).style(THEME.episode)
     ];
 
     if let Some(show) = episode.show.as_ref() {
-        cells.push(Cell::from(show.name.clone()).style(COLORS.context));
+        cells.push(Cell::from(show.name.clone()).style(THEME.context));
     } else {
         cells.push(Cell::default());
     }
@@ -116,10 +132,10 @@ fn format_episode<'l>(episode: &Episode) -> Row<'l> {
 /// Icon | Duration | Name | By | Context
 fn format_track_saved<'l>(track: &Track, saved: bool) -> Row<'l> {
     Row::new(vec![
-        Cell::from(if saved { "♥" } else { "" }).style(COLORS.like),
-        Cell::from(Line::from(format_duration(track.duration)).right_aligned().style(COLORS.duration)),
+        Cell::from(if saved { THEME.glyphs.like.as_str() } else { "" }).style(THEME.like),
+        Cell::from(Line::from(format_duration(track.duration)).right_aligned().style(THEME.duration)),
         Cell::default(),
-        Cell::from(track.name.clone()).style(COLORS.track),
+        Cell::from(track.name.clone()).style(THEME.track),
         Cell::from(
             track
                 .artists
@@ -127,25 +143,25 @@ fn format_track_saved<'l>(track: &Track, saved: bool) -> Row<'l> {
                 .map(|a| a.name.clone())
                 .collect::<Vec<String>>()
                 .join(", "),
-        ).style(COLORS.artists),
+        ).style(THEME.artists),
     ])
 }
 
 /// Icon | Duration | Name | By | Context
 fn format_episode_saved<'l>(episode: &Episode, saved: bool) -> Row<'l> {
     let mut cells = vec![
-        Cell::from(if saved { "♥" } else { "" }).style(COLORS.like),
-        Cell::from(Line::from(format_duration(episode.duration)).right_aligned().style(COLORS.duration)),
+        Cell::from(if saved { THEME.glyphs.like.as_str() } else { "" }).style(THEME.like),
+        Cell::from(Line::from(format_duration(episode.duration)).right_aligned().style(THEME.duration)),
         if episode.resume_point.fully_played {
-            Cell::from("✓").style(COLORS.finished)
+            Cell::from(THEME.glyphs.finished.as_str()).style(THEME.finished)
         } else {
             Cell::default()
         },
-        Cell::from(episode.name.clone()).style(COLORS.episode)
+        Cell::from(episode.name.clone()).style(THEME.episode)
     ];
 
     if let Some(show) = episode.show.as_ref() {
-        cells.push(Cell::from(show.name.clone()).style(COLORS.context));
+        cells.push(Cell::from(show.name.clone()).style(THEME.context));
     } else {
         cells.push(Cell::default());
     }
@@ -153,93 +169,149 @@ fn format_episode_saved<'l>(episode: &Episode, saved: bool) -> Row<'l> {
     Row::new(cells)
 }
 
-impl Widget for State {
-    fn render(mut self, area: ratatui::layout::Rect, buf: &mut ratatui::buffer::Buffer) {
+impl Widget for &State {
+    fn render(self, area: ratatui::layout::Rect, buf: &mut ratatui::buffer::Buffer) {
+        let connection = self.connection.lock().unwrap().clone();
+        let area = match connection {
+            Connection::Online => area,
+            _ => {
+                let [banner, rest] = Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+                OfflineBanner(&connection).render(banner, buf);
+                rest
+            }
+        };
+        let area = match mutation::notice() {
+            Some(notice) => {
+                let [banner, rest] = Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+                Line::from(notice).centered().style(THEME.error).render(banner, buf);
+                rest
+            }
+            None => area,
+        };
+        let area = match enqueue::progress() {
+            Some(progress) => {
+                let [banner, rest] = Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+                Line::from(progress).centered().style(THEME.highlight).render(banner, buf);
+                rest
+            }
+            None => area,
+        };
+
         let layout = Layout::default()
             .direction(Direction::Vertical)
             .constraints([Constraint::Fill(1), Constraint::Length(4)])
             .split(area);
 
-        //let mut dimmed = if let Viewport::Modal(_) = &self.viewport {
-        //    Style::default().dim()
-        //} else {
-        //    Style::default()
-        //};
-        let mut dimmed = Style::default();
-
-        match &mut self.window {
-            Window::Queue => {
-                let qstate = &mut *self.window_state.queue.lock().unwrap();
-                StatefulWidget::render(qstate, layout[0], buf, &mut dimmed);
-            }
-            Window::Library => {
-                Widget::render(&*self.window_state.library.lock().unwrap(), layout[0], buf);
-            },
-            Window::Landing => {
-                Widget::render(&mut *self.window_state.landing.lock().unwrap(), layout[0], buf);
+        // Render the window followed by each modal opened over it
+        for (i, component) in self.focus_stack().into_iter().enumerate() {
+            component.lock().unwrap().render(layout[0], buf);
+
+            // Landings show how they were navigated to, under any modals
+            if i == 0 && self.window == Window::Landing {
+                let crumbs = self
+                    .navigation
+                    .lock()
+                    .unwrap()
+                    .breadcrumb()
+                    .map(String::from)
+                    .chain(std::iter::once(self.label()))
+                    .collect();
+                Breadcrumb { crumbs }.render(layout[0], buf);
             }
         }
 
-        // Viewport State Rendering
-        if let Viewport::Modal(modal) = &mut self.viewport {
-            match modal {
-                Modal::Devices => {
-                    let devices = &mut *self.modal_state.devices.lock().unwrap();
-                    Widget::render(devices, layout[0], buf);
-                }
-                Modal::GoTo => {
-                    let goto = &self.modal_state.go_to.lock().unwrap();
-                    Widget::render(UiGoto(&goto.mappings), layout[0], buf);
-                },
-                Modal::Action => {
-                    let actions = &*self.modal_state.actions.lock().unwrap();
-                    Widget::render(actions, layout[0], buf);
-                }
-                Modal::AddToPlaylist => {
-                    let add_to_playlist = &mut *self.modal_state.add_to_playlist.lock().unwrap();
-                    // TODO:
-                    // - Fetch playlists
-                    // - Render playlists in modal
-                    // - Use loading state
-                    if let Some(add_to_playlist) = add_to_playlist.as_mut() {
-                        Widget::render(add_to_playlist, layout[0], buf);
-                    }
-                }
-                Modal::Artists => {
-                    let artists = &mut *self.modal_state.artists.lock().unwrap();
-                    Widget::render(artists, layout[0], buf);
-                }
-            }
+        // Keys that can come next while a key sequence is being typed
+        if let Some(pending) = keymap::pending() {
+            pending.render(layout[0], buf);
         }
 
+        *self.playback_area.lock().unwrap() = layout[1];
         Widget::render(&*self.playback.lock().unwrap(), layout[1], buf);
     }
 }
 
-pub struct Colors {
-    pub artists: Style,
-    pub track: Style,
-    pub episode: Style,
-    pub duration: Style,
-    pub context: Style,
-    pub like: Style,
-    pub finished: Style,
-    pub chapter_number: Style,
-    pub highlight: Style,
+/// Shown at the top of the screen while spotify can't be reached
+struct OfflineBanner<'a>(&'a Connection);
+
+impl Widget for OfflineBanner<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let message = match self.0 {
+            Connection::Offline(reason) => format!("Offline: {reason}. Press ctrl+r to retry"),
+            _ => "Reconnecting...".to_string(),
+        };
+        Line::from(message)
+            .centered()
+            .style(THEME.error)
+            .render(area, buf);
+    }
 }
 
 lazy_static! {
-    pub static ref COLORS: Colors = Colors {
-        artists: Style::default().gray(),
-        track: Style::default().cyan(),
-        episode: Style::default().light_green(),
-        duration: Style::default().bold(),
-        context: Style::default().white().bold(),
-        like: Style::default().fg(Color::Red),
-        finished: Style::default().green(),
-        chapter_number: Style::default().dim().gray(),
-        highlight: Style::default().fg(Color::Yellow),
-    };
+    pub static ref THEME: Theme = Theme::load().log_error_or(Theme::default());
+}
+
+/// Filter and sort order of a table, shown on the bottom line of the table
+pub struct ViewBar<'a>(pub &'a TableView);
+
+impl Widget for ViewBar<'_> {
+    fn render(self, area: ratatui::layout::Rect, buf: &mut ratatui::buffer::Buffer) {
+        if !(self.0.is_shown() || self.0.has_marks()) || area.height == 0 || area.width < 2 {
+            return;
+        }
+
+        let line = ratatui::layout::Rect::new(area.x + 1, area.bottom() - 1, area.width - 2, 1);
+        if self.0.editing || !self.0.filter.is_empty() {
+            let mut spans = vec![Span::from("/").style(THEME.highlight), Span::from(self.0.filter.as_str())];
+            if self.0.editing {
+                spans.push(Span::from("█").style(THEME.border));
+            }
+            Line::from(spans).render(line, buf);
+        }
+
+        let mut right = Vec::new();
+        if self.0.visual.is_some() {
+            right.push("VISUAL".to_string());
+        }
+        if !self.0.marks.is_empty() {
+            right.push(format!("{} marked", self.0.marks.len()));
+        }
+        if let Some(sort) = self.0.sort {
+            right.push(format!("{} {}", sort.column.label(), if sort.descending { "↓" } else { "↑" }));
+        }
+        if !right.is_empty() {
+            Line::from(right.join("  "))
+                .style(THEME.highlight)
+                .right_aligned()
+                .render(line, buf);
+        }
+    }
+}
+
+/// Highlights the rows of a table that are marked for a batch action. Only the rows visible in
+/// the area the table was rendered in are styled.
+pub struct MarkedRows<'a> {
+    pub view: &'a TableView,
+    /// Items of the rows shown in the table
+    pub uris: &'a [Uri],
+    pub area: &'a TableArea,
+    pub selected: Option<usize>,
+}
+
+impl Widget for MarkedRows<'_> {
+    fn render(self, _area: Rect, buf: &mut Buffer) {
+        if !self.view.has_marks() {
+            return;
+        }
+
+        let marked = self.view.marked(self.uris, self.selected);
+        let area = self.area.area;
+        let visible = self.uris.iter().skip(self.area.offset).take(area.height as usize);
+        for (row, uri) in visible.enumerate() {
+            if marked.contains(uri) {
+                buf.set_style(Rect::new(area.x, area.y + row as u16, area.width, 1), THEME.marked);
+            }
+        }
+    }
 }
 
 pub struct PaginationProgress {
@@ -255,11 +327,11 @@ impl Widget for PaginationProgress {
 
         let mut cells = vec![];
         for _ in 1..(self.current) {
-            cells.push(Span::from(DOT).dim().gray());
+            cells.push(Span::from(DOT).style(THEME.page));
         }
-        cells.push(Span::from(DOT).white().bold());
+        cells.push(Span::from(DOT).style(THEME.page_current));
         for _ in 0..(self.total.saturating_sub(self.current)) {
-            cells.push(Span::from(DOT).dim().gray());
+            cells.push(Span::from(DOT).style(THEME.page));
         }
     
         let vert = Layout::vertical([
@@ -2,17 +2,20 @@ use ratatui::{buffer::Buffer, layout::{Alignment, Constraint, Layout, Rect, Marg
 
 use tupy::api::response::Paged;
 
-use crate::state::{modal::AddToPlaylistState, Loading};
+use crate::state::{modal::AddToPlaylistState, Loading, TableArea};
 use crate::ui::PaginationProgress;
 
-use super::COLORS;
+use super::THEME;
 
 impl Widget for &mut AddToPlaylistState {
     fn render(self, area: Rect, buf: &mut Buffer)
         where
             Self: Sized {
         
-        let title = "[Add To Playlist]";
+        let title = match self.items.len() {
+            1 => "[Add To Playlist]".to_string(),
+            n => format!("[Add {n} Items To Playlist]"),
+        };
         let rows = if let Some(Loading::Some(playlists)) = self.playlists.items.lock().unwrap().as_ref().map(|p| p.as_ref()) {
             playlists.items.iter().map(|i| {
                 [i.name.clone()]
@@ -26,6 +29,12 @@ impl Widget for &mut AddToPlaylistState {
         // Rernder in bottom right corner
         // [{key}] {title}
         let mut count = 0;
+        let block = Block::bordered()
+            //.borders(Borders::TOP | Borders::LEFT)
+            .border_set(border::ROUNDED)
+            .border_style(THEME.border)
+            .padding(Padding::symmetric(1, 1))
+            .title(Title::from(title.as_str()).alignment(Alignment::Center));
         let list = rows.into_iter().map(|parts| {
             count += 1;
             let cells = parts.into_iter().enumerate().map(|(i, part)| {
@@ -39,13 +48,8 @@ impl Widget for &mut AddToPlaylistState {
             Row::new(cells)
         })
             .collect::<Table>()
-            .block(Block::bordered()
-                //.borders(Borders::TOP | Borders::LEFT)
-                .border_set(border::ROUNDED)
-                .padding(Padding::symmetric(1, 1))
-                .title(Title::from(title).alignment(Alignment::Center))
-            )
-            .highlight_style(COLORS.highlight)
+            .block(block.clone())
+            .highlight_style(THEME.highlight)
             .widths(longest_parts.iter().map(|l| Constraint::Length(*l as u16)))
             .column_spacing(2);
 
@@ -66,6 +70,7 @@ impl Widget for &mut AddToPlaylistState {
 
         Clear.render(vert[1], buf);
         StatefulWidget::render(list, vert[1], buf, &mut self.state);
+        self.area = TableArea::new(block.inner(vert[1]), &self.state);
 
         if let Some(Loading::Some(playlists)) = self.playlists.items.lock().unwrap().as_ref().map(|p| p.as_ref()) {
             PaginationProgress {
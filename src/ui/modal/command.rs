@@ -0,0 +1,57 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Layout, Rect},
+    symbols::border,
+    text::{Line, Span},
+    widgets::{block::Title, Block, Cell, Clear, Padding, Row, StatefulWidget, Table, Widget},
+};
+
+use crate::{
+    state::{modal::CommandState, TableArea},
+    ui::THEME,
+};
+
+impl Widget for &mut CommandState {
+    fn render(self, area: Rect, buf: &mut Buffer)
+    where
+        Self: Sized,
+    {
+        // Render along the bottom of the window with the completions above the prompt
+        let height = (self.completions.len() as u16 + 4).min(area.height / 2).max(4);
+        let [_, prompt] = Layout::vertical([Constraint::Fill(1), Constraint::Length(height)]).areas(area);
+
+        let block = Block::bordered()
+            .border_set(border::ROUNDED)
+            .border_style(THEME.border)
+            .padding(Padding::symmetric(1, 0))
+            .title(Title::from("[Command]").alignment(Alignment::Center));
+        let inner = block.inner(prompt);
+        Clear.render(prompt, buf);
+        block.render(prompt, buf);
+
+        let [completions, input] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(inner);
+
+        Line::from(vec![
+            Span::styled(":", THEME.highlight),
+            Span::raw(self.input.as_str()),
+            Span::styled("█", THEME.border),
+        ])
+        .render(input, buf);
+
+        let table = Table::new(
+            self.completions.iter().map(|completion| {
+                Row::new([
+                    Cell::from(completion.label.clone()),
+                    Cell::from(completion.detail.clone()).style(THEME.artists),
+                ])
+            }),
+            [Constraint::Fill(1), Constraint::Fill(1)],
+        )
+        .highlight_style(THEME.highlight)
+        .column_spacing(2);
+
+        StatefulWidget::render(table, completions, buf, &mut self.state);
+        self.area = TableArea::new(completions, &self.state);
+    }
+}
@@ -1,6 +1,6 @@
 use ratatui::widgets::Widget;
 
-use crate::state::modal::DevicesState;
+use crate::state::{modal::DevicesState, TableArea};
 
 use super::render_modal_with_state;
 
@@ -9,8 +9,9 @@ impl Widget for &mut DevicesState {
     where
         Self: Sized,
     {
-        render_modal_with_state(area, buf, "[Devices]", self.devices.iter().map(|d| {
+        let table = render_modal_with_state(area, buf, "[Devices]", self.devices.iter().map(|d| {
             [d.name.clone(), format!("[{}]", d.device_type)]            
         }), &mut self.state);
+        self.area = TableArea::new(table, &self.state);
     }
 }
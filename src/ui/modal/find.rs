@@ -0,0 +1,82 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Layout, Rect},
+    symbols::border,
+    text::{Line, Span},
+    widgets::{block::Title, Block, Cell, Clear, Padding, Row, StatefulWidget, Table, Widget},
+};
+
+use crate::{
+    state::{local_library::LOCAL_LIBRARY, modal::FindState, TableArea},
+    ui::THEME,
+};
+
+impl Widget for &mut FindState {
+    fn render(self, area: Rect, buf: &mut Buffer)
+    where
+        Self: Sized,
+    {
+        // Render centered over the window
+        let hoz = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Percentage(60),
+            Constraint::Fill(1),
+        ])
+        .split(area);
+        let vert = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Percentage(60),
+            Constraint::Fill(1),
+        ])
+        .split(hoz[1]);
+
+        let block = Block::bordered()
+            .border_set(border::ROUNDED)
+            .border_style(THEME.border)
+            .padding(Padding::symmetric(1, 0))
+            .title(Title::from("[Find]").alignment(Alignment::Center));
+        let inner = block.inner(vert[1]);
+        Clear.render(vert[1], buf);
+        block.render(vert[1], buf);
+
+        let [input, results] =
+            Layout::vertical([Constraint::Length(2), Constraint::Fill(1)]).areas(inner);
+
+        Line::from(vec![
+            Span::styled("> ", THEME.highlight),
+            Span::raw(self.query.as_str()),
+            Span::styled("█", THEME.border),
+        ])
+        .render(input, buf);
+
+        if self.results.is_empty() {
+            let (count, synced) = {
+                let library = LOCAL_LIBRARY.lock().unwrap();
+                (library.items.values().map(Vec::len).sum::<usize>(), library.synced_at.is_some())
+            };
+            let message = match (self.query.trim().is_empty(), synced) {
+                (false, _) => "<No Matches>".to_string(),
+                (true, false) if count == 0 => "Syncing library...".to_string(),
+                (true, _) => format!("Search {count} saved items"),
+            };
+            Line::from(message).centered().render(results, buf);
+            return;
+        }
+
+        let table = Table::new(
+            self.results.iter().map(|item| {
+                Row::new([
+                    Cell::from(item.kind.label()).style(THEME.context),
+                    Cell::from(item.name.clone()),
+                    Cell::from(item.detail.clone()).style(THEME.artists),
+                ])
+            }),
+            [Constraint::Length(9), Constraint::Fill(2), Constraint::Fill(1)],
+        )
+        .highlight_style(THEME.highlight)
+        .column_spacing(2);
+
+        StatefulWidget::render(table, results, buf, &mut self.state);
+        self.area = TableArea::new(results, &self.state);
+    }
+}
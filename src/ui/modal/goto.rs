@@ -1,7 +1,7 @@
 use crossterm::event::KeyEvent;
 use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
 
-use crate::state::actions::GoTo;
+use crate::{key, state::actions::GoTo};
 
 use super::{render_modal, KeyToString};
 
@@ -14,6 +14,6 @@ impl<'a> Widget for UiGoto<'a> {
     {
         render_modal(area, buf, "[Go To]", self.0.iter().map(|(key, goto)| {
             [key.key_to_string(), format!("{:?}", goto)]
-        }));
+        }).chain(std::iter::once([key!('g').key_to_string(), "Top".to_string()])));
     }
 }
@@ -1,13 +1,18 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyEventState, KeyModifiers};
 use ratatui::{buffer::Buffer, layout::{Alignment, Constraint, Layout, Rect}, style::{Color, Style}, symbols::border, widgets::{block::Title, Block, Cell, Clear, Padding, Row, StatefulWidget, Table, TableState, Widget}};
 
-use super::COLORS;
+use super::THEME;
 
 pub mod devices;
 pub mod actions;
 pub mod goto;
 pub mod add_to_playlist;
 pub mod artists;
+pub mod find;
+pub mod command;
+pub mod playlist_form;
+pub mod radio;
+pub mod which_key;
 
 trait KeyToString {
     fn key_to_string(&self) -> String;
@@ -64,6 +69,7 @@ pub fn render_modal<const N: usize, I: IntoIterator<Item=[String; N]>>(area: Rec
         .block(Block::bordered()
             //.borders(Borders::TOP | Borders::LEFT)
             .border_set(border::ROUNDED)
+            .border_style(THEME.border)
             .padding(Padding::symmetric(1, 1))
             .title(Title::from(title).alignment(Alignment::Center))
         )
@@ -88,12 +94,19 @@ pub fn render_modal<const N: usize, I: IntoIterator<Item=[String; N]>>(area: Rec
     Widget::render(list, vert[1], buf);
 }
 
-pub fn render_modal_with_state<const N: usize, I: IntoIterator<Item=[String; N]>>(area: Rect, buf: &mut Buffer, title: &str, rows: I, state: &mut TableState) {
+/// Render the rows as a table in the bottom right corner, returning the area the rows were rendered to
+pub fn render_modal_with_state<const N: usize, I: IntoIterator<Item=[String; N]>>(area: Rect, buf: &mut Buffer, title: &str, rows: I, state: &mut TableState) -> Rect {
     let mut longest_parts: [usize; N] = [0; N];
 
     // Rernder in bottom right corner
     // [{key}] {title}
     let mut count = 0;
+    let block = Block::bordered()
+        //.borders(Borders::TOP | Borders::LEFT)
+        .border_set(border::ROUNDED)
+        .border_style(THEME.border)
+        .padding(Padding::symmetric(1, 1))
+        .title(Title::from(title).alignment(Alignment::Center));
     let list = rows.into_iter().map(|parts| {
         count += 1;
         let cells = parts.into_iter().enumerate().map(|(i, part)| {
@@ -107,13 +120,8 @@ pub fn render_modal_with_state<const N: usize, I: IntoIterator<Item=[String; N]>
         Row::new(cells)
     })
         .collect::<Table>()
-        .block(Block::bordered()
-            //.borders(Borders::TOP | Borders::LEFT)
-            .border_set(border::ROUNDED)
-            .padding(Padding::symmetric(1, 1))
-            .title(Title::from(title).alignment(Alignment::Center))
-        )
-        .highlight_style(COLORS.highlight)
+        .block(block.clone())
+        .highlight_style(THEME.highlight)
         .widths(longest_parts.iter().map(|l| Constraint::Length(*l as u16)))
         .column_spacing(2);
 
@@ -133,4 +141,5 @@ pub fn render_modal_with_state<const N: usize, I: IntoIterator<Item=[String; N]>
 
     Clear.render(vert[1], buf);
     StatefulWidget::render(list, vert[1], buf, state);
+    block.inner(vert[1])
 }
@@ -0,0 +1,97 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
+    symbols::border,
+    text::{Line, Span},
+    widgets::{block::Title, Block, Clear, Padding, Widget},
+};
+
+use crate::{
+    state::{actions::PlaylistForm, modal::{FormField, PlaylistFormState}},
+    ui::THEME,
+};
+
+impl Widget for &PlaylistFormState {
+    fn render(self, area: Rect, buf: &mut Buffer)
+    where
+        Self: Sized,
+    {
+        let title = match &self.form {
+            PlaylistForm::Create => "[New Playlist]".to_string(),
+            PlaylistForm::Edit(_) => format!("[Edit: {}]", self.name),
+            PlaylistForm::Cover(_) => format!("[Cover: {}]", self.name),
+            PlaylistForm::Delete(..) => "[Delete Playlist]".to_string(),
+        };
+
+        // Centered over the window with a line for each field and one for the hint or error
+        let height = self.fields.len().max(1) as u16 + 4;
+        let [form] = Layout::vertical([Constraint::Length(height)]).flex(Flex::Center).areas(area);
+        let [form] = Layout::horizontal([Constraint::Length(60.min(area.width))]).flex(Flex::Center).areas(form);
+
+        let block = Block::bordered()
+            .border_set(border::ROUNDED)
+            .border_style(THEME.border)
+            .padding(Padding::symmetric(1, 1))
+            .title(Title::from(title).alignment(Alignment::Center));
+        let inner = block.inner(form);
+        Clear.render(form, buf);
+        block.render(form, buf);
+
+        let [fields, hint] = Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(inner);
+
+        if let PlaylistForm::Delete(..) = self.form {
+            Line::from(format!("Delete {}?", self.name)).render(fields, buf);
+        }
+
+        FormFields { fields: &self.fields, selected: self.selected }.render(fields, buf);
+
+        match self.error.as_ref() {
+            Some(error) => Line::from(error.as_str()).style(THEME.error).render(hint, buf),
+            None => Line::from(match self.form {
+                PlaylistForm::Delete(..) => "enter: delete  esc: cancel",
+                _ => "tab: next field  space: toggle  enter: save  esc: cancel",
+            })
+            .style(THEME.border)
+            .render(hint, buf),
+        }
+    }
+}
+
+/// Fields of a form, one to a line with their labels lined up
+pub struct FormFields<'a> {
+    pub fields: &'a [FormField],
+    pub selected: usize,
+}
+
+impl Widget for FormFields<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let label_width = self.fields.iter().map(|field| field.label().len()).max().unwrap_or_default() as u16;
+        let rows = Layout::vertical(vec![Constraint::Length(1); self.fields.len()]).split(area);
+        for (i, (field, row)) in self.fields.iter().zip(rows.iter()).enumerate() {
+            let selected = i == self.selected;
+            let [label, value] = Layout::horizontal([Constraint::Length(label_width), Constraint::Fill(1)])
+                .spacing(2)
+                .areas(*row);
+
+            Line::from(field.label())
+                .style(if selected { THEME.highlight } else { THEME.border })
+                .render(label, buf);
+            match field {
+                FormField::Text(_, text) => {
+                    // Keep the end of long text in view while it is typed
+                    let skip = (text.chars().count() + 1).saturating_sub(value.width as usize);
+                    let mut spans = vec![Span::raw(text.chars().skip(skip).collect::<String>())];
+                    if selected {
+                        spans.push(Span::styled("█", THEME.border));
+                    }
+                    Line::from(spans).render(value, buf);
+                }
+                FormField::Toggle(_, on) => {
+                    Line::from(if *on { "[x]" } else { "[ ]" })
+                        .style(if selected { THEME.highlight } else { THEME.border })
+                        .render(value, buf);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
+    symbols::border,
+    text::Line,
+    widgets::{block::Title, Block, Clear, Padding, Widget},
+};
+
+use crate::{
+    state::{modal::RadioState, Loading},
+    ui::THEME,
+};
+
+use super::playlist_form::FormFields;
+
+impl Widget for &RadioState {
+    fn render(self, area: Rect, buf: &mut Buffer)
+    where
+        Self: Sized,
+    {
+        let title = if self.radio.seeds.is_empty() {
+            "[Genre Radio]".to_string()
+        } else {
+            format!("[Radio: {}]", self.radio.name)
+        };
+
+        // Centered over the window with a line for each field and one for the hint or error
+        let height = self.fields.len() as u16 + 4;
+        let [form] = Layout::vertical([Constraint::Length(height)]).flex(Flex::Center).areas(area);
+        let [form] = Layout::horizontal([Constraint::Length(60.min(area.width))]).flex(Flex::Center).areas(form);
+
+        let block = Block::bordered()
+            .border_set(border::ROUNDED)
+            .border_style(THEME.border)
+            .padding(Padding::symmetric(1, 1))
+            .title(Title::from(title).alignment(Alignment::Center));
+        let inner = block.inner(form);
+        Clear.render(form, buf);
+        block.render(form, buf);
+
+        let [fields, hint] = Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(inner);
+        FormFields { fields: &self.fields, selected: self.selected }.render(fields, buf);
+
+        if let Some(error) = self.error.as_ref() {
+            Line::from(error.as_str()).style(THEME.error).render(hint, buf);
+            return;
+        }
+        // Genres that match what was typed take the place of the hint while one is picked
+        let hint_text = if self.picking_genre() {
+            match *self.genres.lock().unwrap() {
+                Loading::Loading => "Loading genres...".to_string(),
+                Loading::None => "Couldn't load the genres".to_string(),
+                Loading::Some(_) => match self.matching_genres().as_slice() {
+                    [] => "No genre matches".to_string(),
+                    genres => format!("right: {}", genres.join("  ")),
+                },
+            }
+        } else {
+            "tab: next field  space: toggle  enter: start  esc: cancel".to_string()
+        };
+        Line::from(hint_text).style(THEME.border).render(hint, buf);
+    }
+}
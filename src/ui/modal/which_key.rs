@@ -0,0 +1,28 @@
+use ratatui::widgets::Widget;
+
+use super::{render_modal, KeyToString};
+use crate::keymap::Pending;
+
+/// Keys that can finish the sequence typed so far
+impl Widget for &Pending {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
+        where
+            Self: Sized {
+
+        let typed = self
+            .count
+            .map(|count| count.to_string())
+            .into_iter()
+            .chain(self.keys.iter().map(|key| key.key_to_string()))
+            .collect::<String>();
+
+        let mut next = self
+            .next
+            .iter()
+            .map(|(key, label)| [key.key_to_string(), label.clone().unwrap_or_else(|| "+more".to_string())])
+            .collect::<Vec<_>>();
+        next.sort();
+
+        render_modal(area, buf, &format!("[{typed}]"), next)
+    }
+}
@@ -1,11 +1,11 @@
 use ratatui::{
-    buffer::Buffer, layout::{Constraint, Direction, Layout, Rect}, style::{Stylize, Style}, text::{Line, Span}, widgets::{Block, Borders, StatefulWidget, Widget}
+    buffer::Buffer, layout::{Constraint, Direction, Layout, Rect}, style::Stylize, text::{Line, Span}, widgets::{Block, Borders, StatefulWidget, Widget}
 };
 use tupy::{api::response::{Device, PlaybackItem, Repeat}, DateTime, Duration, Local};
 
 use crate::state::playback::{Playback, PlaybackState};
 
-use super::{format_duration, COLORS};
+use super::{format_duration, THEME};
 
 impl Widget for &PlaybackState {
     fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
@@ -19,6 +19,11 @@ impl Widget for &PlaybackState {
                 buf,
                 &mut self.last_playback_poll.clone(),
             ),
+            None if !self.loaded => UI {
+                title: Span::from("Loading..."),
+                ..Default::default()
+            }
+                .render(area, buf),
             None => {
                 NoPlayback.render(area, buf);
             }
@@ -68,7 +73,7 @@ impl StatefulWidget for &Playback {
 
                 UI {
                     title: Span::from(track.name.clone()),
-                    saved: self.saved,
+                    saved: self.saved(),
                     device: self.device.clone(),
                     shuffle: Some(self.shuffle),
                     repeat: Some(self.repeat),
@@ -84,7 +89,7 @@ impl StatefulWidget for &Playback {
 
                 UI {
                     title: Span::from(episode.name.clone()),
-                    saved: self.saved,
+                    saved: self.saved(),
                     device: self.device.clone(),
                     shuffle: Some(self.shuffle),
                     repeat: Some(self.repeat),
@@ -113,12 +118,45 @@ impl StatefulWidget for &Playback {
     }
 }
 
+/// Area of the progress bar within the area the playback is rendered to
+pub fn progress_area(area: Rect) -> Rect {
+    let play_area = Block::bordered().borders(Borders::LEFT | Borders::RIGHT).inner(area);
+    Rect {
+        y: play_area.y + 2,
+        height: play_area.height.saturating_sub(2).min(1),
+        ..play_area
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct ProgressBar {
     current: Duration,
     total: Duration,
 }
 
+impl ProgressBar {
+    /// Position in the item that a column of the rendered progress bar represents.
+    ///
+    /// Returns `None` if the column is outside of the bar.
+    pub fn position(area: Rect, current: Duration, total: Duration, column: u16) -> Option<Duration> {
+        if total <= Duration::zero() {
+            return None;
+        }
+
+        let time_prog = format_duration(current.min(total)).chars().count() as u16;
+        let time_dur = format_duration(total).chars().count() as u16;
+        let rw = area.width.checked_sub(2 + time_prog + time_dur)?;
+        let start = area.x + time_prog + 1;
+
+        if rw == 0 || column < start || column >= start + rw {
+            return None;
+        }
+
+        let scale = (column - start) as f64 / rw as f64;
+        Some(Duration::milliseconds((total.num_milliseconds() as f64 * scale) as i64))
+    }
+}
+
 impl Widget for ProgressBar {
     fn render(mut self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
     where
@@ -143,12 +181,8 @@ impl Widget for ProgressBar {
         Line::from(vec![
             time_prog.bold(),
             " ".into(),
-            (0..progress)
-                .map(|_| "─")
-                .collect::<String>()
-                .green()
-                .bold(),
-            (0..remaining).map(|_| "┄").collect::<String>().black(),
+            Span::styled((0..progress).map(|_| "─").collect::<String>(), THEME.progress),
+            Span::styled((0..remaining).map(|_| "┄").collect::<String>(), THEME.progress_remaining),
             " ".into(),
             time_dur.bold(),
         ])
@@ -199,9 +233,9 @@ impl<'a> Widget for UI<'a> {
             Line::from(vec![
                 Span::from("Shuffle: "),
                 Span::from(if shuffle { "On" } else { "Off" }).style(if shuffle {
-                    Style::default().green()
+                    THEME.enabled
                 } else {
-                    Style::default().red()
+                    THEME.disabled
                 })
             ])
                 .dim()
@@ -211,9 +245,9 @@ impl<'a> Widget for UI<'a> {
             Line::from(vec![
                 Span::from("Repeat: "),
                 Span::from(format!("{:?}", repeat)).style(match repeat {
-                    Repeat::Off => Style::default().red(),
-                    Repeat::Track => Style::default().cyan(),
-                    Repeat::Context => Style::default().yellow(),
+                    Repeat::Off => THEME.disabled,
+                    Repeat::Track => THEME.repeat_track,
+                    Repeat::Context => THEME.repeat_context,
                 })
             ])
                 .dim()
@@ -223,12 +257,12 @@ impl<'a> Widget for UI<'a> {
             if !device.is_restricted && device.supports_volume {
                 Line::from(vec![
                     Span::from("Vol: "),
-                    Span::from(format!("{}%", device.volume_percent)).magenta()
+                    Span::from(format!("{}%", device.volume_percent)).style(THEME.device)
                 ])
                     .dim()
                     .render(info_layout[2], buf);
             }
-            Line::from(vec!["Playing on ".into(), device.name.clone().magenta()])
+            Line::from(vec!["Playing on ".into(), Span::styled(device.name.clone(), THEME.device)])
                 .right_aligned()
                 .dim()
                 .gray()
@@ -241,7 +275,7 @@ impl<'a> Widget for UI<'a> {
             .split(playing[0]);
 
         render_title(title[0], buf, self.title, self.fully_played);
-        Line::from(if self.saved { "♥ " } else { "  " }).style(COLORS.like).render(title[1], buf);
+        Line::from(if self.saved { format!("{} ", THEME.glyphs.like) } else { "  ".to_string() }).style(THEME.like).render(title[1], buf);
         if let Some(c) = &self.context {
             c.render(title[2], buf);
         }
@@ -250,7 +284,7 @@ impl<'a> Widget for UI<'a> {
             current: self.progress.unwrap_or(Duration::zero()),
             total: self.duration.unwrap_or(Duration::zero()),
         }
-        .render(playing[2], buf);
+        .render(progress_area(area), buf);
     }
 }
 
@@ -258,7 +292,7 @@ fn render_title<'a>(area: Rect, buf: &mut Buffer, title: Span<'a>, fully_played:
     if fully_played {
         Line::from(vec![
             title,
-            Span::from(" ✓").style(COLORS.finished),
+            Span::from(format!(" {}", THEME.glyphs.finished)).style(THEME.finished),
         ]).render(area, buf);
     } else {
         title.render(area, buf);
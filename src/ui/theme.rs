@@ -0,0 +1,271 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use color_eyre::{eyre::eyre, Result};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use serde::Deserialize;
+
+lazy_static::lazy_static! {
+    pub static ref THEME_FILE_PATH: PathBuf = dirs::config_dir().unwrap().join("rataify").join("theme.json");
+}
+
+/// Built in palettes that a theme file can start from
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Palette {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Glyphs {
+    pub like: String,
+    pub finished: String,
+}
+
+impl Default for Glyphs {
+    fn default() -> Self {
+        Self {
+            like: "♥".to_string(),
+            finished: "✓".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub artists: Style,
+    pub track: Style,
+    pub episode: Style,
+    pub duration: Style,
+    pub context: Style,
+    pub like: Style,
+    pub finished: Style,
+    pub chapter_number: Style,
+    pub highlight: Style,
+    pub marked: Style,
+    pub border: Style,
+    pub error: Style,
+    pub public: Style,
+    pub private: Style,
+    pub progress: Style,
+    pub progress_remaining: Style,
+    pub enabled: Style,
+    pub disabled: Style,
+    pub repeat_track: Style,
+    pub repeat_context: Style,
+    pub device: Style,
+    pub page: Style,
+    pub page_current: Style,
+
+    pub glyphs: Glyphs,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl From<Palette> for Theme {
+    fn from(palette: Palette) -> Self {
+        match palette {
+            Palette::Dark => Self::dark(),
+            Palette::Light => Self::light(),
+            Palette::HighContrast => Self::high_contrast(),
+        }
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            artists: Style::default().gray(),
+            track: Style::default().cyan(),
+            episode: Style::default().light_green(),
+            duration: Style::default().bold(),
+            context: Style::default().white().bold(),
+            like: Style::default().fg(Color::Red),
+            finished: Style::default().green(),
+            chapter_number: Style::default().dim().gray(),
+            highlight: Style::default().fg(Color::Yellow),
+            marked: Style::default().on_dark_gray(),
+            border: Style::default(),
+            error: Style::default().red(),
+            public: Style::default().cyan(),
+            private: Style::default().magenta(),
+            progress: Style::default().green().bold(),
+            progress_remaining: Style::default().black(),
+            enabled: Style::default().green(),
+            disabled: Style::default().red(),
+            repeat_track: Style::default().cyan(),
+            repeat_context: Style::default().yellow(),
+            device: Style::default().magenta(),
+            page: Style::default().dim().gray(),
+            page_current: Style::default().white().bold(),
+            glyphs: Glyphs::default(),
+        }
+    }
+
+    /// Palette for terminals with a light background
+    pub fn light() -> Self {
+        Self {
+            artists: Style::default().dark_gray(),
+            track: Style::default().blue(),
+            episode: Style::default().green(),
+            duration: Style::default().bold(),
+            context: Style::default().black().bold(),
+            like: Style::default().fg(Color::Red),
+            finished: Style::default().green(),
+            chapter_number: Style::default().dark_gray(),
+            highlight: Style::default().fg(Color::Magenta).bold(),
+            marked: Style::default().on_gray(),
+            border: Style::default().dark_gray(),
+            error: Style::default().red(),
+            public: Style::default().blue(),
+            private: Style::default().magenta(),
+            progress: Style::default().green().bold(),
+            progress_remaining: Style::default().gray(),
+            enabled: Style::default().green(),
+            disabled: Style::default().red(),
+            repeat_track: Style::default().blue(),
+            repeat_context: Style::default().magenta(),
+            device: Style::default().magenta(),
+            page: Style::default().gray(),
+            page_current: Style::default().black().bold(),
+            glyphs: Glyphs::default(),
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        Self {
+            artists: Style::default().white(),
+            track: Style::default().light_cyan().bold(),
+            episode: Style::default().light_green().bold(),
+            duration: Style::default().white().bold(),
+            context: Style::default().white().bold(),
+            like: Style::default().light_red().bold(),
+            finished: Style::default().light_green().bold(),
+            chapter_number: Style::default().white(),
+            highlight: Style::default().black().on_yellow().bold(),
+            marked: Style::default().underlined().bold(),
+            border: Style::default().white(),
+            error: Style::default().light_red().bold(),
+            public: Style::default().light_cyan(),
+            private: Style::default().light_magenta(),
+            progress: Style::default().light_green().bold(),
+            progress_remaining: Style::default().white(),
+            enabled: Style::default().light_green().bold(),
+            disabled: Style::default().light_red().bold(),
+            repeat_track: Style::default().light_cyan().bold(),
+            repeat_context: Style::default().light_yellow().bold(),
+            device: Style::default().light_magenta(),
+            page: Style::default().white(),
+            page_current: Style::default().yellow().bold(),
+            glyphs: Glyphs::default(),
+        }
+    }
+
+    fn style_mut(&mut self, element: &str) -> Option<&mut Style> {
+        Some(match element {
+            "artists" => &mut self.artists,
+            "track" => &mut self.track,
+            "episode" => &mut self.episode,
+            "duration" => &mut self.duration,
+            "context" => &mut self.context,
+            "like" => &mut self.like,
+            "finished" => &mut self.finished,
+            "chapter_number" => &mut self.chapter_number,
+            "highlight" => &mut self.highlight,
+            "marked" => &mut self.marked,
+            "border" => &mut self.border,
+            "error" => &mut self.error,
+            "public" => &mut self.public,
+            "private" => &mut self.private,
+            "progress" => &mut self.progress,
+            "progress_remaining" => &mut self.progress_remaining,
+            "enabled" => &mut self.enabled,
+            "disabled" => &mut self.disabled,
+            "repeat_track" => &mut self.repeat_track,
+            "repeat_context" => &mut self.repeat_context,
+            "device" => &mut self.device,
+            "page" => &mut self.page,
+            "page_current" => &mut self.page_current,
+            _ => return None,
+        })
+    }
+
+    /// Load the theme from the theme file, using the dark palette if the file doesn't exist
+    ///
+    /// The file picks a palette and can then replace the style of any element and the glyphs
+    ///
+    /// ```json
+    /// {
+    ///     "palette": "light",
+    ///     "styles": {
+    ///         "track": { "fg": "blue", "add_modifier": "BOLD" },
+    ///         "highlight": { "fg": "black", "bg": "#f5c211" }
+    ///     },
+    ///     "glyphs": { "like": "+", "finished": "x" }
+    /// }
+    /// ```
+    pub fn load() -> Result<Self> {
+        if !THEME_FILE_PATH.exists() {
+            return Ok(Self::default());
+        }
+
+        let file: ThemeFile = serde_json::from_str(&std::fs::read_to_string(THEME_FILE_PATH.as_path())?)?;
+
+        let mut theme = Self::from(file.palette);
+        for (element, style) in file.styles {
+            match theme.style_mut(&element) {
+                Some(s) => *s = style.into(),
+                None => return Err(eyre!("Unknown theme element '{element}' in {}", THEME_FILE_PATH.display())),
+            }
+        }
+
+        if let Some(like) = file.glyphs.like {
+            theme.glyphs.like = like;
+        }
+        if let Some(finished) = file.glyphs.finished {
+            theme.glyphs.finished = finished;
+        }
+
+        Ok(theme)
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ThemeFile {
+    palette: Palette,
+    styles: HashMap<String, ElementStyle>,
+    glyphs: GlyphsFile,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct GlyphsFile {
+    like: Option<String>,
+    finished: Option<String>,
+}
+
+/// Style for an element in the theme file, replacing the palette's style entirely
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(default)]
+struct ElementStyle {
+    fg: Option<Color>,
+    bg: Option<Color>,
+    add_modifier: Modifier,
+    sub_modifier: Modifier,
+}
+
+impl From<ElementStyle> for Style {
+    fn from(style: ElementStyle) -> Self {
+        let mut s = Style::default().add_modifier(style.add_modifier).remove_modifier(style.sub_modifier);
+        s.fg = style.fg;
+        s.bg = style.bg;
+        s
+    }
+}
@@ -0,0 +1,161 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    symbols::{self, border},
+    text::Line,
+    widgets::{
+        block::{Position, Title},
+        Block, Borders, Cell, Padding, Paragraph, Row, StatefulWidget, Table, TableState, Tabs, Widget,
+    },
+};
+use strum::IntoEnumIterator;
+use tupy::api::response::{Categories, NewReleases, PagedPlaylists};
+
+use crate::{
+    state::{
+        window::{browse::{BrowseState, BrowseTab, CATEGORY_COLUMNS}, Page},
+        Loading,
+    },
+    ui::{PaginationProgress, THEME},
+};
+
+use super::top::render_message;
+
+/// Height of a single category tile in the grid
+static TILE_HEIGHT: u16 = 3;
+
+impl Widget for &BrowseState {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let title = match (self.selected_tab, self.category.as_ref()) {
+            (BrowseTab::Categories, Some(category)) => format!("[Browse: {}]", category.category.name),
+            _ => "[Browse]".to_string(),
+        };
+
+        let block = Block::bordered()
+            .borders(Borders::all())
+            .title(
+                Title::from(title)
+                    .alignment(Alignment::Center)
+                    .position(Position::Bottom),
+            )
+            .border_set(border::ROUNDED)
+            .border_style(THEME.border);
+
+        (&block).render(area, buf);
+
+        let layout = Layout::vertical([Constraint::Length(2), Constraint::Fill(1)])
+            .split(block.inner(area));
+
+        Tabs::new(BrowseTab::iter().map(|t| Line::from(t.title()).centered()))
+            .highlight_style(THEME.highlight)
+            .padding(" ", " ")
+            .select(self.selected_tab as usize)
+            .divider(symbols::DOT)
+            .render(layout[0], buf);
+
+        let area = Block::default().padding(Padding::horizontal(1)).inner(layout[1]);
+        match self.selected_tab {
+            BrowseTab::Categories => match self.category.as_ref() {
+                Some(category) => match category.pages.items.lock().unwrap().as_ref() {
+                    Some(Loading::Some(playlists)) => {
+                        render_playlists(playlists, &category.pages.page.lock().unwrap(), category.state.clone(), area, buf)
+                    }
+                    Some(Loading::Loading) => render_message("Loading...", false, area, buf),
+                    None | Some(Loading::None) => render_message("<No Playlists>", true, area, buf),
+                },
+                None => match self.categories.items.lock().unwrap().as_ref() {
+                    Some(Loading::Some(categories)) => {
+                        render_categories(categories, &self.categories.page.lock().unwrap(), self.state.selected(), area, buf)
+                    }
+                    Some(Loading::Loading) => render_message("Loading...", false, area, buf),
+                    None | Some(Loading::None) => render_message("<No Categories>", true, area, buf),
+                },
+            },
+            BrowseTab::Featured => match self.featured.items.lock().unwrap().as_ref() {
+                Some(Loading::Some(playlists)) => {
+                    render_playlists(playlists, &self.featured.page.lock().unwrap(), self.state.clone(), area, buf)
+                }
+                Some(Loading::Loading) => render_message("Loading...", false, area, buf),
+                None | Some(Loading::None) => render_message("<No Featured Playlists>", true, area, buf),
+            },
+            BrowseTab::NewReleases => match self.new_releases.items.lock().unwrap().as_ref() {
+                Some(Loading::Some(releases)) => {
+                    render_new_releases(releases, &self.new_releases.page.lock().unwrap(), self.state.clone(), area, buf)
+                }
+                Some(Loading::Loading) => render_message("Loading...", false, area, buf),
+                None | Some(Loading::None) => render_message("<No New Releases>", true, area, buf),
+            },
+        }
+    }
+}
+
+fn render_pagination(page: &Page, area: Rect, buf: &mut Buffer) {
+    if page.max_page > 1 {
+        PaginationProgress {
+            current: page.page,
+            total: page.max_page,
+        }
+        .render(area, buf);
+    }
+}
+
+fn render_categories(categories: &Categories, page: &Page, selected: Option<usize>, area: Rect, buf: &mut Buffer) {
+    let [grid, _] = Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(area);
+
+    let visible = (grid.height / TILE_HEIGHT).max(1) as usize;
+    let selected_row = selected.unwrap_or(0) / CATEGORY_COLUMNS;
+    // Scroll the grid so the selected row is always visible
+    let offset = selected_row.saturating_sub(visible - 1);
+
+    let rows = Layout::vertical(vec![Constraint::Length(TILE_HEIGHT); visible]).split(grid);
+    for (r, row) in categories.items.chunks(CATEGORY_COLUMNS).skip(offset).take(visible).enumerate() {
+        let columns = Layout::horizontal(vec![Constraint::Ratio(1, CATEGORY_COLUMNS as u32); CATEGORY_COLUMNS]).split(rows[r]);
+        for (c, category) in row.iter().enumerate() {
+            let index = (offset + r) * CATEGORY_COLUMNS + c;
+            let style = if selected == Some(index) { THEME.highlight } else { Style::default() };
+
+            Paragraph::new(category.name.clone())
+                .alignment(Alignment::Center)
+                .style(style)
+                .block(Block::bordered().border_set(border::ROUNDED).style(style))
+                .render(columns[c], buf);
+        }
+    }
+
+    render_pagination(page, area, buf);
+}
+
+fn render_playlists(playlists: &PagedPlaylists, page: &Page, mut state: TableState, area: Rect, buf: &mut Buffer) {
+    let table = playlists.items.iter().map(|p| {
+        Row::new(vec![
+            Cell::from(p.name.clone()),
+            Cell::from(Line::from(p.owner.name.clone().unwrap_or(p.owner.id.clone())).right_aligned()),
+            Cell::from(p.description.clone().unwrap_or_default()).style(THEME.artists),
+        ])
+    })
+        .collect::<Table>()
+        .widths([Constraint::Fill(1), Constraint::Length(20), Constraint::Fill(2)])
+        .column_spacing(1)
+        .highlight_style(THEME.highlight);
+
+    StatefulWidget::render(table, area, buf, &mut state);
+    render_pagination(page, area, buf);
+}
+
+fn render_new_releases(releases: &NewReleases, page: &Page, mut state: TableState, area: Rect, buf: &mut Buffer) {
+    let table = releases.items.iter().map(|a| {
+        Row::new(vec![
+            Cell::from(a.name.clone()),
+            Cell::from(Line::from(format!("{:?}", a.album_type)).right_aligned()).dim(),
+            Cell::from(a.artists.iter().map(|a| a.name.clone()).collect::<Vec<_>>().join(", ")).style(THEME.artists),
+        ])
+    })
+        .collect::<Table>()
+        .widths([Constraint::Fill(1), Constraint::Length(12), Constraint::Fill(1)])
+        .column_spacing(1)
+        .highlight_style(THEME.highlight);
+
+    StatefulWidget::render(table, area, buf, &mut state);
+    render_pagination(page, area, buf);
+}
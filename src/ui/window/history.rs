@@ -0,0 +1,80 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Rect},
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{
+        block::{Position, Title},
+        Block, Borders, Cell, Padding, Row, StatefulWidget, Table, Widget,
+    },
+};
+
+use crate::{
+    state::{window::history::HistoryState, Loading},
+    ui::{format_duration, THEME},
+};
+
+use super::top::render_message;
+
+impl Widget for &HistoryState {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .borders(Borders::all())
+            .title(
+                Title::from("[Recently Played]")
+                    .alignment(Alignment::Center)
+                    .position(Position::Bottom),
+            )
+            .padding(Padding::symmetric(1, 0))
+            .border_set(border::ROUNDED)
+            .border_style(THEME.border);
+
+        (&block).render(area, buf);
+        let area = block.inner(area);
+
+        match self.pages.items.lock().unwrap().as_ref() {
+            Some(Loading::Loading) => render_message("Loading...", false, area, buf),
+            None | Some(Loading::None) => render_message("<No Recently Played>", true, area, buf),
+            Some(Loading::Some(items)) => {
+                let max_name = items.iter().map(|h| h.as_ref().track.name.len()).max().unwrap_or(0);
+
+                // ♥ | Played At | Duration | Name | Artists | Context
+                let table = items
+                    .iter()
+                    .map(|h| {
+                        let history = h.as_ref();
+                        Row::new(vec![
+                            Cell::from(if h.saved() { THEME.glyphs.like.as_str() } else { "" }).style(THEME.like),
+                            Cell::from(history.played_at.format("%b %d %H:%M").to_string()).dim(),
+                            Cell::from(Line::from(format_duration(history.track.duration)).right_aligned().style(THEME.duration)),
+                            Cell::from(history.track.name.clone()).style(THEME.track),
+                            Cell::from(
+                                history
+                                    .track
+                                    .artists
+                                    .iter()
+                                    .map(|a| a.name.clone())
+                                    .collect::<Vec<String>>()
+                                    .join(", "),
+                            ).style(THEME.artists),
+                            Cell::from(history.context.as_ref().map(|c| c.context_type.clone()).unwrap_or_default()).style(THEME.context),
+                        ])
+                    })
+                    .collect::<Table>()
+                    .highlight_style(THEME.highlight)
+                    .widths([
+                        Constraint::Length(3),
+                        Constraint::Length(12),
+                        Constraint::Length(8),
+                        Constraint::Max(max_name as u16),
+                        Constraint::Fill(1),
+                        Constraint::Length(8),
+                    ])
+                    .column_spacing(2);
+
+                StatefulWidget::render(table, area, buf, &mut self.state.clone());
+            }
+        }
+    }
+}
@@ -13,9 +13,9 @@ use crate::{
     state::{
         window::{
             landing::{Cover, LandingSection}, MappedPages
-        }, wrappers::Saved, Loading
+        }, view::{Columns, Rows}, wrappers::Saved, Loading, TableArea
     },
-    ui::{format_duration, PaginationProgress, COLORS},
+    ui::{format_duration, MarkedRows, PaginationProgress, ViewBar, THEME},
     Locked, Shared,
 };
 
@@ -30,6 +30,7 @@ pub fn render(
     state: &TableState,
     section: &LandingSection,
     cover: &mut Shared<Locked<Loading<Cover>>>,
+    table_area: &mut TableArea,
 ) {
     let title = format!("[Album: {}]", album.name);
     let (under, main) = render_landing(
@@ -40,7 +41,7 @@ pub fn render(
     );
 
     let artists = album.artists.iter().map(|v| v.name.clone()).collect::<Vec<_>>().join(", ");
-    let info_highlight = if let LandingSection::Context = section { COLORS.highlight } else { Style::default() };
+    let info_highlight = if let LandingSection::Context = section { THEME.highlight } else { Style::default() };
     let info = [
         if under.height <= 2 {
             Paragraph::new(artists).style(info_highlight)
@@ -91,7 +92,7 @@ pub fn render(
 
             Line::from("<No Album Items>")
                 .centered()
-                .red()
+                .style(THEME.error)
                 .render(vert, buf);
         }
         Some(Loading::Some(data)) => {
@@ -103,10 +104,10 @@ pub fn render(
                 .iter()
                 .map(|track| {
                     Row::new(vec![
-                        Cell::from(if track.saved { "♥" } else { "" }).style(COLORS.like),
-                        Cell::from(format_duration(track.as_ref().duration)).style(COLORS.duration),
-                        Cell::from(track.as_ref().name.clone()).style(COLORS.track),
-                        Cell::from(track.as_ref().artists.iter().map(|a| a.name.clone()).collect::<Vec<_>>().join(", ")).style(COLORS.artists),
+                        Cell::from(if track.saved() { THEME.glyphs.like.as_str() } else { "" }).style(THEME.like),
+                        Cell::from(format_duration(track.as_ref().duration)).style(THEME.duration),
+                        Cell::from(track.as_ref().name.clone()).style(THEME.track),
+                        Cell::from(track.as_ref().artists.iter().map(|a| a.name.clone()).collect::<Vec<_>>().join(", ")).style(THEME.artists),
                     ])
                 })
                 .collect::<Table>()
@@ -116,16 +117,27 @@ pub fn render(
                     Constraint::Fill(1),
                     Constraint::Fill(1),
                 ])
-                .block(block)
-                .highlight_style(if section.is_content() { COLORS.highlight } else { Style::default() });
+                .block(block.clone())
+                .highlight_style(if section.is_content() { THEME.highlight } else { Style::default() });
 
-            StatefulWidget::render(table_tracks, main, buf, &mut state.clone());
+            let mut state = state.clone();
+            StatefulWidget::render(table_tracks, main, buf, &mut state);
+            *table_area = TableArea::new(block.inner(main), &state);
 
             PaginationProgress {
                 current: page.page,
                 total: page.max_page,
             }
             .render(main, buf);
+            ViewBar(&pages.view.lock().unwrap()).render(main, buf);
+            let uris = data.rows().iter().map(Columns::uri).collect::<Vec<_>>();
+            MarkedRows {
+                view: &pages.view.lock().unwrap(),
+                uris: &uris,
+                area: table_area,
+                selected: state.selected(),
+            }
+            .render(main, buf);
 
             if scrollable {
                 let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight);
@@ -1,7 +1,7 @@
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Margin, Rect},
-    style::{Style, Stylize},
+    style::Style,
     text::Line,
     widgets::{
         Block, Cell, Padding, Paragraph, Row, Scrollbar, ScrollbarOrientation, ScrollbarState, StatefulWidget, Table, TableState, Widget, Wrap
@@ -14,9 +14,9 @@ use crate::{
         window::{
             landing::{ArtistLanding, Cover, LandingSection},
             MappedPages,
-        }, wrappers::Saved, Loading
+        }, view::Columns, wrappers::Saved, Loading, TableArea
     },
-    ui::{format_track_saved, PaginationProgress, COLORS},
+    ui::{format_track_saved, MarkedRows, PaginationProgress, ViewBar, THEME},
     Locked, Shared,
 };
 
@@ -33,6 +33,8 @@ pub fn render(
     section: &ArtistLanding,
     landing_section: &LandingSection,
     cover: &Shared<Locked<Loading<Cover>>>,
+    tracks_area: &mut TableArea,
+    albums_area: &mut TableArea,
 ) {
     let title = format!("[Artist: {}]", artist.as_ref().name);
     let (under, main) = render_landing(
@@ -51,10 +53,10 @@ pub fn render(
         .unwrap()
         .join(",");
 
-    let info_highlight = if let LandingSection::Context = landing_section { COLORS.highlight } else { Style::default() };
+    let info_highlight = if let LandingSection::Context = landing_section { THEME.highlight } else { Style::default() };
 
     let info = [
-        Paragraph::new(Line::from(if artist.saved { "♥" } else { "" }).centered().style(COLORS.like)),
+        Paragraph::new(Line::from(if artist.saved() { THEME.glyphs.like.as_str() } else { "" }).centered().style(THEME.like)),
         if under.height <= 4 {
             Paragraph::new(artist.as_ref().genres.join(", ")).style(info_highlight)
         } else {
@@ -95,7 +97,7 @@ pub fn render(
     // RENDER ARTIST'S TOP TRACKS
     let table_top_tracks = top_tracks
         .iter()
-        .map(|v| format_track_saved(v.as_ref(), v.saved))
+        .map(|v| format_track_saved(v.as_ref(), v.saved()))
         .collect::<Table>()
         .widths([
             Constraint::Length(2),
@@ -104,16 +106,19 @@ pub fn render(
             Constraint::Fill(1),
             Constraint::Fill(2),
         ])
-        .highlight_style(if landing_section.is_content() && section.is_tracks() { COLORS.highlight } else { Style::default() });
+        .highlight_style(if landing_section.is_content() && section.is_tracks() { THEME.highlight } else { Style::default() });
 
         match section {
             ArtistLanding::Tracks => {
-                StatefulWidget::render(table_top_tracks, vert[0], buf, &mut state.clone());
+                let mut state = state.clone();
+                StatefulWidget::render(table_top_tracks, vert[0], buf, &mut state);
+                *tracks_area = TableArea::new(vert[0], &state);
             }
             ArtistLanding::Albums => {
                 let mut temp_state = TableState::default();
                 temp_state.select(Some(top_tracks.len() - 1));
                 StatefulWidget::render(table_top_tracks, vert[0], buf, &mut temp_state);
+                *tracks_area = TableArea::new(vert[0], &temp_state);
             }
         }
     
@@ -136,7 +141,7 @@ pub fn render(
 
             Line::from("<No Playlist Items>")
                 .centered()
-                .red()
+                .style(THEME.error)
                 .render(vert, buf);
         }
         Some(Loading::Some(data)) => {
@@ -148,8 +153,8 @@ pub fn render(
                 .iter()
                 .map(|a| {
                     Row::new(vec![
-                        Cell::from(if a.saved { "♥" } else { "" }).style(COLORS.like),
-                        Cell::from(a.as_ref().name.clone()).style(COLORS.context),
+                        Cell::from(if a.saved() { THEME.glyphs.like.as_str() } else { "" }).style(THEME.like),
+                        Cell::from(a.as_ref().name.clone()).style(THEME.context),
                         Cell::from(format!("{:?}", a.as_ref().album_type)),
                         Cell::from(
                             a.as_ref().artists
@@ -158,36 +163,48 @@ pub fn render(
                                 .collect::<Vec<_>>()
                                 .join(", "),
                         )
-                        .style(COLORS.artists),
+                        .style(THEME.artists),
                     ])
                 })
                 .collect::<Table>()
-                .block(block)
+                .block(block.clone())
                 .widths([
                     Constraint::Length(2),
                     Constraint::Fill(3),
                     Constraint::Length(11),
                     Constraint::Fill(1),
                 ])
-                .highlight_style(if landing_section.is_content() { COLORS.highlight } else { Style::default() });
+                .highlight_style(if landing_section.is_content() { THEME.highlight } else { Style::default() });
 
             PaginationProgress {
                 current: page.page,
                 total: page.max_page,
             }
             .render(vert[1], buf);
+            ViewBar(&albums.view.lock().unwrap()).render(vert[1], buf);
 
             let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight);
             let mut scrollbar_state = match section {
                 ArtistLanding::Tracks => {
                     Widget::render(table_albums, vert[1], buf);
+                    *albums_area = TableArea::new(block.inner(vert[1]), &TableState::default());
                     ScrollbarState::new(data.len()).position(0)
                 }
                 ArtistLanding::Albums => {
-                    StatefulWidget::render(table_albums, vert[1], buf, &mut state.clone());
+                    let mut state = state.clone();
+                    StatefulWidget::render(table_albums, vert[1], buf, &mut state);
+                    *albums_area = TableArea::new(block.inner(vert[1]), &state);
                     ScrollbarState::new(data.len()).position(state.selected().unwrap_or(0))
                 }
             };
+            let uris = data.iter().map(Columns::uri).collect::<Vec<_>>();
+            MarkedRows {
+                view: &albums.view.lock().unwrap(),
+                uris: &uris,
+                area: albums_area,
+                selected: if section.is_albums() { state.selected() } else { None },
+            }
+            .render(vert[1], buf);
 
             if scrollable {
                 StatefulWidget::render(
@@ -1,7 +1,7 @@
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Margin, Rect},
-    style::{Style, Stylize},
+    style::Style,
     text::{Line, Span},
     widgets::{
         Block, Cell, Padding, Paragraph, Row, Scrollbar, ScrollbarOrientation, ScrollbarState, StatefulWidget, Table, TableState, Widget, Wrap
@@ -15,9 +15,10 @@ use crate::{
             landing::{Cover, LandingSection},
             Pages,
         },
-        Loading,
+        view::{Columns, Rows},
+        Loading, TableArea,
     },
-    ui::{components::OpenInSpotify, format_duration, PaginationProgress, COLORS},
+    ui::{components::OpenInSpotify, format_duration, MarkedRows, PaginationProgress, ViewBar, THEME},
     Locked, Shared,
 };
 
@@ -32,6 +33,7 @@ pub fn render(
     state: &TableState,
     section: &LandingSection,
     cover: &mut Shared<Locked<Loading<Cover>>>,
+    table_area: &mut TableArea,
 ) {
     let title = format!("[Audiobook: {}]", audiobook.name);
     let (under, main) = render_landing(
@@ -50,7 +52,7 @@ pub fn render(
         }
     });
 
-    let info_highlight = if let LandingSection::Context = section { COLORS.highlight } else { Style::default() };
+    let info_highlight = if let LandingSection::Context = section { THEME.highlight } else { Style::default() };
 
     let info = [
         Paragraph::new(description).style(info_highlight).wrap(Wrap { trim: true })
@@ -93,7 +95,7 @@ pub fn render(
 
             Line::from("<No Audiobook Chapters>")
                 .centered()
-                .red()
+                .style(THEME.error)
                 .render(vert, buf);
         }
         Some(Loading::Some(data)) => {
@@ -105,15 +107,15 @@ pub fn render(
                 .map(|e| {
                     Row::new(vec![
                         // duration, chapter, name, finished
-                        Cell::from(format_duration(e.duration)).style(COLORS.duration),
-                        Cell::from(format!("Chapter {}", e.chapter_number + 1)).style(COLORS.chapter_number),
+                        Cell::from(format_duration(e.duration)).style(THEME.duration),
+                        Cell::from(format!("Chapter {}", e.chapter_number + 1)).style(THEME.chapter_number),
                         if e.resume_point.fully_played {
                             Cell::from(Line::from(vec![
-                                Span::from(e.name.clone()).style(COLORS.episode),
-                                Span::from(" ✓").style(COLORS.finished)
+                                Span::from(e.name.clone()).style(THEME.episode),
+                                Span::from(format!(" {}", THEME.glyphs.finished)).style(THEME.finished)
                             ]))
                         } else {
-                            Cell::from(e.name.clone()).style(COLORS.episode)
+                            Cell::from(e.name.clone()).style(THEME.episode)
                         }
                     ])
                 })
@@ -123,16 +125,27 @@ pub fn render(
                     Constraint::Length(8 + format!("{}", data.total).len() as u16),
                     Constraint::Fill(1),
                 ])
-                .block(block)
-                .highlight_style(if section.is_content() { COLORS.highlight } else { Style::default() });
+                .block(block.clone())
+                .highlight_style(if section.is_content() { THEME.highlight } else { Style::default() });
 
-            StatefulWidget::render(table_chapters, main, buf, &mut state.clone());
+            let mut state = state.clone();
+            StatefulWidget::render(table_chapters, main, buf, &mut state);
+            *table_area = TableArea::new(block.inner(main), &state);
 
             PaginationProgress {
                 current: data.page(),
                 total: data.max_page(),
             }
             .render(main, buf);
+            ViewBar(&pages.view.lock().unwrap()).render(main, buf);
+            let uris = data.rows().iter().map(Columns::uri).collect::<Vec<_>>();
+            MarkedRows {
+                view: &pages.view.lock().unwrap(),
+                uris: &uris,
+                area: table_area,
+                selected: state.selected(),
+            }
+            .render(main, buf);
 
             if scrollable {
                 let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight);
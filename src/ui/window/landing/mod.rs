@@ -6,7 +6,7 @@ use ratatui::{
 use ratatui_image::Image;
 
 use crate::{
-    state::{window::landing::{Cover, Landing}, Loading}, ui::{components::OpenInSpotify, PaginationProgress, COLORS}, Locked, Shared
+    state::{window::landing::{Cover, Landing}, Loading}, ui::{components::OpenInSpotify, THEME}, Locked, Shared
 };
 
 mod artist;
@@ -20,26 +20,39 @@ lazy_static::lazy_static! {
     pub static ref HTML_TAG: regex::Regex = regex::Regex::new("</?[abis][^>]*>").unwrap();
 }
 
+/// Decode the html character references and strip the formatting tags spotify puts in playlist
+/// descriptions
+pub fn decode_description(description: &str) -> String {
+    let description = HTML_UNICODE.replace_all(description, |captures: &regex::Captures| {
+        match captures.name("decimal") {
+            Some(decimal) => std::char::from_u32(decimal.as_str().parse::<u32>().unwrap()).unwrap().to_string(),
+            None => std::char::from_u32(u32::from_str_radix(captures.name("hex").unwrap().as_str(), 16).unwrap()).unwrap().to_string(),
+        }
+    });
+    HTML_TAG.replace_all(&description, "").to_string()
+}
+
 impl Widget for &mut Landing {
 fn render(self, area: Rect, buf: &mut Buffer) {
+        let cut = self.cut();
         match self {
             Landing::None => {},
-            Landing::Playlist{ pages, state, playlist, cover, section } => {
-                playlist::render(area, buf, playlist, pages, state, section, cover);
+            Landing::Playlist{ pages, state, playlist, cover, section, area: table_area, .. } => {
+                playlist::render(area, buf, playlist, pages, state, section, cut, cover, table_area);
             },
-            Landing::Album{ pages, state, album, cover, section } => {
-                album::render(area, buf, album, pages, state, section, cover);
+            Landing::Album{ pages, state, album, cover, section, area: table_area } => {
+                album::render(area, buf, album, pages, state, section, cover, table_area);
             },
-            Landing::Show{ pages, state, show, cover, section } => {
-                show::render(area, buf, show, pages, state, section, cover);
+            Landing::Show{ pages, state, show, cover, section, area: table_area } => {
+                show::render(area, buf, show, pages, state, section, cover, table_area);
             },
-            Landing::Audiobook{ pages, state, audiobook, cover, section } => {
-                audiobook::render(area, buf, audiobook, pages, state, section, cover);
+            Landing::Audiobook{ pages, state, audiobook, cover, section, area: table_area } => {
+                audiobook::render(area, buf, audiobook, pages, state, section, cover, table_area);
             },
-            Landing::Artist { top_tracks, state, section, albums, artist, cover, landing_section } => {
+            Landing::Artist { top_tracks, state, section, albums, artist, cover, landing_section, tracks_area, albums_area } => {
                 let top_tracks = top_tracks.lock().unwrap();
                 let artist = &*artist.lock().unwrap();
-                artist::render(area, buf, artist, top_tracks.as_slice(), albums, state, section, landing_section, cover);
+                artist::render(area, buf, artist, top_tracks.as_slice(), albums, state, section, landing_section, cover, tracks_area, albums_area);
             }
         }
     }
@@ -57,6 +70,7 @@ fn render_landing(
 {
     let block = Block::bordered()
         .border_set(border::ROUNDED)
+        .border_style(THEME.border)
         .padding(Padding::symmetric(1, 1))
         .title(
             Title::from(title)
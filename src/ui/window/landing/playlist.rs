@@ -13,13 +13,13 @@ use crate::{
     state::{
         window::{
             landing::{Cover, LandingSection}, MappedPages
-        }, wrappers::Saved, Loading
+        }, view::{Columns, Rows}, wrappers::Saved, Loading, TableArea
     },
-    ui::{format_episode_saved, format_track_saved, PaginationProgress, COLORS},
+    ui::{format_episode_saved, format_track_saved, MarkedRows, PaginationProgress, ViewBar, THEME},
     Locked, Shared,
 };
 
-use super::{render_landing, HTML_TAG, HTML_UNICODE};
+use super::{decode_description, render_landing};
 
 #[allow(clippy::too_many_arguments)]
 pub fn render(
@@ -29,7 +29,9 @@ pub fn render(
     pages: &MappedPages<Vec<Saved<PlaylistItemInfo>>, PlaylistItems, PlaylistItems>,
     state: &TableState,
     section: &LandingSection,
+    cut: Option<(usize, usize)>,
     cover: &mut Shared<Locked<Loading<Cover>>>,
+    table_area: &mut TableArea,
 ) {
     let title = format!("[Playlist: {}]", playlist.name);
     let (under, main) = render_landing(
@@ -39,16 +41,9 @@ pub fn render(
         cover.clone(),
     );
     
-    let description = playlist.description.clone().unwrap_or_default();
-    let description = HTML_UNICODE.replace_all(&description, |captures: &regex::Captures| {
-        match captures.name("decimal") {
-            Some(decimal) => std::char::from_u32(decimal.as_str().parse::<u32>().unwrap()).unwrap().to_string(),
-            None => std::char::from_u32(u32::from_str_radix(captures.name("hex").unwrap().as_str(), 16).unwrap()).unwrap().to_string(),
-        }
-    }).to_string();
-    let description = HTML_TAG.replace_all(description.as_str(), "").to_string();
+    let description = decode_description(playlist.description.as_deref().unwrap_or_default());
 
-    let info_highlight = if let LandingSection::Context = section { COLORS.highlight } else { Style::default() };
+    let info_highlight = if let LandingSection::Context = section { THEME.highlight } else { Style::default() };
 
     let info = [
         if under.height <= 3 {
@@ -58,8 +53,8 @@ pub fn render(
         },
         Paragraph::new(playlist.owner.name.clone().unwrap_or(playlist.owner.id.clone())).style(info_highlight).bold(),
         Paragraph::new(match playlist.public.unwrap_or_default() {
-            true => Span::from("Public").style(info_highlight).cyan(),
-            false => Span::from("Private").style(info_highlight).magenta(),
+            true => Span::from("Public").style(info_highlight.patch(THEME.public)),
+            false => Span::from("Private").style(info_highlight.patch(THEME.private)),
         }),
     ];
 
@@ -99,7 +94,7 @@ pub fn render(
 
             Line::from("<No Playlist Items>")
                 .centered()
-                .red()
+                .style(THEME.error)
                 .render(vert, buf);
         }
         Some(Loading::Some(data)) => {
@@ -109,12 +104,20 @@ pub fn render(
 
             let table_albums = data
                 .iter()
-                .map(|a| match &a.as_ref().item {
-                    Item::Track(track) => format_track_saved(track, a.saved),
-                    Item::Episode(episode) => format_episode_saved(episode, a.saved),
+                .enumerate()
+                .map(|(i, a)| {
+                    let row = match &a.as_ref().item {
+                        Item::Track(track) => format_track_saved(track, a.saved()),
+                        Item::Episode(episode) => format_episode_saved(episode, a.saved()),
+                    };
+                    // Items marked to be moved are dimmed until they are pasted
+                    match cut {
+                        Some((start, end)) if (start..=end).contains(&i) => row.style(Style::new().dim().italic()),
+                        _ => row,
+                    }
                 })
                 .collect::<Table>()
-                .block(block)
+                .block(block.clone())
                 .widths([
                     Constraint::Length(2),
                     Constraint::Length(8),
@@ -122,15 +125,26 @@ pub fn render(
                     Constraint::Fill(1),
                     Constraint::Fill(2),
                 ])
-                .highlight_style(if section.is_content() { COLORS.highlight } else { Style::default() });
+                .highlight_style(if section.is_content() { THEME.highlight } else { Style::default() });
 
-            StatefulWidget::render(table_albums, main, buf, &mut state.clone());
+            let mut state = state.clone();
+            StatefulWidget::render(table_albums, main, buf, &mut state);
+            *table_area = TableArea::new(block.inner(main), &state);
 
             PaginationProgress {
                 current: page.page,
                 total: page.max_page,
             }
             .render(main, buf);
+            ViewBar(&pages.view.lock().unwrap()).render(main, buf);
+            let uris = data.rows().iter().map(Columns::uri).collect::<Vec<_>>();
+            MarkedRows {
+                view: &pages.view.lock().unwrap(),
+                uris: &uris,
+                area: table_area,
+                selected: state.selected(),
+            }
+            .render(main, buf);
 
             if scrollable {
                 let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight);
@@ -13,9 +13,9 @@ use crate::{
     state::{
         window::{
             landing::{Cover, LandingSection}, MappedPages, Pages
-        }, wrappers::Saved, Loading
+        }, view::{Columns, Rows}, wrappers::Saved, Loading, TableArea
     },
-    ui::{format_duration, PaginationProgress, COLORS},
+    ui::{format_duration, MarkedRows, PaginationProgress, ViewBar, THEME},
     Locked, Shared,
 };
 
@@ -30,6 +30,7 @@ pub fn render(
     state: &TableState,
     section: &LandingSection,
     cover: &mut Shared<Locked<Loading<Cover>>>,
+    table_area: &mut TableArea,
 ) {
     let title = format!("[Show: {}]", show.name);
     let (under, main) = render_landing(
@@ -47,7 +48,7 @@ pub fn render(
         }
     });
 
-    let info_highlight = if let LandingSection::Context = section { COLORS.highlight } else { Style::default() };
+    let info_highlight = if let LandingSection::Context = section { THEME.highlight } else { Style::default() };
 
     let info = [
         if under.height <= 2 {
@@ -95,7 +96,7 @@ pub fn render(
 
             Line::from("<No Show Items>")
                 .centered()
-                .red()
+                .style(THEME.error)
                 .render(vert, buf);
         }
         Some(Loading::Some(data)) => {
@@ -107,14 +108,14 @@ pub fn render(
                 .iter()
                 .map(|e| {
                     Row::new(vec![
-                        Cell::from(if e.saved { "♥" } else { "" }).style(COLORS.like),
-                        Cell::from(format_duration(e.as_ref().duration)).style(COLORS.duration),
+                        Cell::from(if e.saved() { THEME.glyphs.like.as_str() } else { "" }).style(THEME.like),
+                        Cell::from(format_duration(e.as_ref().duration)).style(THEME.duration),
                         if e.as_ref().resume_point.fully_played {
-                            Cell::from("✓").style(COLORS.finished)
+                            Cell::from(THEME.glyphs.finished.as_str()).style(THEME.finished)
                         } else {
                             Cell::default()
                         },
-                        Cell::from(e.as_ref().name.clone()).style(COLORS.episode),
+                        Cell::from(e.as_ref().name.clone()).style(THEME.episode),
                     ])
                 })
                 .collect::<Table>()
@@ -124,16 +125,27 @@ pub fn render(
                     Constraint::Length(1),
                     Constraint::Fill(2),
                 ])
-                .block(block)
-                .highlight_style(if section.is_content() { COLORS.highlight } else { Style::default() });
+                .block(block.clone())
+                .highlight_style(if section.is_content() { THEME.highlight } else { Style::default() });
 
-            StatefulWidget::render(table_episodes, main, buf, &mut state.clone());
+            let mut state = state.clone();
+            StatefulWidget::render(table_episodes, main, buf, &mut state);
+            *table_area = TableArea::new(block.inner(main), &state);
 
             PaginationProgress {
                 current: page.page,
                 total: page.max_page,
             }
             .render(main, buf);
+            ViewBar(&pages.view.lock().unwrap()).render(main, buf);
+            let uris = data.rows().iter().map(Columns::uri).collect::<Vec<_>>();
+            MarkedRows {
+                view: &pages.view.lock().unwrap(),
+                uris: &uris,
+                area: table_area,
+                selected: state.selected(),
+            }
+            .render(main, buf);
 
             if scrollable {
                 let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight);
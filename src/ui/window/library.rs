@@ -1,10 +1,10 @@
-use crate::{state::{window::library::{FromSpotify, LibraryState, LibraryTab}, Loading}, ui::COLORS};
+use crate::{state::{window::library::{FromSpotify, LibraryState, LibraryTab}, Loading, TableArea}, ui::{tab_areas, THEME}};
 use strum::{EnumCount, IntoEnumIterator};
 use tupy::api::response::{PagedPlaylists, FollowedArtists, SavedAlbums, SavedShows, SavedAudiobooks};
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Constraint, Layout, Rect},
-    style::{Color, Modifier, Style, Stylize},
+    style::{Color, Modifier, Style},
     symbols::{self, border},
     text::{Line, Span},
     widgets::{
@@ -14,7 +14,7 @@ use ratatui::{
     },
 };
 
-use crate::ui::PaginationProgress;
+use crate::ui::{MarkedRows, PaginationProgress, ViewBar};
 
 impl FromSpotify {
     fn render(self, area: Rect, buf: &mut Buffer, selected: bool) {
@@ -41,7 +41,7 @@ impl FromSpotify {
                 Line::default(),
                 Line::default(),
                 Line::default(),
-                Line::from("🎵").centered().red(),
+                Line::from("🎵").centered().style(THEME.like),
                 Line::default(),
                 Line::default(),
                 Line::default(),
@@ -50,7 +50,7 @@ impl FromSpotify {
                 Line::default(),
                 Line::default(),
                 Line::default(),
-                Line::from("🎙️").centered().green(),
+                Line::from("🎙️").centered().style(THEME.episode),
                 Line::default(),
                 Line::default(),
                 Line::default(),
@@ -99,7 +99,7 @@ impl<'a> Widget for Art<'a> {
             .split(vert[0])[1];
 
         let style = if self.selected {
-            COLORS.highlight
+            THEME.highlight
         } else {
             Style::default()
         };
@@ -124,7 +124,7 @@ impl<'a> Widget for Art<'a> {
     }
 }
 
-impl Widget for &LibraryState {
+impl Widget for &mut LibraryState {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let block = Block::bordered()
             .borders(Borders::all())
@@ -133,7 +133,8 @@ impl Widget for &LibraryState {
                     .alignment(Alignment::Center)
                     .position(Position::Bottom),
             )
-            .border_set(border::ROUNDED);
+            .border_set(border::ROUNDED)
+            .border_style(THEME.border);
 
         (&block).render(area, buf);
 
@@ -148,16 +149,19 @@ impl Widget for &LibraryState {
         FromSpotify::iter().enumerate().for_each(|(i, f)| {
             f.render(from_spotify[i], buf, self.selection.is_spotify_playlist() && self.selected_spotify_playlist == f);
         });
+        self.tile_areas = from_spotify.to_vec();
+        self.tab_areas = tab_areas(layout[1], LibraryTab::iter().map(|t| t.title()));
 
         Tabs::new(LibraryTab::iter().map(|t| Line::from(t.title()).centered()))
-            .highlight_style(COLORS.highlight)
+            .highlight_style(THEME.highlight)
             .padding(" ", " ")
             //.block(Block::bordered().borders(Borders::TOP | Borders::BOTTOM))
             .select(self.selected_tab as usize)
             .divider(symbols::DOT)
             .render(layout[1], buf);
 
-        match self.selected_tab {
+        let mut result_state = self.result_state.clone();
+        let table = match self.selected_tab {
             LibraryTab::Playlists => {
                 let results = self.playlists.items.lock().unwrap();
                 let items = results.as_ref().map(|a| a.as_ref());
@@ -174,7 +178,7 @@ impl Widget for &LibraryState {
                 }
                 unwrap_render_results(
                     items,
-                    self.result_state.clone(),
+                    &mut result_state,
                     layout[2],
                     buf
                 )
@@ -195,7 +199,7 @@ impl Widget for &LibraryState {
                 }
                 unwrap_render_results(
                     items,
-                    self.result_state.clone(),
+                    &mut result_state,
                     layout[2],
                     buf
                 )
@@ -216,7 +220,7 @@ impl Widget for &LibraryState {
                 }
                 unwrap_render_results(
                     items,
-                    self.result_state.clone(),
+                    &mut result_state,
                     layout[2],
                     buf
                 )
@@ -237,7 +241,7 @@ impl Widget for &LibraryState {
                 }
                 unwrap_render_results(
                     items,
-                    self.result_state.clone(),
+                    &mut result_state,
                     layout[2],
                     buf
                 )
@@ -258,12 +262,21 @@ impl Widget for &LibraryState {
                 }
                 unwrap_render_results(
                     items,
-                    self.result_state.clone(),
+                    &mut result_state,
                     layout[2],
                     buf
                 )
             },
+        };
+        ViewBar(&self.table().view()).render(layout[2], buf);
+        self.result_area = table.map(|t| TableArea::new(t, &result_state)).unwrap_or_default();
+        MarkedRows {
+            view: &self.table().view(),
+            uris: &self.table().uris(),
+            area: &self.result_area,
+            selected: result_state.selected(),
         }
+        .render(layout[2], buf);
     }
 }
 
@@ -271,7 +284,8 @@ pub trait IntoWidgetWrapper<T: StatefulWidget> {
     fn into_widget_wrapper(&self) -> T;
 }
 
-fn unwrap_render_results<'a, W: StatefulWidget, T: IntoWidgetWrapper<W>>(loading: Option<Loading<T>>, mut state: W::State, area: Rect, buf: &mut Buffer)
+/// Render the results, returning the area the table was rendered to if there were any
+fn unwrap_render_results<W: StatefulWidget, T: IntoWidgetWrapper<W>>(loading: Option<Loading<T>>, state: &mut W::State, area: Rect, buf: &mut Buffer) -> Option<Rect>
 {
     match loading {
         Some(Loading::Loading) => {
@@ -281,6 +295,7 @@ fn unwrap_render_results<'a, W: StatefulWidget, T: IntoWidgetWrapper<W>>(loading
             Line::from("Loading...")
                 .centered()
                 .render(vert[1], buf);
+            None
         },
         None | Some(Loading::None) => {
             let vert = Layout::vertical([Constraint::Fill(1), Constraint::Length(1), Constraint::Fill(1)])
@@ -288,14 +303,16 @@ fn unwrap_render_results<'a, W: StatefulWidget, T: IntoWidgetWrapper<W>>(loading
 
             Line::from("<No Results>")
                 .centered()
-                .red()
+                .style(THEME.error)
                 .render(vert[1], buf);
+            None
         },
         Some(Loading::Some(items)) => {
             let block = Block::default()
                 .padding(Padding::horizontal(1));
             let area = block.inner(area);
-            StatefulWidget::render(items.into_widget_wrapper(), area, buf, &mut state)
+            StatefulWidget::render(items.into_widget_wrapper(), area, buf, state);
+            Some(area)
         },
     }
 }
@@ -347,12 +364,12 @@ impl<'a> StatefulWidget for Playlists<'a> {
             Row::new(vec![
                 Line::from(p.name.clone()),
                 Line::from(p.owner.name.clone().unwrap_or(p.owner.id.clone())).right_aligned(),
-                Line::from(if p.public.unwrap_or(false) { "Public".cyan() } else { "Private".red() })
+                Line::from(if p.public.unwrap_or(false) { Span::styled("Public", THEME.public) } else { Span::styled("Private", THEME.private) })
             ])
         })
             .collect::<Table>()
             .column_spacing(1)
-            .highlight_style(COLORS.highlight);
+            .highlight_style(THEME.highlight);
 
         StatefulWidget::render(table, area, buf, state);
     }
@@ -369,7 +386,7 @@ impl<'a> StatefulWidget for Artists<'a> {
         })
             .collect::<Table>()
             .column_spacing(1)
-            .highlight_style(COLORS.highlight);
+            .highlight_style(THEME.highlight);
 
         StatefulWidget::render(table, area, buf, state);
     }
@@ -387,7 +404,7 @@ impl<'a> StatefulWidget for Albums<'a> {
         })
             .collect::<Table>()
             .column_spacing(1)
-            .highlight_style(COLORS.highlight);
+            .highlight_style(THEME.highlight);
 
         StatefulWidget::render(table, area, buf, state);
     }
@@ -405,7 +422,7 @@ impl<'a> StatefulWidget for Shows<'a> {
         })
             .collect::<Table>()
             .column_spacing(1)
-            .highlight_style(COLORS.highlight);
+            .highlight_style(THEME.highlight);
 
         StatefulWidget::render(table, area, buf, state);
     }
@@ -424,7 +441,7 @@ impl<'a> StatefulWidget for Audiobooks<'a> {
         })
             .collect::<Table>()
             .column_spacing(1)
-            .highlight_style(COLORS.highlight);
+            .highlight_style(THEME.highlight);
 
         StatefulWidget::render(table, area, buf, state);
     }
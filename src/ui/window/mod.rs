@@ -1,3 +1,6 @@
 pub mod library;
 pub mod queue;
 pub mod landing;
+pub mod top;
+pub mod history;
+pub mod browse;
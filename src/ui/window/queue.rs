@@ -6,9 +6,9 @@ use ratatui::{
 };
 use tupy::api::response::Item;
 
-use crate::{state::{window::queue::QueueState, Loading}, ui::COLORS};
+use crate::{state::{local_queue, view::Columns, window::queue::QueueState, Loading, TableArea}, ui::THEME};
 
-use crate::ui::{format_episode_saved, format_track_saved};
+use crate::ui::{format_episode_saved, format_track_saved, MarkedRows, ViewBar};
 
 impl StatefulWidget for &mut QueueState {
     type State = Style;
@@ -19,13 +19,17 @@ impl StatefulWidget for &mut QueueState {
         buf: &mut ratatui::prelude::Buffer,
         state: &mut Self::State,
     ) {
-        let title = Title::from("[Queue]").alignment(Alignment::Center).position(Position::Bottom);
-        match self.queue.as_ref() {
+        let title = if local_queue::is_active() { "[Local Queue]" } else { "[Queue]" };
+        let title = Title::from(title).alignment(Alignment::Center).position(Position::Bottom);
+        let queue = self.queue.clone();
+        let queue = queue.lock().unwrap();
+        match queue.as_ref() {
             Loading::Loading => {
                 let block = Block::bordered()
                     .padding(Padding::new(0, 0, area.height / 2, 0))
                     .title(title)
-                    .border_set(border::ROUNDED);
+                    .border_set(border::ROUNDED)
+                    .border_style(THEME.border);
                 Paragraph::new("Loading Queue...")
                     .block(block)
                     .style(*state)
@@ -36,7 +40,8 @@ impl StatefulWidget for &mut QueueState {
                 let block = Block::bordered()
                     .padding(Padding::new(0, 0, area.height / 2, 0))
                     .title(title)
-                    .border_set(border::ROUNDED);
+                    .border_set(border::ROUNDED)
+                    .border_style(THEME.border);
                 Paragraph::new("<No Queue>")
                     .block(block)
                     .style(*state)
@@ -47,7 +52,8 @@ impl StatefulWidget for &mut QueueState {
                 let block = Block::bordered()
                     .padding(Padding::symmetric(1, 0))
                     .title(title)
-                    .border_set(border::ROUNDED);
+                    .border_set(border::ROUNDED)
+                    .border_style(THEME.border);
                 
                 //let table = text
                 //    .split("\n")
@@ -66,13 +72,13 @@ impl StatefulWidget for &mut QueueState {
                     .iter()
                     .map(|item|  match item.as_ref() {
                         // TODO: Format each line for specific item type
-                        Item::Track(t) => format_track_saved(&t, item.saved),
-                        Item::Episode(e) => format_episode_saved(&e, item.saved),
+                        Item::Track(t) => format_track_saved(t, item.saved()),
+                        Item::Episode(e) => format_episode_saved(e, item.saved()),
                     })
                     .collect::<Table>()
-                    .block(block)
+                    .block(block.clone())
                     .style(*state)
-                    .highlight_style(COLORS.highlight)
+                    .highlight_style(THEME.highlight)
                     .widths([
                         Constraint::Length(3),
                         Constraint::Length(8),
@@ -84,6 +90,16 @@ impl StatefulWidget for &mut QueueState {
                     .column_spacing(2);
 
                 StatefulWidget::render(table, area, buf, &mut self.state);
+                self.area = TableArea::new(block.inner(area), &self.state);
+                ViewBar(&self.view.lock().unwrap()).render(area, buf);
+                let uris = q.items.iter().map(Columns::uri).collect::<Vec<_>>();
+                MarkedRows {
+                    view: &self.view.lock().unwrap(),
+                    uris: &uris,
+                    area: &self.area,
+                    selected: self.state.selected(),
+                }
+                .render(area, buf);
             }
         }
     }
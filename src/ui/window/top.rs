@@ -0,0 +1,131 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Layout, Rect},
+    symbols::{self, border},
+    text::Line,
+    widgets::{
+        block::{Position, Title},
+        Block, Borders, Cell, Padding, Row, StatefulWidget, Table, TableState, Tabs, Widget,
+    },
+};
+use strum::IntoEnumIterator;
+use tupy::api::response::{Artist, Track};
+
+use crate::{
+    state::{
+        window::{top::{TopPages, TopState, TopTab}, Page},
+        wrappers::Saved,
+        Loading,
+    },
+    ui::{format_track_saved, PaginationProgress, THEME},
+};
+
+impl Widget for &TopState {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .borders(Borders::all())
+            .title(
+                Title::from("[Top]")
+                    .alignment(Alignment::Center)
+                    .position(Position::Bottom),
+            )
+            .border_set(border::ROUNDED)
+            .border_style(THEME.border);
+
+        (&block).render(area, buf);
+
+        let layout = Layout::vertical([Constraint::Length(2), Constraint::Fill(1)])
+            .split(block.inner(area));
+
+        Tabs::new(TopTab::iter().map(|t| Line::from(t.title()).centered()))
+            .highlight_style(THEME.highlight)
+            .padding(" ", " ")
+            .select(self.selected_tab as usize)
+            .divider(symbols::DOT)
+            .render(layout[0], buf);
+
+        let area = Block::default().padding(Padding::horizontal(1)).inner(layout[1]);
+        match self.pages() {
+            TopPages::Tracks(pages) => match pages.items.lock().unwrap().as_ref() {
+                Some(Loading::Some(items)) => {
+                    render_tracks(items, &pages.page.lock().unwrap(), self.state.clone(), area, buf)
+                }
+                Some(Loading::Loading) => render_message("Loading...", false, area, buf),
+                None | Some(Loading::None) => render_message("<No Top Tracks>", true, area, buf),
+            },
+            TopPages::Artists(pages) => match pages.items.lock().unwrap().as_ref() {
+                Some(Loading::Some(items)) => {
+                    render_artists(items, &pages.page.lock().unwrap(), self.state.clone(), area, buf)
+                }
+                Some(Loading::Loading) => render_message("Loading...", false, area, buf),
+                None | Some(Loading::None) => render_message("<No Top Artists>", true, area, buf),
+            },
+        }
+    }
+}
+
+pub(super) fn render_message(message: &'static str, error: bool, area: Rect, buf: &mut Buffer) {
+    let vert = Layout::vertical([Constraint::Fill(1), Constraint::Length(1), Constraint::Fill(1)])
+        .split(area);
+
+    let line = Line::from(message).centered();
+    if error {
+        line.style(THEME.error).render(vert[1], buf);
+    } else {
+        line.render(vert[1], buf);
+    }
+}
+
+fn render_pagination(page: &Page, area: Rect, buf: &mut Buffer) {
+    if page.max_page > 1 {
+        PaginationProgress {
+            current: page.page,
+            total: page.max_page,
+        }
+        .render(area, buf);
+    }
+}
+
+fn render_tracks(items: &[Saved<Track>], page: &Page, mut state: TableState, area: Rect, buf: &mut Buffer) {
+    let max_name = items.iter().map(|t| t.as_ref().name.len()).max().unwrap_or(0);
+
+    let table = items
+        .iter()
+        .map(|t| format_track_saved(t.as_ref(), t.saved()))
+        .collect::<Table>()
+        .highlight_style(THEME.highlight)
+        .widths([
+            Constraint::Length(3),
+            Constraint::Length(8),
+            Constraint::Length(1),
+            Constraint::Max(max_name as u16),
+            Constraint::Fill(1),
+        ])
+        .column_spacing(2);
+
+    StatefulWidget::render(table, area, buf, &mut state);
+    render_pagination(page, area, buf);
+}
+
+fn render_artists(items: &[Saved<Artist>], page: &Page, mut state: TableState, area: Rect, buf: &mut Buffer) {
+    let table = items
+        .iter()
+        .map(|a| {
+            Row::new(vec![
+                Cell::from(if a.saved() { THEME.glyphs.like.as_str() } else { "" }).style(THEME.like),
+                Cell::from(a.as_ref().name.clone()),
+                Cell::from(Line::from(a.as_ref().genres.join(", ")).right_aligned()).style(THEME.artists),
+            ])
+        })
+        .collect::<Table>()
+        .highlight_style(THEME.highlight)
+        .widths([
+            Constraint::Length(3),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+        ])
+        .column_spacing(1);
+
+    StatefulWidget::render(table, area, buf, &mut state);
+    render_pagination(page, area, buf);
+}